@@ -0,0 +1,461 @@
+use crate::{
+    errors::CompilerError,
+    schemas::{Ast, BinOpKind, Conversion, Expr, ExprKind, IntWidth, Primitive, Span, Stmt, UnaryOpKind},
+};
+use std::collections::HashMap;
+
+const PROGRAM_HEADER: &str = "#include <stdbool.h>\n#include <stdio.h>\n\nint main(void) {\n";
+
+/// Emits a C99 program for a semantically checked program. The statements become the body of `main`
+/// and every `print` calls `printf`, so the program can be compiled with any standard C compiler.
+pub fn emit_c(ast: &Ast) -> Result<String, CompilerError> {
+    emit_c_with_int_width(ast, IntWidth::I64)
+}
+
+/// Like `emit_c`, but with ints of the given width: `long long` for 64 bit and `int32_t` for 32 bit.
+pub fn emit_c_with_int_width(ast: &Ast, int_width: IntWidth) -> Result<String, CompilerError> {
+    let mut generator = CGenerator {
+        int_width,
+        ..CGenerator::default()
+    };
+    for stmt in ast {
+        generator.emit_stmt(stmt)?;
+    }
+
+    let mut program = match int_width {
+        IntWidth::I32 => format!("#include <inttypes.h>\n{}", PROGRAM_HEADER),
+        IntWidth::I64 => PROGRAM_HEADER.to_string(),
+    };
+    for line in generator.lines {
+        program.push_str(&format!("{}\n", line));
+    }
+    program.push_str("    return 0;\n}\n");
+    Ok(program)
+}
+
+#[derive(Default)]
+struct CGenerator {
+    lines: Vec<String>,
+    /// Maps a variable to the C name of its (latest) declaration and its type.
+    variables: HashMap<String, (String, Primitive)>,
+    declarations: usize,
+    /// Number of blocks the current line is nested in, not counting the body of `main`.
+    depth: usize,
+    int_width: IntWidth,
+}
+
+impl CGenerator {
+    fn push_line(&mut self, line: String) {
+        self.lines.push(format!("{}{}", "    ".repeat(self.depth + 1), line));
+    }
+
+    fn int_type(&self) -> &'static str {
+        match self.int_width {
+            IntWidth::I32 => "int32_t",
+            IntWidth::I64 => "long long",
+        }
+    }
+
+    fn c_type(&self, primitive: &Primitive, span: &Span) -> Result<&'static str, CompilerError> {
+        match primitive {
+            Primitive::Int => Ok(self.int_type()),
+            Primitive::Float => Ok("double"),
+            Primitive::Bool => Ok("bool"),
+            primitive => Err(Self::unsupported_type(primitive, span)),
+        }
+    }
+
+    fn unsupported_type(primitive: &Primitive, span: &Span) -> CompilerError {
+        CompilerError::CodegenError {
+            message: format!("Values of type '{:?}' are not supported by the C backend.", primitive),
+            span: span.clone(),
+        }
+    }
+
+    fn variable(&self, name: &str, span: &Span) -> Result<(String, Primitive), CompilerError> {
+        self.variables.get(name).cloned().ok_or_else(|| CompilerError::NameError {
+            name: name.to_string(),
+            self_reference: false,
+            suggestion: None,
+            span: span.clone(),
+        })
+    }
+
+    fn convert(&self, value: String, from: &Primitive, to: &Primitive, span: &Span) -> Result<String, CompilerError> {
+        Ok(match Conversion::between(from, to, span)? {
+            Conversion::IntToFloat => format!("(double){}", value),
+            Conversion::FloatToInt => format!("({}){}", self.int_type(), value),
+            Conversion::Identity => value,
+        })
+    }
+
+    fn store(&mut self, name: &str, expr: &Expr, span: &Span) -> Result<(String, Primitive), CompilerError> {
+        let (c_name, primitive) = self.variable(name, span)?;
+        let (value, value_type) = self.emit_expr(expr)?;
+        let value = self.convert(value, &value_type, &primitive, span)?;
+        Ok((format!("{} = {}", c_name, value), primitive))
+    }
+
+    /// Runs `emit` with its own scope, declarations inside shadow outer variables only until it ends.
+    fn scoped(&mut self, emit: impl FnOnce(&mut Self) -> Result<(), CompilerError>) -> Result<(), CompilerError> {
+        let outer_variables = self.variables.clone();
+        emit(self)?;
+        self.variables = outer_variables;
+        Ok(())
+    }
+
+    fn emit_body(&mut self, body: &Ast) -> Result<(), CompilerError> {
+        self.depth += 1;
+        self.scoped(|generator| body.iter().try_for_each(|stmt| generator.emit_stmt(stmt)))?;
+        self.depth -= 1;
+        Ok(())
+    }
+
+    /// Emits a declaration, assignment or expression statement without its ';', as it is also used
+    /// for the initializer and step of a for loop.
+    fn emit_simple_stmt(&mut self, stmt: &Stmt) -> Result<String, CompilerError> {
+        match stmt {
+            Stmt::Declare {
+                dtype,
+                mutable,
+                name,
+                expr,
+                span,
+                ..
+            } => {
+                // The initializer is emitted first, it may still refer to a previous declaration of the name.
+                let (value, value_type) = self.emit_expr(expr)?;
+                let value = self.convert(value, &value_type, dtype, span)?;
+
+                // Every declaration gets its own C name, since C resolves the name in its own initializer
+                // to the new variable and doesn't allow declaring a name twice in a scope.
+                let c_name = format!("{}_{}", name, self.declarations);
+                self.declarations += 1;
+                let qualifier = if *mutable { "" } else { "const " };
+                let declaration = format!("{}{} {} = {}", qualifier, self.c_type(dtype, span)?, c_name, value);
+                self.variables.insert(name.clone(), (c_name, dtype.clone()));
+                Ok(declaration)
+            }
+            Stmt::MutAssign { name, expr, span, .. } => Ok(self.store(name, expr, span)?.0),
+            Stmt::Expr { expr, .. } => Ok(self.emit_expr(expr)?.0),
+            stmt => Err(CompilerError::CodegenError {
+                message: "Expected a declaration, assignment or expression.".to_string(),
+                span: stmt.span().clone(),
+            }),
+        }
+    }
+
+    fn emit_print(&mut self, function: &str, expr: &Expr, span: &Span) -> Result<(), CompilerError> {
+        let (value, primitive) = self.emit_expr(expr)?;
+        let arguments = match primitive {
+            Primitive::Int if self.int_width == IntWidth::I32 => format!("\"%\" PRId32 \"\\n\", {}", value),
+            Primitive::Int => format!("\"%lld\\n\", {}", value),
+            Primitive::Float => format!("\"%f\\n\", {}", value),
+            Primitive::Bool => format!("\"%s\\n\", {} ? \"true\" : \"false\"", value),
+            primitive => return Err(Self::unsupported_type(&primitive, span)),
+        };
+        self.push_line(format!("{}{});", function, arguments));
+        Ok(())
+    }
+
+    fn emit_stmt(&mut self, stmt: &Stmt) -> Result<(), CompilerError> {
+        match stmt {
+            Stmt::Declare { .. } | Stmt::MutAssign { .. } | Stmt::Expr { .. } => {
+                let line = self.emit_simple_stmt(stmt)?;
+                self.push_line(format!("{};", line));
+            }
+            Stmt::Print { expr, span, .. } => self.emit_print("printf(", expr, span)?,
+            Stmt::EPrint { expr, span, .. } => self.emit_print("fprintf(stderr, ", expr, span)?,
+            Stmt::Swap { left, right, span, .. } => {
+                let (left, primitive) = self.variable(left, span)?;
+                let (right, _) = self.variable(right, span)?;
+                let c_type = self.c_type(&primitive, span)?;
+                self.push_line(format!(
+                    "{{ {} swap = {}; {} = {}; {} = swap; }}",
+                    c_type, left, left, right, right
+                ));
+            }
+            Stmt::Block { body, .. } => {
+                self.push_line("{".to_string());
+                self.emit_body(body)?;
+                self.push_line("}".to_string());
+            }
+            Stmt::If {
+                cond,
+                then_body,
+                else_body,
+                ..
+            } => {
+                let (cond, _) = self.emit_expr(cond)?;
+                self.push_line(format!("if ({}) {{", cond));
+                self.emit_body(then_body)?;
+                if let Some(else_body) = else_body {
+                    self.push_line("} else {".to_string());
+                    self.emit_body(else_body)?;
+                }
+                self.push_line("}".to_string());
+            }
+            // The variables declared by the initializer are only visible inside the loop.
+            Stmt::For {
+                init, cond, step, body, ..
+            } => self.scoped(|generator| {
+                let init = generator.emit_simple_stmt(init)?;
+                let (cond, _) = generator.emit_expr(cond)?;
+                let step = generator.emit_simple_stmt(step)?;
+                generator.push_line(format!("for ({}; {}; {}) {{", init, cond, step));
+                generator.emit_body(body)?;
+                generator.push_line("}".to_string());
+                Ok(())
+            })?,
+            Stmt::FnDecl { span, .. } | Stmt::Return { span, .. } => {
+                return Err(CompilerError::CodegenError {
+                    message: "Functions are not supported by the C backend.".to_string(),
+                    span: span.clone(),
+                });
+            }
+        }
+        Ok(())
+    }
+
+    /// Emits an expression and returns it with its type. Operations are parenthesized, so their
+    /// precedence never depends on the surrounding C expression.
+    fn emit_expr(&mut self, expr: &Expr) -> Result<(String, Primitive), CompilerError> {
+        let span = &expr.span;
+        match &expr.node {
+            ExprKind::Literal { value, primitive } => Ok((self.emit_literal(value, primitive, false, span)?, primitive.clone())),
+            ExprKind::Identifier { name } => self.variable(name, span),
+            ExprKind::UnaryOp { op, expr } => {
+                // Negated literals are emitted as constants, `-9223372036854775808` has no positive counterpart.
+                if let (UnaryOpKind::Neg, ExprKind::Literal { value, primitive }) = (op, &expr.node) {
+                    return Ok((self.emit_literal(value, primitive, true, span)?, primitive.clone()));
+                }
+
+                let (value, primitive) = self.emit_expr(expr)?;
+                match (op, &primitive) {
+                    (UnaryOpKind::Neg, Primitive::Int | Primitive::Float) => Ok((format!("(-{})", value), primitive)),
+                    (UnaryOpKind::Not, Primitive::Bool) => Ok((format!("(!{})", value), primitive)),
+                    (UnaryOpKind::BitNot, Primitive::Int) => Ok((format!("(~{})", value), primitive)),
+                    (op, primitive) => Err(CompilerError::TypeUnaryOpError {
+                        op: op.clone(),
+                        operand: primitive.clone(),
+                        span: span.clone(),
+                    }),
+                }
+            }
+            ExprKind::BinOp { op, left, right } => {
+                let left = self.emit_expr(left)?;
+                let right = self.emit_expr(right)?;
+                self.emit_binop(op, left, right, span)
+            }
+            ExprKind::Assign { name, expr } => {
+                let (assignment, primitive) = self.store(name, expr, span)?;
+                Ok((format!("({})", assignment), primitive))
+            }
+            ExprKind::TypeOf { .. } => Err(Self::unsupported_type(&Primitive::Str, span)),
+            ExprKind::Call { name, .. } => Err(CompilerError::CodegenError {
+                message: format!("Calling '{}' is not supported by the C backend.", name),
+                span: span.clone(),
+            }),
+        }
+    }
+
+    fn emit_literal(&self, value: &str, primitive: &Primitive, negated: bool, span: &Span) -> Result<String, CompilerError> {
+        let sign = if negated { "-" } else { "" };
+        let out_of_range = || CompilerError::LiteralOutOfRange {
+            value: value.to_string(),
+            primitive: primitive.clone(),
+            span: span.clone(),
+        };
+        match primitive {
+            // Ints are reformatted, C would read a leading zero as an octal literal.
+            Primitive::Int => match format!("{}{}", sign, value).parse::<i64>() {
+                Ok(value) if !self.int_width.contains(value) => Err(out_of_range()),
+                // The smallest int has no literal in C either, its absolute value is out of range.
+                Ok(i64::MIN) => Ok(format!("({}LL - 1)", i64::MIN + 1)),
+                Ok(value) if self.int_width == IntWidth::I32 && value == i32::MIN as i64 => Ok(format!("(INT32_C({}) - 1)", i32::MIN + 1)),
+                Ok(value) if self.int_width == IntWidth::I32 => Ok(format!("INT32_C({})", value)),
+                Ok(value) => Ok(format!("{}LL", value)),
+                Err(_) => Err(out_of_range()),
+            },
+            Primitive::Float => match format!("{}{}", sign, value).parse::<f64>() {
+                Ok(value) if value.is_finite() => Ok(format!("{:?}", value)),
+                _ => Err(out_of_range()),
+            },
+            Primitive::Bool if !negated => Ok(value.to_string()),
+            primitive => Err(Self::unsupported_type(primitive, span)),
+        }
+    }
+
+    fn emit_binop(
+        &self,
+        op: &BinOpKind,
+        (left, left_type): (String, Primitive),
+        (right, right_type): (String, Primitive),
+        span: &Span,
+    ) -> Result<(String, Primitive), CompilerError> {
+        let both_int = left_type.is_integer() && right_type.is_integer();
+        let numeric = left_type.is_numeric() && right_type.is_numeric();
+        let both_bool = left_type.is_boolean() && right_type.is_boolean();
+
+        let (left, right, result_type) = match op {
+            BinOpKind::Pow => {
+                return Err(CompilerError::CodegenError {
+                    message: "The '^' operator is not supported by the C backend.".to_string(),
+                    span: span.clone(),
+                });
+            }
+            BinOpKind::Add
+            | BinOpKind::Sub
+            | BinOpKind::Mult
+            | BinOpKind::Mod
+            | BinOpKind::BitAnd
+            | BinOpKind::BitOr
+            | BinOpKind::Shl
+            | BinOpKind::Shr
+                if both_int =>
+            {
+                (left, right, Primitive::Int)
+            }
+            // C's `%` is only defined on integers, a float remainder needs `fmod` from the math library.
+            BinOpKind::Mod if numeric => {
+                return Err(CompilerError::CodegenError {
+                    message: "The '%' operator on floats is not supported by the C backend.".to_string(),
+                    span: span.clone(),
+                });
+            }
+            // Division always returns float, also for int operands.
+            BinOpKind::Add | BinOpKind::Sub | BinOpKind::Mult | BinOpKind::Div if numeric => (
+                self.convert(left, &left_type, &Primitive::Float, span)?,
+                self.convert(right, &right_type, &Primitive::Float, span)?,
+                Primitive::Float,
+            ),
+            // C converts an int compared to a float to float itself.
+            BinOpKind::Gt | BinOpKind::Lt | BinOpKind::Ge | BinOpKind::Le | BinOpKind::Eq | BinOpKind::Ne
+                if numeric =>
+            {
+                (left, right, Primitive::Bool)
+            }
+            BinOpKind::Eq | BinOpKind::Ne | BinOpKind::And | BinOpKind::Or if both_bool => (left, right, Primitive::Bool),
+            op => {
+                return Err(CompilerError::TypeBinOpError {
+                    op: op.clone(),
+                    left: left_type,
+                    right: right_type,
+                    span: span.clone(),
+                });
+            }
+        };
+        Ok((format!("({} {} {})", left, op.symbol(), right), result_type))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::compile_str;
+
+    fn emit(input: &str) -> Result<String, CompilerError> {
+        emit_c(&compile_str(input)?)
+    }
+
+    #[test]
+    fn test_emit_arithmetic_program() {
+        let c = emit("int a = 2 + 3 * 4;\nmut int b = a - 1;\nb = -b;\nfloat c = a / 2;\nprint(b);\nprint(c * 1.5);\nprint(7 % 3);")
+            .unwrap();
+        assert_eq!(
+            c,
+            "#include <stdbool.h>\n\
+             #include <stdio.h>\n\
+             \n\
+             int main(void) {\n\
+             \x20   const long long a_0 = (2LL + (3LL * 4LL));\n\
+             \x20   long long b_1 = (a_0 - 1LL);\n\
+             \x20   b_1 = (-b_1);\n\
+             \x20   const double c_2 = ((double)a_0 / (double)2LL);\n\
+             \x20   printf(\"%lld\\n\", b_1);\n\
+             \x20   printf(\"%f\\n\", (c_2 * 1.5));\n\
+             \x20   printf(\"%lld\\n\", (7LL % 3LL));\n\
+             \x20   return 0;\n\
+             }\n"
+        );
+    }
+
+    #[test]
+    fn test_emit_control_flow_program() {
+        let c = emit(
+            "mut int sum = 0;\nfor (mut int i = 1; i <= 4; i += 1) { sum += i; }\n\
+             bool done = sum > 5 && !false;\nif (done) {\n  int sum = 1;\n  eprint(sum);\n} else {\n  print(done);\n}",
+        )
+        .unwrap();
+        assert_eq!(
+            c,
+            "#include <stdbool.h>\n\
+             #include <stdio.h>\n\
+             \n\
+             int main(void) {\n\
+             \x20   long long sum_0 = 0LL;\n\
+             \x20   for (long long i_1 = 1LL; (i_1 <= 4LL); i_1 = (i_1 + 1LL)) {\n\
+             \x20       sum_0 = (sum_0 + i_1);\n\
+             \x20   }\n\
+             \x20   const bool done_2 = ((sum_0 > 5LL) && (!false));\n\
+             \x20   if (done_2) {\n\
+             \x20       const long long sum_3 = 1LL;\n\
+             \x20       fprintf(stderr, \"%lld\\n\", sum_3);\n\
+             \x20   } else {\n\
+             \x20       printf(\"%s\\n\", done_2 ? \"true\" : \"false\");\n\
+             \x20   }\n\
+             \x20   return 0;\n\
+             }\n"
+        );
+    }
+
+    #[test]
+    fn test_emit_shadowing_and_swap() {
+        let c = emit("mut int a = 1;\nmut int b = 2;\n{\n  int a = a + 1;\n}\nswap(a, b);").unwrap();
+        assert!(c.contains("    {\n        const long long a_2 = (a_0 + 1LL);\n    }\n"));
+        assert!(c.contains("    { long long swap = a_0; a_0 = b_1; b_1 = swap; }\n"));
+    }
+
+    #[test]
+    fn test_emit_literals() {
+        let c = emit("int a = -9223372036854775808;\nint b = 007;\nfloat c = -1e300;\nbool d = true;").unwrap();
+        assert!(c.contains("const long long a_0 = (-9223372036854775807LL - 1);"));
+        assert!(c.contains("const long long b_1 = 7LL;"));
+        assert!(c.contains("const double c_2 = -1e300;"));
+        assert!(c.contains("const bool d_3 = true;"));
+
+        let c = emit("print(~0xF & 3 << 1 | 16 >> 2);").unwrap();
+        assert!(c.contains("printf(\"%lld\\n\", (((~15LL) & (3LL << 1LL)) | (16LL >> 2LL)));"));
+    }
+
+    #[test]
+    fn test_emit_int_width() {
+        let ast = compile_str("mut int a = 2 * 3;\na = -a;\nfloat b = a;\nint c = -2147483648;\nprint(a);\nprint(c + b);").unwrap();
+        let c = emit_c_with_int_width(&ast, IntWidth::I32).unwrap();
+        assert!(c.starts_with("#include <inttypes.h>\n#include <stdbool.h>\n"));
+        assert!(c.contains("    int32_t a_0 = (INT32_C(2) * INT32_C(3));\n"));
+        assert!(c.contains("    const int32_t c_2 = (INT32_C(-2147483647) - 1);\n"));
+        assert!(c.contains("    printf(\"%\" PRId32 \"\\n\", a_0);\n"));
+        assert!(!c.contains("long long"));
+
+        assert_eq!(emit_c_with_int_width(&ast, IntWidth::I64).unwrap(), emit_c(&ast).unwrap());
+    }
+
+    #[test]
+    fn test_emit_unsupported() {
+        let result = emit("print(2 ^ 3);");
+        assert!(matches!(
+            result,
+            Err(CompilerError::CodegenError { message, .. }) if message == "The '^' operator is not supported by the C backend."
+        ));
+
+        let result = emit("print(7.5 % 2);");
+        assert!(matches!(
+            result,
+            Err(CompilerError::CodegenError { message, .. }) if message == "The '%' operator on floats is not supported by the C backend."
+        ));
+
+        let result = emit("string s = \"hi\";");
+        assert!(matches!(result, Err(CompilerError::CodegenError { .. })));
+    }
+}
@@ -0,0 +1,463 @@
+use crate::{
+    errors::CompilerError,
+    schemas::{Ast, BinOpKind, Conversion, Expr, ExprKind, IntWidth, Primitive, Span, Stmt, UnaryOpKind},
+};
+use std::collections::HashMap;
+
+const MODULE_HEADER: &str = r#"@.fmt.int = private unnamed_addr constant [6 x i8] c"%lld\0A\00"
+@.fmt.float = private unnamed_addr constant [4 x i8] c"%f\0A\00"
+@.fmt.str = private unnamed_addr constant [4 x i8] c"%s\0A\00"
+@.str.true = private unnamed_addr constant [5 x i8] c"true\00"
+@.str.false = private unnamed_addr constant [6 x i8] c"false\00"
+
+declare i32 @printf(ptr, ...)
+"#;
+
+/// Emits a textual LLVM IR module for a semantically checked program. The statements become the body
+/// of `main` and every `print` calls `printf`, so the module can be run with `lli` or compiled with `llc`.
+pub fn emit_llvm_ir(ast: &Ast) -> Result<String, CompilerError> {
+    emit_llvm_ir_with_int_width(ast, IntWidth::I64)
+}
+
+/// Like `emit_llvm_ir`, but maps the `int` primitive to an integer of the given width, e.g. `i32` for WASM.
+pub fn emit_llvm_ir_with_int_width(ast: &Ast, int_width: IntWidth) -> Result<String, CompilerError> {
+    let mut generator = LlvmGenerator {
+        int_width,
+        ..Default::default()
+    };
+    for stmt in ast {
+        generator.emit_stmt(stmt)?;
+    }
+
+    let mut module = format!("{}\ndefine i32 @main() {{\nentry:\n", MODULE_HEADER);
+    for instruction in generator.instructions {
+        module.push_str(&format!("  {}\n", instruction));
+    }
+    module.push_str("  ret i32 0\n}\n");
+    Ok(module)
+}
+
+#[derive(Default)]
+struct LlvmGenerator {
+    instructions: Vec<String>,
+    /// Maps a variable to the pointer of its (latest) declaration and its type.
+    variables: HashMap<String, (String, Primitive)>,
+    declarations: usize,
+    temporaries: usize,
+    int_width: IntWidth,
+}
+
+impl LlvmGenerator {
+    fn int_type(&self) -> &'static str {
+        match self.int_width {
+            IntWidth::I32 => "i32",
+            IntWidth::I64 => "i64",
+        }
+    }
+
+    fn llvm_type(&self, primitive: &Primitive, span: &Span) -> Result<&'static str, CompilerError> {
+        match primitive {
+            Primitive::Int => Ok(self.int_type()),
+            Primitive::Float => Ok("double"),
+            Primitive::Bool => Ok("i1"),
+            primitive => Err(Self::unsupported_type(primitive, span)),
+        }
+    }
+
+    fn unsupported_type(primitive: &Primitive, span: &Span) -> CompilerError {
+        CompilerError::CodegenError {
+            message: format!("Values of type '{:?}' are not supported by the LLVM backend.", primitive),
+            span: span.clone(),
+        }
+    }
+
+    fn temporary(&mut self, instruction: String) -> String {
+        let name = format!("%t{}", self.temporaries);
+        self.temporaries += 1;
+        self.instructions.push(format!("{} = {}", name, instruction));
+        name
+    }
+
+    fn variable(&self, name: &str, span: &Span) -> Result<(String, Primitive), CompilerError> {
+        self.variables.get(name).cloned().ok_or_else(|| CompilerError::NameError {
+            name: name.to_string(),
+            self_reference: false,
+            suggestion: None,
+            span: span.clone(),
+        })
+    }
+
+    fn convert(&mut self, value: String, from: &Primitive, to: &Primitive, span: &Span) -> Result<String, CompilerError> {
+        Ok(match Conversion::between(from, to, span)? {
+            Conversion::IntToFloat => self.temporary(format!("sitofp {} {} to double", self.int_type(), value)),
+            Conversion::FloatToInt => self.temporary(format!("fptosi double {} to {}", value, self.int_type())),
+            Conversion::Identity => value,
+        })
+    }
+
+    fn store(&mut self, name: &str, expr: &Expr, span: &Span) -> Result<String, CompilerError> {
+        let (pointer, primitive) = self.variable(name, span)?;
+        let (value, value_type) = self.emit_expr(expr)?;
+        let value = self.convert(value, &value_type, &primitive, span)?;
+        self.instructions.push(format!(
+            "store {} {}, ptr {}",
+            self.llvm_type(&primitive, span)?,
+            value,
+            pointer
+        ));
+        Ok(value)
+    }
+
+    fn emit_stmt(&mut self, stmt: &Stmt) -> Result<(), CompilerError> {
+        match stmt {
+            Stmt::Declare {
+                dtype, name, expr, span, ..
+            } => {
+                // The initializer is emitted first, it may still refer to a previous declaration of the name.
+                let (value, value_type) = self.emit_expr(expr)?;
+                let value = self.convert(value, &value_type, dtype, span)?;
+
+                // Every declaration gets its own stack slot, since a variable may be declared again.
+                let llvm_type = self.llvm_type(dtype, span)?;
+                let pointer = format!("%{}.{}", name, self.declarations);
+                self.declarations += 1;
+                self.instructions.push(format!("{} = alloca {}", pointer, llvm_type));
+                self.instructions.push(format!("store {} {}, ptr {}", llvm_type, value, pointer));
+                self.variables.insert(name.clone(), (pointer, dtype.clone()));
+            }
+            Stmt::MutAssign { name, expr, span, .. } => {
+                self.store(name, expr, span)?;
+            }
+            Stmt::Print { expr, span, .. } => {
+                let (value, primitive) = self.emit_expr(expr)?;
+                let arguments = match primitive {
+                    // The format string expects a 64 bit integer, narrower ints are sign extended first.
+                    Primitive::Int if self.int_width == IntWidth::I32 => {
+                        let value = self.temporary(format!("sext i32 {} to i64", value));
+                        format!("ptr @.fmt.int, i64 {}", value)
+                    }
+                    Primitive::Int => format!("ptr @.fmt.int, i64 {}", value),
+                    Primitive::Float => format!("ptr @.fmt.float, double {}", value),
+                    Primitive::Bool => {
+                        let text = self.temporary(format!("select i1 {}, ptr @.str.true, ptr @.str.false", value));
+                        format!("ptr @.fmt.str, ptr {}", text)
+                    }
+                    primitive => return Err(Self::unsupported_type(&primitive, span)),
+                };
+                self.temporary(format!("call i32 (ptr, ...) @printf({})", arguments));
+            }
+            Stmt::Expr { expr, .. } => {
+                self.emit_expr(expr)?;
+            }
+            Stmt::Swap { left, right, span, .. } => {
+                let (left_pointer, primitive) = self.variable(left, span)?;
+                let (right_pointer, _) = self.variable(right, span)?;
+                let llvm_type = self.llvm_type(&primitive, span)?;
+
+                let left_value = self.temporary(format!("load {}, ptr {}", llvm_type, left_pointer));
+                let right_value = self.temporary(format!("load {}, ptr {}", llvm_type, right_pointer));
+                self.instructions.push(format!("store {} {}, ptr {}", llvm_type, right_value, left_pointer));
+                self.instructions.push(format!("store {} {}, ptr {}", llvm_type, left_value, right_pointer));
+            }
+            Stmt::EPrint { span, .. } => {
+                return Err(CompilerError::CodegenError {
+                    message: "The 'eprint' statement is not supported by the LLVM backend.".to_string(),
+                    span: span.clone(),
+                });
+            }
+            // Declarations inside the block shadow outer variables only until the block ends.
+            Stmt::Block { body, .. } => {
+                let outer_variables = self.variables.clone();
+                for stmt in body {
+                    self.emit_stmt(stmt)?;
+                }
+                self.variables = outer_variables;
+            }
+            Stmt::For { span, .. } => {
+                return Err(CompilerError::CodegenError {
+                    message: "The 'for' loop is not supported by the LLVM backend.".to_string(),
+                    span: span.clone(),
+                });
+            }
+            Stmt::If { span, .. } => {
+                return Err(CompilerError::CodegenError {
+                    message: "The 'if' statement is not supported by the LLVM backend.".to_string(),
+                    span: span.clone(),
+                });
+            }
+            Stmt::FnDecl { span, .. } | Stmt::Return { span, .. } => {
+                return Err(CompilerError::CodegenError {
+                    message: "Functions are not supported by the LLVM backend.".to_string(),
+                    span: span.clone(),
+                });
+            }
+        }
+        Ok(())
+    }
+
+    fn emit_expr(&mut self, expr: &Expr) -> Result<(String, Primitive), CompilerError> {
+        let span = &expr.span;
+        match &expr.node {
+            ExprKind::Literal { value, primitive } => Ok((Self::emit_literal(value, primitive, false, span)?, primitive.clone())),
+            ExprKind::Identifier { name } => {
+                let (pointer, primitive) = self.variable(name, span)?;
+                let llvm_type = self.llvm_type(&primitive, span)?;
+                Ok((self.temporary(format!("load {}, ptr {}", llvm_type, pointer)), primitive))
+            }
+            ExprKind::UnaryOp { op, expr } => {
+                // Negated literals are emitted as constants, `-9223372036854775808` has no positive counterpart.
+                if let (UnaryOpKind::Neg, ExprKind::Literal { value, primitive }) = (op, &expr.node) {
+                    return Ok((Self::emit_literal(value, primitive, true, span)?, primitive.clone()));
+                }
+
+                let (value, primitive) = self.emit_expr(expr)?;
+                let instruction = match (op, &primitive) {
+                    (UnaryOpKind::Neg, Primitive::Int) => format!("sub {} 0, {}", self.int_type(), value),
+                    (UnaryOpKind::Neg, Primitive::Float) => format!("fneg double {}", value),
+                    (UnaryOpKind::Not, Primitive::Bool) => format!("xor i1 {}, true", value),
+                    (UnaryOpKind::BitNot, Primitive::Int) => format!("xor {} {}, -1", self.int_type(), value),
+                    (op, primitive) => {
+                        return Err(CompilerError::TypeUnaryOpError {
+                            op: op.clone(),
+                            operand: primitive.clone(),
+                            span: span.clone(),
+                        });
+                    }
+                };
+                Ok((self.temporary(instruction), primitive))
+            }
+            ExprKind::BinOp { op, left, right } => {
+                let (left, left_type) = self.emit_expr(left)?;
+                let (right, right_type) = self.emit_expr(right)?;
+                self.emit_binop(op, (left, left_type), (right, right_type), span)
+            }
+            ExprKind::Assign { name, expr } => {
+                let value = self.store(name, expr, span)?;
+                Ok((value, self.variable(name, span)?.1))
+            }
+            ExprKind::TypeOf { .. } => Err(Self::unsupported_type(&Primitive::Str, span)),
+            ExprKind::Call { name, .. } => Err(CompilerError::CodegenError {
+                message: format!("Calling '{}' is not supported by the LLVM backend.", name),
+                span: span.clone(),
+            }),
+        }
+    }
+
+    fn emit_literal(value: &str, primitive: &Primitive, negated: bool, span: &Span) -> Result<String, CompilerError> {
+        let sign = if negated { "-" } else { "" };
+        match primitive {
+            Primitive::Int => Ok(format!("{}{}", sign, value)),
+            // Doubles are written as their bit pattern, LLVM rejects decimal constants which are not exact.
+            Primitive::Float => match format!("{}{}", sign, value).parse::<f64>() {
+                Ok(value) => Ok(format!("0x{:016X}", value.to_bits())),
+                Err(_) => Err(CompilerError::LiteralOutOfRange {
+                    value: value.to_string(),
+                    primitive: Primitive::Float,
+                    span: span.clone(),
+                }),
+            },
+            Primitive::Bool if !negated => Ok(value.to_string()),
+            primitive => Err(Self::unsupported_type(primitive, span)),
+        }
+    }
+
+    fn emit_binop(
+        &mut self,
+        op: &BinOpKind,
+        (left, left_type): (String, Primitive),
+        (right, right_type): (String, Primitive),
+        span: &Span,
+    ) -> Result<(String, Primitive), CompilerError> {
+        let both_int = left_type.is_integer() && right_type.is_integer();
+        let numeric = left_type.is_numeric() && right_type.is_numeric();
+
+        let (instruction, result_type) = match op {
+            BinOpKind::Pow => {
+                return Err(CompilerError::CodegenError {
+                    message: "The '^' operator is not supported by the LLVM backend.".to_string(),
+                    span: span.clone(),
+                });
+            }
+            BinOpKind::Add
+            | BinOpKind::Sub
+            | BinOpKind::Mult
+            | BinOpKind::Mod
+            | BinOpKind::BitAnd
+            | BinOpKind::BitOr
+            | BinOpKind::Shl
+            | BinOpKind::Shr
+                if both_int =>
+            {
+                let instruction = match op {
+                    BinOpKind::Add => "add",
+                    BinOpKind::Sub => "sub",
+                    BinOpKind::Mult => "mul",
+                    BinOpKind::BitAnd => "and",
+                    BinOpKind::BitOr => "or",
+                    BinOpKind::Shl => "shl",
+                    BinOpKind::Shr => "ashr",
+                    _ => "srem",
+                };
+                (format!("{} {} {}, {}", instruction, self.int_type(), left, right), Primitive::Int)
+            }
+            // Division always returns float, also for int operands.
+            BinOpKind::Add | BinOpKind::Sub | BinOpKind::Mult | BinOpKind::Div | BinOpKind::Mod if numeric => {
+                let left = self.convert(left, &left_type, &Primitive::Float, span)?;
+                let right = self.convert(right, &right_type, &Primitive::Float, span)?;
+                let instruction = match op {
+                    BinOpKind::Add => "fadd",
+                    BinOpKind::Sub => "fsub",
+                    BinOpKind::Mult => "fmul",
+                    // Like Rust's `%`, `frem` is the truncated remainder.
+                    BinOpKind::Mod => "frem",
+                    _ => "fdiv",
+                };
+                (format!("{} double {}, {}", instruction, left, right), Primitive::Float)
+            }
+            BinOpKind::Gt | BinOpKind::Lt | BinOpKind::Ge | BinOpKind::Le | BinOpKind::Eq | BinOpKind::Ne
+                if both_int =>
+            {
+                let condition = match op {
+                    BinOpKind::Gt => "sgt",
+                    BinOpKind::Lt => "slt",
+                    BinOpKind::Ge => "sge",
+                    BinOpKind::Le => "sle",
+                    BinOpKind::Eq => "eq",
+                    _ => "ne",
+                };
+                (format!("icmp {} {} {}, {}", condition, self.int_type(), left, right), Primitive::Bool)
+            }
+            BinOpKind::Gt | BinOpKind::Lt | BinOpKind::Ge | BinOpKind::Le | BinOpKind::Eq | BinOpKind::Ne
+                if numeric =>
+            {
+                let left = self.convert(left, &left_type, &Primitive::Float, span)?;
+                let right = self.convert(right, &right_type, &Primitive::Float, span)?;
+                let condition = match op {
+                    BinOpKind::Gt => "ogt",
+                    BinOpKind::Lt => "olt",
+                    BinOpKind::Ge => "oge",
+                    BinOpKind::Le => "ole",
+                    BinOpKind::Eq => "oeq",
+                    _ => "une",
+                };
+                (format!("fcmp {} double {}, {}", condition, left, right), Primitive::Bool)
+            }
+            BinOpKind::Eq | BinOpKind::Ne | BinOpKind::And | BinOpKind::Or
+                if left_type == Primitive::Bool && right_type == Primitive::Bool =>
+            {
+                let instruction = match op {
+                    BinOpKind::Eq => "icmp eq",
+                    BinOpKind::Ne => "icmp ne",
+                    BinOpKind::And => "and",
+                    _ => "or",
+                };
+                (format!("{} i1 {}, {}", instruction, left, right), Primitive::Bool)
+            }
+            op => {
+                return Err(CompilerError::TypeBinOpError {
+                    op: op.clone(),
+                    left: left_type,
+                    right: right_type,
+                    span: span.clone(),
+                });
+            }
+        };
+        Ok((self.temporary(instruction), result_type))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{compile_str, lexer::Lexer, parser::Parser};
+
+    fn emit(input: &str) -> Result<String, CompilerError> {
+        emit_llvm_ir(&compile_str(input)?)
+    }
+
+    #[test]
+    fn test_emit_int_program() {
+        let ir = emit("int a = 2 + 3 * 4;\nmut int b = a - 1;\nb = -b;\nprint(b);").unwrap();
+        assert!(ir.contains("define i32 @main() {"));
+        assert!(ir.contains("declare i32 @printf(ptr, ...)"));
+        assert!(ir.contains("%t0 = mul i64 3, 4"));
+        assert!(ir.contains("%t1 = add i64 2, %t0"));
+        assert!(ir.contains("%a.0 = alloca i64"));
+        assert!(ir.contains("store i64 %t1, ptr %a.0"));
+        assert!(ir.contains("sub i64 %t2, 1"));
+        assert!(ir.contains("sub i64 0, %t4"));
+        assert!(ir.contains("call i32 (ptr, ...) @printf(ptr @.fmt.int, i64 %t6)"));
+        assert!(ir.ends_with("  ret i32 0\n}\n"));
+
+        let ir = emit("print(7 % 3);").unwrap();
+        assert!(ir.contains("%t0 = srem i64 7, 3"));
+
+        let ir = emit("print(~7 & 3 << 1 | 16 >> 2);").unwrap();
+        assert!(ir.contains("%t0 = xor i64 7, -1"));
+        assert!(ir.contains("%t1 = shl i64 3, 1"));
+        assert!(ir.contains("%t2 = and i64 %t0, %t1"));
+        assert!(ir.contains("%t3 = ashr i64 16, 2"));
+        assert!(ir.contains("%t4 = or i64 %t2, %t3"));
+    }
+
+    #[test]
+    fn test_emit_float_program() {
+        let ir = emit("int a = 7;\nfloat b = a / 2;\nprint(b * 1.5);\nprint(b > 3);").unwrap();
+        assert!(ir.contains("%b.1 = alloca double"));
+        assert!(ir.contains("sitofp i64 %t0 to double"));
+        assert!(ir.contains("fdiv double %t1, %t2"));
+        assert!(ir.contains("fmul double %t4, 0x3FF8000000000000"));
+        assert!(ir.contains("fcmp ogt double"));
+        assert!(ir.contains("select i1 %t9, ptr @.str.true, ptr @.str.false"));
+
+        let ir = emit("float a = 7.5;\nprint(a % 2);").unwrap();
+        assert!(ir.contains("frem double %t0, %t1"), "{}", ir);
+    }
+
+    #[test]
+    fn test_emit_shadowing_uses_previous_slot() {
+        let ir = emit("int a = 1;\n{\n  int a = a + 1;\n  print(a);\n}").unwrap();
+        assert!(ir.contains("%t0 = load i64, ptr %a.0"));
+        assert!(ir.contains("store i64 %t1, ptr %a.1"));
+        assert!(ir.contains("%t2 = load i64, ptr %a.1"));
+    }
+
+    #[test]
+    fn test_emit_block_scope() {
+        let ir = emit("int a = 1;\n{\n  int a = 2;\n  print(a);\n}\nprint(a);").unwrap();
+        assert!(ir.contains("%t0 = load i64, ptr %a.1"));
+        assert!(ir.contains("%t2 = load i64, ptr %a.0"));
+    }
+
+    #[test]
+    fn test_emit_int_width() {
+        let mut lexer = Lexer::new("mut int a = 2 * 3;\na = -a;\nprint(a);\nprint(a / 2);");
+        lexer.tokenize().unwrap();
+        let mut parser = Parser::new(lexer.get_tokens().to_vec());
+        parser.parse().unwrap();
+        let ast = parser.into_tree();
+
+        let ir = emit_llvm_ir_with_int_width(&ast, IntWidth::I32).unwrap();
+        assert!(ir.contains("%t0 = mul i32 2, 3"));
+        assert!(ir.contains("%a.0 = alloca i32"));
+        assert!(ir.contains("sub i32 0, %t1"));
+        assert!(ir.contains("%t4 = sext i32 %t3 to i64"));
+        assert!(ir.contains("@printf(ptr @.fmt.int, i64 %t4)"));
+        assert!(ir.contains("sitofp i32 %t6 to double"));
+        assert!(!ir.contains("alloca i64"));
+
+        assert_eq!(emit_llvm_ir_with_int_width(&ast, IntWidth::I64).unwrap(), emit_llvm_ir(&ast).unwrap());
+    }
+
+    #[test]
+    fn test_emit_unsupported() {
+        let result = emit("int a = 1;\neprint(a);");
+        assert!(matches!(
+            result,
+            Err(CompilerError::CodegenError { message, span }) if message == "The 'eprint' statement is not supported by the LLVM backend." && span.line == 2
+        ));
+
+        let result = emit("string s = \"hi\";");
+        assert!(matches!(result, Err(CompilerError::CodegenError { .. })));
+    }
+}
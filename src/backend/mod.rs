@@ -0,0 +1,61 @@
+pub mod c_gen;
+pub mod llvm_gen;
+
+use crate::{
+    errors::CompilerError,
+    schemas::{Ast, IntWidth},
+};
+
+/// A backend a checked program can be compiled with.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Target {
+    Llvm,
+    C,
+}
+
+impl Target {
+    pub const ALL: [Target; 2] = [Target::Llvm, Target::C];
+
+    pub fn emit(self, ast: &Ast, int_width: IntWidth) -> Result<String, CompilerError> {
+        match self {
+            Target::Llvm => llvm_gen::emit_llvm_ir_with_int_width(ast, int_width),
+            Target::C => c_gen::emit_c_with_int_width(ast, int_width),
+        }
+    }
+}
+
+/// Dry-runs every backend on a checked program. A backend that cannot handle the program reports the
+/// first construct it doesn't support, which helps to pick a backend.
+pub fn supported_backends(ast: &Ast, int_width: IntWidth) -> Vec<(Target, Result<(), CompilerError>)> {
+    Target::ALL
+        .into_iter()
+        .map(|target| (target, target.emit(ast, int_width).map(|_| ())))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::compile_str;
+
+    #[test]
+    fn test_supported_backends() {
+        let ast = compile_str("float a = 1.5 * 2;\nprint(a / 4);").unwrap();
+        let support = supported_backends(&ast, IntWidth::I64);
+        assert_eq!(support.len(), 2);
+        assert!(support.iter().all(|(_, result)| result.is_ok()));
+
+        let ast = compile_str("float a = 1.5;\neprint(a);").unwrap();
+        let support = supported_backends(&ast, IntWidth::I64);
+        assert!(matches!(
+            &support[0],
+            (Target::Llvm, Err(CompilerError::CodegenError { message, .. })) if message == "The 'eprint' statement is not supported by the LLVM backend."
+        ));
+        assert!(matches!(support[1], (Target::C, Ok(()))));
+
+        // The int width selects the integer type of both backends.
+        let ast = compile_str("int a = 1;\nprint(a);").unwrap();
+        assert!(Target::Llvm.emit(&ast, IntWidth::I32).unwrap().contains("alloca i32"));
+        assert!(Target::C.emit(&ast, IntWidth::I32).unwrap().contains("int32_t a_0"));
+    }
+}
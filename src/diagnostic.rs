@@ -0,0 +1,303 @@
+use crate::{
+    errors::{CompilerError, CompilerWarning},
+    schemas::Span,
+};
+use std::fmt;
+
+#[derive(Debug, PartialEq, Clone)]
+pub enum Severity {
+    Error,
+    Warning,
+    Note,
+}
+
+/// Common representation of errors and warnings, used for rendering and tooling output.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Diagnostic {
+    pub severity: Severity,
+    pub code: String,
+    pub message: String,
+    pub span: Span,
+    pub notes: Vec<String>,
+}
+
+impl From<&CompilerError> for Diagnostic {
+    fn from(error: &CompilerError) -> Self {
+        Diagnostic {
+            severity: Severity::Error,
+            code: error.code().to_string(),
+            message: error.message(),
+            span: error.span().clone(),
+            notes: vec![],
+        }
+    }
+}
+
+impl From<&CompilerWarning> for Diagnostic {
+    fn from(warning: &CompilerWarning) -> Self {
+        Diagnostic {
+            severity: Severity::Warning,
+            code: warning.code().to_string(),
+            message: warning.message(),
+            span: warning.span().clone(),
+            notes: warning.notes(),
+        }
+    }
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Severity::Error => write!(f, "error"),
+            Severity::Warning => write!(f, "warning"),
+            Severity::Note => write!(f, "note"),
+        }
+    }
+}
+
+impl Severity {
+    /// ANSI style of the label and the caret of a diagnostic with this severity.
+    fn ansi_style(&self) -> &'static str {
+        match self {
+            Severity::Error => "1;31",
+            Severity::Warning => "1;33",
+            Severity::Note => "1;36",
+        }
+    }
+}
+
+const ANSI_DIM: &str = "2";
+
+impl Diagnostic {
+    /// Renders the diagnostic like its `Display` output, but with the offending line of `source` and a
+    /// `^` under the column of the span between the header and the notes. Tabs are expanded to four
+    /// spaces, so the caret stays aligned.
+    pub fn render(&self, source: &str) -> String {
+        self.render_in_file(None, source)
+    }
+
+    /// Like `render`, but also names the file containing `source`, e.g. ` --> main.arith:2:9`.
+    pub fn render_in_file(&self, file_name: Option<&str>, source: &str) -> String {
+        self.render_styled(file_name, source, false)
+    }
+
+    /// Like `render`, but with `color` set the `error[Exxxx]` label and the caret are colored by
+    /// severity and the gutter is dimmed using ANSI escape codes, for output to a terminal.
+    pub fn render_colored(&self, source: &str, color: bool) -> String {
+        self.render_styled(None, source, color)
+    }
+
+    fn render_styled(&self, file_name: Option<&str>, source: &str, color: bool) -> String {
+        let style = |code: &str, text: &str| {
+            if color {
+                format!("\x1b[{}m{}\x1b[0m", code, text)
+            } else {
+                text.to_string()
+            }
+        };
+        let span = &self.span;
+        let label = style(self.severity.ansi_style(), &format!("{}[{}]", self.severity, self.code));
+        let mut rendered = format!("{} (line {}, position {}): {}", label, span.line, span.col, self.message);
+
+        let line_number = span.line.to_string();
+        let gutter = " ".repeat(line_number.len());
+        if let Some(file_name) = file_name {
+            rendered.push_str(&format!("\n{}--> {}:{}:{}", gutter, file_name, span.line, span.col));
+        }
+        // Spans without a line in the source, e.g. at the end of the input, get no snippet.
+        if let Some(line) = span.line.checked_sub(1).and_then(|index| source.lines().nth(index)) {
+            // The column may point right behind the end of the line, e.g. for a missing ';'.
+            let before_caret: String = line.chars().take(span.col.saturating_sub(1)).collect();
+            let caret_offset = expand_tabs(&before_caret).chars().count()
+                + span.col.saturating_sub(1).saturating_sub(before_caret.chars().count());
+
+            let gutter = style(ANSI_DIM, &format!("{} |", gutter));
+            rendered.push_str(&format!("\n{}\n", gutter));
+            rendered.push_str(&format!("{} {}\n", style(ANSI_DIM, &format!("{} |", line_number)), expand_tabs(line)));
+            rendered.push_str(&format!("{} {}{}", gutter, " ".repeat(caret_offset), style(self.severity.ansi_style(), "^")));
+        }
+        for note in &self.notes {
+            rendered.push_str(&format!("\n  = {}: {}", Severity::Note, note));
+        }
+        rendered
+    }
+}
+
+fn expand_tabs(text: &str) -> String {
+    text.replace('\t', "    ")
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}[{}] (line {}, position {}): {}",
+            self.severity, self.code, self.span.line, self.span.col, self.message
+        )?;
+        for note in &self.notes {
+            write!(f, "\n  = {}: {}", Severity::Note, note)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schemas::BinOpKind;
+
+    #[test]
+    fn test_error_to_diagnostic() {
+        let error = CompilerError::NameError {
+            name: "b".to_string(),
+            self_reference: false,
+            suggestion: None,
+            span: Span { line: 2, col: 7, end_line: 2, end_col: 7, file_id: None },
+        };
+        let diagnostic = Diagnostic::from(&error);
+
+        assert_eq!(
+            diagnostic,
+            Diagnostic {
+                severity: Severity::Error,
+                code: "E0005".to_string(),
+                message: "Cannot find identifier 'b'.".to_string(),
+                span: Span { line: 2, col: 7, end_line: 2, end_col: 7, file_id: None },
+                notes: vec![],
+            }
+        );
+        assert_eq!(
+            diagnostic.to_string(),
+            "error[E0005] (line 2, position 7): Cannot find identifier 'b'."
+        );
+    }
+
+    #[test]
+    fn test_warning_to_diagnostic() {
+        let warning = CompilerWarning::DiscardedComparison {
+            op: BinOpKind::Eq,
+            assign_hint: Some("a".to_string()),
+            span: Span { line: 1, col: 3, end_line: 1, end_col: 3, file_id: None },
+        };
+        let diagnostic = Diagnostic::from(&warning);
+
+        assert_eq!(diagnostic.severity, Severity::Warning);
+        assert_eq!(diagnostic.code, "discarded-comparison");
+        assert_eq!(
+            diagnostic.to_string(),
+            "warning[discarded-comparison] (line 1, position 3): The result of comparison 'Eq' is discarded.\n  = note: Did you mean to assign to 'a' using '='?"
+        );
+    }
+
+    fn syntax_error(line: usize, col: usize) -> Diagnostic {
+        Diagnostic::from(&CompilerError::SyntaxError {
+            message: "Unexpected token.".to_string(),
+            span: Span { line, col, end_line: line, end_col: col, file_id: None },
+            fixit: None,
+        })
+    }
+
+    #[test]
+    fn test_render() {
+        let source = "int a = 1;\nint b = * 2;\nprint(b);";
+        assert_eq!(
+            syntax_error(2, 9).render(source),
+            "\
+error[E0001] (line 2, position 9): Unexpected token.
+  |
+2 | int b = * 2;
+  |         ^"
+        );
+    }
+
+    #[test]
+    fn test_render_warning_with_notes() {
+        let warning = CompilerWarning::DiscardedComparison {
+            op: BinOpKind::Eq,
+            assign_hint: Some("a".to_string()),
+            span: Span { line: 2, col: 3, end_line: 2, end_col: 5, file_id: None },
+        };
+        assert_eq!(
+            Diagnostic::from(&warning).render("mut int a = 1;\na == 2;"),
+            "\
+warning[discarded-comparison] (line 2, position 3): The result of comparison 'Eq' is discarded.
+  |
+2 | a == 2;
+  |   ^
+  = note: Did you mean to assign to 'a' using '='?"
+        );
+    }
+
+    /// Removes all ANSI escape sequences, which all end with 'm' in the colored output.
+    fn strip_ansi(text: &str) -> String {
+        let mut stripped = String::new();
+        let mut chars = text.chars();
+        while let Some(c) = chars.next() {
+            if c == '\x1b' {
+                chars.by_ref().find(|c| *c == 'm');
+            } else {
+                stripped.push(c);
+            }
+        }
+        stripped
+    }
+
+    #[test]
+    fn test_render_colored() {
+        let source = "int a = 1;\nint b = * 2;\nprint(b);";
+        let diagnostic = syntax_error(2, 9);
+
+        let plain = diagnostic.render_colored(source, false);
+        assert_eq!(plain, diagnostic.render(source));
+        assert!(!plain.contains('\x1b'));
+
+        let colored = diagnostic.render_colored(source, true);
+        assert!(colored.starts_with("\x1b[1;31merror[E0001]\x1b[0m (line 2, position 9)"));
+        assert!(colored.contains("\x1b[2m2 |\x1b[0m int b = * 2;"));
+        assert!(colored.ends_with("\x1b[2m  |\x1b[0m         \x1b[1;31m^\x1b[0m"));
+        assert_eq!(strip_ansi(&colored), plain);
+    }
+
+    #[test]
+    fn test_render_expands_tabs() {
+        let source = "{\n\tint a = * 2;\n}";
+        assert_eq!(
+            syntax_error(2, 10).render(source),
+            "\
+error[E0001] (line 2, position 10): Unexpected token.
+  |
+2 |     int a = * 2;
+  |             ^"
+        );
+    }
+
+    #[test]
+    fn test_render_non_ascii_prefix() {
+        // Columns count characters, not the bytes of their UTF-8 encoding.
+        let source = "string s = \"ééééé\"; int a = true;";
+        assert_eq!(
+            syntax_error(1, 21).render(source),
+            "\
+error[E0001] (line 1, position 21): Unexpected token.
+  |
+1 | string s = \"ééééé\"; int a = true;
+  |                     ^"
+        );
+    }
+
+    #[test]
+    fn test_render_wide_line_numbers_and_end_of_line() {
+        let source = format!("{}print(1)", "\n".repeat(11));
+        assert_eq!(
+            syntax_error(12, 9).render(&source),
+            "\
+error[E0001] (line 12, position 9): Unexpected token.
+   |
+12 | print(1)
+   |         ^"
+        );
+
+        // Diagnostics without a line in the source, e.g. at the end of the input, fall back to `Display`.
+        assert_eq!(syntax_error(0, 0).render("int a;"), syntax_error(0, 0).to_string());
+    }
+}
@@ -1,11 +1,25 @@
-use crate::schemas::{BinOpKind, Primitive, Span, UnaryOpKind};
+use crate::{
+    diagnostic::Diagnostic,
+    schemas::{BinOpKind, Primitive, Span, UnaryOpKind},
+};
 use std::fmt;
 
+/// Machine-applicable suggestion for an error, e.g. for an editor quick-fix: replaces the `len`
+/// characters starting at `span` with `replacement`. A `len` of zero inserts the replacement.
+#[derive(Debug, PartialEq, Clone)]
+pub struct FixIt {
+    pub span: Span,
+    pub len: usize,
+    pub replacement: String,
+}
+
+#[allow(clippy::enum_variant_names)]
 #[derive(Debug)]
 pub enum CompilerError {
     SyntaxError {
         message: String,
         span: Span,
+        fixit: Option<Box<FixIt>>,
     },
     TypeDeclarationError {
         expected: Primitive,
@@ -25,68 +39,392 @@ pub enum CompilerError {
     },
     NameError {
         name: String,
+        /// Set when the identifier is used in the initializer of its own declaration, e.g. `int a = a;`.
+        self_reference: bool,
+        /// The most similar known identifier, e.g. `count` for `cont`.
+        suggestion: Option<String>,
         span: Span,
     },
     MutabilityError {
         name: String,
         span: Span,
     },
+    ExpressionDepthError {
+        depth: usize,
+        limit: usize,
+        span: Span,
+    },
+    UnknownType {
+        name: String,
+        /// The most similar known type, e.g. `string` for `strng`.
+        suggestion: Option<String>,
+        span: Span,
+    },
+    LiteralOutOfRange {
+        value: String,
+        primitive: Primitive,
+        span: Span,
+    },
+    CodegenError {
+        message: String,
+        span: Span,
+    },
+    ArgumentCountError {
+        name: String,
+        expected: usize,
+        found: usize,
+        span: Span,
+    },
+    /// Raised by the interpreter while executing a program, e.g. on a division by zero.
+    RuntimeError {
+        message: String,
+        span: Span,
+    },
+    /// A variable is declared twice in the same scope, `original_span` is the span of the first declaration.
+    RedeclarationError {
+        name: String,
+        original_span: Span,
+        span: Span,
+    },
+    /// An int division or modulo whose divisor is a constant zero, e.g. `5 / (2 - 2)`.
+    DivisionByZero {
+        span: Span,
+    },
+    /// A function whose body can end without reaching a `return` statement.
+    MissingReturn {
+        name: String,
+        span: Span,
+    },
+    ReturnOutsideFunction {
+        span: Span,
+    },
+    /// A value taken from or returned by a function without return type.
+    UnitFunctionValue {
+        name: String,
+        span: Span,
+    },
 }
 
-impl fmt::Display for CompilerError {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+impl CompilerError {
+    pub fn code(&self) -> &'static str {
+        match self {
+            CompilerError::SyntaxError { .. } => "E0001",
+            CompilerError::TypeDeclarationError { .. } => "E0002",
+            CompilerError::TypeBinOpError { .. } => "E0003",
+            CompilerError::TypeUnaryOpError { .. } => "E0004",
+            CompilerError::NameError { .. } => "E0005",
+            CompilerError::MutabilityError { .. } => "E0006",
+            CompilerError::ExpressionDepthError { .. } => "E0007",
+            CompilerError::UnknownType { .. } => "E0008",
+            CompilerError::LiteralOutOfRange { .. } => "E0009",
+            CompilerError::CodegenError { .. } => "E0010",
+            CompilerError::ArgumentCountError { .. } => "E0011",
+            CompilerError::RuntimeError { .. } => "E0012",
+            CompilerError::RedeclarationError { .. } => "E0013",
+            CompilerError::DivisionByZero { .. } => "E0014",
+            CompilerError::MissingReturn { .. } => "E0015",
+            CompilerError::ReturnOutsideFunction { .. } => "E0016",
+            CompilerError::UnitFunctionValue { .. } => "E0017",
+        }
+    }
+
+    pub fn fixit(&self) -> Option<&FixIt> {
+        match self {
+            CompilerError::SyntaxError { fixit, .. } => fixit.as_deref(),
+            _ => None,
+        }
+    }
+
+    fn kind(&self) -> &'static str {
+        match self {
+            CompilerError::SyntaxError { .. } | CompilerError::ReturnOutsideFunction { .. } => "SyntaxError",
+            CompilerError::TypeDeclarationError { .. }
+            | CompilerError::TypeBinOpError { .. }
+            | CompilerError::TypeUnaryOpError { .. }
+            | CompilerError::UnknownType { .. }
+            | CompilerError::ArgumentCountError { .. }
+            | CompilerError::MissingReturn { .. }
+            | CompilerError::UnitFunctionValue { .. } => "TypeError",
+            CompilerError::NameError { .. } | CompilerError::RedeclarationError { .. } => "NameError",
+            CompilerError::MutabilityError { .. } => "MutabilityError",
+            CompilerError::ExpressionDepthError { .. } => "DepthError",
+            CompilerError::LiteralOutOfRange { .. } => "RangeError",
+            CompilerError::CodegenError { .. } => "CodegenError",
+            CompilerError::RuntimeError { .. } => "RuntimeError",
+            CompilerError::DivisionByZero { .. } => "ZeroDivisionError",
+        }
+    }
+
+    pub fn span(&self) -> &Span {
         match self {
-            CompilerError::SyntaxError { message, span } => {
-                write!(
-                    f,
-                    "SyntaxError (line {}, position {}): {}",
-                    span.line, span.col, message
-                )
+            CompilerError::SyntaxError { span, .. }
+            | CompilerError::TypeDeclarationError { span, .. }
+            | CompilerError::TypeBinOpError { span, .. }
+            | CompilerError::TypeUnaryOpError { span, .. }
+            | CompilerError::NameError { span, .. }
+            | CompilerError::MutabilityError { span, .. }
+            | CompilerError::ExpressionDepthError { span, .. }
+            | CompilerError::UnknownType { span, .. }
+            | CompilerError::LiteralOutOfRange { span, .. }
+            | CompilerError::CodegenError { span, .. }
+            | CompilerError::ArgumentCountError { span, .. }
+            | CompilerError::RuntimeError { span, .. }
+            | CompilerError::RedeclarationError { span, .. }
+            | CompilerError::DivisionByZero { span }
+            | CompilerError::MissingReturn { span, .. }
+            | CompilerError::ReturnOutsideFunction { span }
+            | CompilerError::UnitFunctionValue { span, .. } => span,
+        }
+    }
+
+    pub fn message(&self) -> String {
+        match self {
+            CompilerError::SyntaxError { message, .. }
+            | CompilerError::CodegenError { message, .. }
+            | CompilerError::RuntimeError { message, .. } => {
+                message.clone()
             }
             CompilerError::TypeDeclarationError {
-                expected,
-                found,
-                span,
-            } => {
-                write!(
-                    f,
-                    "TypeError (line {}, position {}): Expected '{:?}', found '{:?}'.",
-                    span.line, span.col, expected, found
-                )
-            }
+                expected, found, ..
+            } => format!("Expected '{:?}', found '{:?}'.", expected, found),
             CompilerError::TypeBinOpError {
-                op,
-                left,
-                right,
-                span,
-            } => {
-                write!(
-                    f,
-                    "TypeError (line {}, position {}): Cannot apply binary operation '{:?}' to '{:?}' and '{:?}'.",
-                    span.line, span.col, op, left, right
-                )
+                op, left, right, ..
+            } => format!(
+                "Cannot apply binary operation '{:?}' to '{:?}' and '{:?}'.",
+                op, left, right
+            ),
+            CompilerError::TypeUnaryOpError { op, operand, .. } => format!(
+                "Cannot apply unary operation '{:?}' to '{:?}'.",
+                op, operand
+            ),
+            CompilerError::NameError {
+                name,
+                self_reference: true,
+                ..
+            } => format!("Variable '{}' is used in its own initializer.", name),
+            CompilerError::NameError {
+                name,
+                suggestion: Some(suggestion),
+                ..
+            } => format!("Cannot find identifier '{}'. Did you mean '{}'?", name, suggestion),
+            CompilerError::NameError { name, .. } => {
+                format!("Cannot find identifier '{}'.", name)
+            }
+            CompilerError::MutabilityError { name, .. } => {
+                format!("Cannot assign twice to immutable variable '{}'.", name)
             }
-            CompilerError::TypeUnaryOpError { op, operand, span } => {
-                write!(
-                    f,
-                    "TypeError (line {}, position {}): Cannot apply unary operation '{:?}' to '{:?}''.",
-                    span.line, span.col, op, operand
-                )
+            CompilerError::ExpressionDepthError { depth, limit, .. } => format!(
+                "Expression is nested {} levels deep, the maximum allowed depth is {}.",
+                depth, limit
+            ),
+            CompilerError::UnknownType {
+                name,
+                suggestion: Some(suggestion),
+                ..
+            } => format!("Cannot find type '{}', did you mean '{}'?", name, suggestion),
+            CompilerError::UnknownType { name, .. } => format!("Cannot find type '{}'.", name),
+            CompilerError::LiteralOutOfRange {
+                value, primitive, ..
+            } => format!("Literal '{}' is out of range for type '{:?}'.", value, primitive),
+            CompilerError::ArgumentCountError {
+                name, expected, found, ..
+            } => format!("Function '{}' expects {} argument(s), found {}.", name, expected, found),
+            CompilerError::RedeclarationError {
+                name, original_span, ..
+            } => format!(
+                "'{}' is already declared in this scope, on line {}.",
+                name, original_span.line
+            ),
+            CompilerError::DivisionByZero { .. } => "Division by zero.".to_string(),
+            CompilerError::MissingReturn { name, .. } => {
+                format!("Function '{}' does not return a value on every path.", name)
             }
-            CompilerError::NameError { name, span } => {
-                write!(
-                    f,
-                    "NameError (line {}, position {}): Cannot find identifier '{}'.",
-                    span.line, span.col, name
-                )
+            CompilerError::ReturnOutsideFunction { .. } => "'return' outside of a function.".to_string(),
+            CompilerError::UnitFunctionValue { name, .. } => {
+                format!("Function '{}' has no return type, it does not produce a value.", name)
             }
-            CompilerError::MutabilityError { name, span } => {
-                write!(
-                    f,
-                    "MutabilityError (line {}, position {}): Cannot assign twice to immutable variable '{}'.",
-                    span.line, span.col, name
-                )
-            },
         }
     }
+
+    /// Renders the error with the offending line of `source`, see `Diagnostic::render`.
+    pub fn render(&self, source: &str) -> String {
+        Diagnostic::from(self).render(source)
+    }
+
+    /// Like `render`, but also names the file containing `source`, see `Diagnostic::render_in_file`.
+    pub fn render_in_file(&self, file_name: Option<&str>, source: &str) -> String {
+        Diagnostic::from(self).render_in_file(file_name, source)
+    }
+
+    /// Like `render`, but colored for output to a terminal if `color` is set, see
+    /// `Diagnostic::render_colored`.
+    pub fn render_colored(&self, source: &str, color: bool) -> String {
+        Diagnostic::from(self).render_colored(source, color)
+    }
+}
+
+impl fmt::Display for CompilerError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let span = self.span();
+        write!(
+            f,
+            "{} (line {}, position {}): {}",
+            self.kind(),
+            span.line,
+            span.col,
+            self.message()
+        )
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub enum CompilerWarning {
+    DiscardedComparison {
+        op: BinOpKind,
+        assign_hint: Option<String>,
+        span: Span,
+    },
+    UnusedVariable {
+        name: String,
+        span: Span,
+    },
+    SuggestParentheses {
+        op: BinOpKind,
+        span: Span,
+    },
+    MixedIndentation {
+        span: Span,
+    },
+    MissingOutput {
+        span: Span,
+    },
+    IntDivisionToInt {
+        name: String,
+        span: Span,
+    },
+}
+
+impl CompilerWarning {
+    pub fn code(&self) -> &'static str {
+        match self {
+            CompilerWarning::DiscardedComparison { .. } => "discarded-comparison",
+            CompilerWarning::UnusedVariable { .. } => "unused-variable",
+            CompilerWarning::SuggestParentheses { .. } => "suggest-parentheses",
+            CompilerWarning::MixedIndentation { .. } => "mixed-indentation",
+            CompilerWarning::MissingOutput { .. } => "missing-output",
+            CompilerWarning::IntDivisionToInt { .. } => "int-division-to-int",
+        }
+    }
+
+    pub fn span(&self) -> &Span {
+        match self {
+            CompilerWarning::DiscardedComparison { span, .. }
+            | CompilerWarning::UnusedVariable { span, .. }
+            | CompilerWarning::SuggestParentheses { span, .. }
+            | CompilerWarning::MixedIndentation { span }
+            | CompilerWarning::MissingOutput { span }
+            | CompilerWarning::IntDivisionToInt { span, .. } => span,
+        }
+    }
+
+    pub fn message(&self) -> String {
+        match self {
+            CompilerWarning::DiscardedComparison { op, .. } => {
+                format!("The result of comparison '{:?}' is discarded.", op)
+            }
+            CompilerWarning::UnusedVariable { name, .. } => {
+                format!("Variable '{}' is declared but never used.", name)
+            }
+            CompilerWarning::SuggestParentheses { op, .. } => format!(
+                "Operands of '{:?}' mix operators of different precedence, consider adding parentheses.",
+                op
+            ),
+            CompilerWarning::MixedIndentation { .. } => {
+                "Indentation mixes tabs and spaces.".to_string()
+            }
+            CompilerWarning::MissingOutput { .. } => {
+                "The program does not end with a 'print' statement.".to_string()
+            }
+            CompilerWarning::IntDivisionToInt { name, .. } => format!(
+                "Division of two ints returns a float, which is truncated when assigned to int variable '{}'.",
+                name
+            ),
+        }
+    }
+
+    pub fn notes(&self) -> Vec<String> {
+        match self {
+            CompilerWarning::DiscardedComparison {
+                assign_hint: Some(name),
+                ..
+            } => vec![format!("Did you mean to assign to '{}' using '='?", name)],
+            CompilerWarning::IntDivisionToInt { name, .. } => vec![format!(
+                "Declare '{}' as float to keep the fraction, or assign the division to a float variable first to make the truncation explicit.",
+                name
+            )],
+            CompilerWarning::DiscardedComparison { .. }
+            | CompilerWarning::UnusedVariable { .. }
+            | CompilerWarning::SuggestParentheses { .. }
+            | CompilerWarning::MixedIndentation { .. }
+            | CompilerWarning::MissingOutput { .. } => vec![],
+        }
+    }
+}
+
+impl fmt::Display for CompilerWarning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let span = self.span();
+        write!(
+            f,
+            "Warning (line {}, position {}): {}",
+            span.line,
+            span.col,
+            self.message()
+        )?;
+        for note in self.notes() {
+            write!(f, " {}", note)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn syntax_error(line: usize, col: usize) -> CompilerError {
+        CompilerError::SyntaxError {
+            message: "Unexpected token.".to_string(),
+            span: Span { line, col, end_line: line, end_col: col, file_id: None },
+            fixit: None,
+        }
+    }
+
+    #[test]
+    fn test_render() {
+        let source = "int a = 1;\nint b = * 2;\nprint(b);";
+        assert_eq!(
+            syntax_error(2, 9).render(source),
+            "\
+error[E0001] (line 2, position 9): Unexpected token.
+  |
+2 | int b = * 2;
+  |         ^"
+        );
+    }
+
+    #[test]
+    fn test_render_in_file() {
+        let source = "int a = 1;\nint b = * 2;\nprint(b);";
+        let rendered = syntax_error(2, 9).render_in_file(Some("main.arith"), source);
+        assert!(rendered.contains("\n --> main.arith:2:9\n"), "{}", rendered);
+    }
+
+    #[test]
+    fn test_render_colored() {
+        let source = "int a = 1;\nint b = * 2;\nprint(b);";
+        let err = syntax_error(2, 9);
+        assert_eq!(err.render_colored(source, false), err.render(source));
+        assert!(err.render_colored(source, true).starts_with("\x1b[1;31merror[E0001]\x1b[0m (line 2, position 9)"));
+    }
 }
@@ -0,0 +1,820 @@
+use crate::{
+    errors::CompilerError,
+    schemas::{Ast, BinOpKind, Conversion, Expr, ExprKind, IntWidth, Primitive, Span, Stmt, UnaryOpKind},
+};
+use std::{collections::HashMap, fmt, io::Write, rc::Rc};
+
+/// Nesting depth of function calls at which a runaway recursion is stopped by default, before the
+/// native stack overflows. Kept low since an unoptimized build needs tens of kilobytes of stack per
+/// call, and a spawned thread only gets 2 MiB of stack by default.
+pub const DEFAULT_MAX_CALL_DEPTH: usize = 64;
+
+/// Native stack size of a thread which runs programs with `DEEP_MAX_CALL_DEPTH`.
+pub const STACK_SIZE: usize = 256 << 20;
+
+/// Nesting depth of function calls which is safe on a thread with `STACK_SIZE` of stack.
+pub const DEEP_MAX_CALL_DEPTH: usize = 5_000;
+
+/// A runtime value, its variant always matches the static type of the expression it was computed by.
+#[derive(Debug, PartialEq, Clone)]
+pub enum Value {
+    Int(i64),
+    Float(f64),
+    Bool(bool),
+    Str(String),
+    Char(char),
+}
+
+impl Value {
+    pub fn primitive(&self) -> Primitive {
+        match self {
+            Value::Int(_) => Primitive::Int,
+            Value::Float(_) => Primitive::Float,
+            Value::Bool(_) => Primitive::Bool,
+            Value::Str(_) => Primitive::Str,
+            Value::Char(_) => Primitive::Char,
+        }
+    }
+
+    fn as_float(&self) -> Option<f64> {
+        match self {
+            Value::Int(value) => Some(*value as f64),
+            Value::Float(value) => Some(*value),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Value::Int(value) => write!(f, "{}", value),
+            Value::Float(value) => match f.precision() {
+                Some(precision) => write!(f, "{:.*}", precision, value),
+                // Debug formatting keeps the decimal point of whole numbers, e.g. `2.0` instead of `2`.
+                None => write!(f, "{:?}", value),
+            },
+            Value::Bool(value) => write!(f, "{}", value),
+            Value::Str(value) => write!(f, "{}", value),
+            Value::Char(value) => write!(f, "{}", value),
+        }
+    }
+}
+
+/// A declared function, shared so that its body can be executed while the interpreter is borrowed mutably.
+struct Function {
+    params: Vec<(String, Primitive)>,
+    ret: Option<Primitive>,
+    body: Ast,
+}
+
+/// Executes a semantically checked program by walking its AST. `print` writes to `stdout` and
+/// `eprint` to `stderr`, so the output can be captured.
+pub struct Interpreter<'a> {
+    /// Variables by scope, the innermost scope is the last one.
+    scopes: Vec<HashMap<String, Value>>,
+    functions: HashMap<String, Rc<Function>>,
+    /// Set by an executed `return` together with its value, until the call it returns from has ended.
+    returning: bool,
+    return_value: Option<Value>,
+    call_depth: usize,
+    max_call_depth: usize,
+    float_precision: Option<usize>,
+    int_width: IntWidth,
+    stdout: &'a mut dyn Write,
+    stderr: &'a mut dyn Write,
+}
+
+impl<'a> Interpreter<'a> {
+    pub fn new(stdout: &'a mut dyn Write, stderr: &'a mut dyn Write) -> Self {
+        Interpreter {
+            scopes: vec![HashMap::new()],
+            functions: HashMap::new(),
+            returning: false,
+            return_value: None,
+            call_depth: 0,
+            max_call_depth: DEFAULT_MAX_CALL_DEPTH,
+            float_precision: None,
+            int_width: IntWidth::default(),
+            stdout,
+            stderr,
+        }
+    }
+
+    /// Limits how deeply function calls may be nested. A limit above `DEFAULT_MAX_CALL_DEPTH` needs a
+    /// thread with a larger stack, see `STACK_SIZE`.
+    pub fn max_call_depth(&mut self, limit: usize) {
+        self.max_call_depth = limit;
+    }
+
+    /// Prints floats with the given number of decimal places instead of their shortest representation.
+    pub fn float_precision(&mut self, precision: Option<usize>) {
+        self.float_precision = precision;
+    }
+
+    /// Sets the width of the `int` primitive, int arithmetic overflows beyond it. Should match the
+    /// width the program was checked with, 64 bit by default.
+    pub fn int_width(&mut self, width: IntWidth) {
+        self.int_width = width;
+    }
+
+    /// Executes the statements in order, stopping early once one of them returns from a function.
+    /// Returns the result of the program for an embedding host: the value of the last statement if it
+    /// is a declaration or an expression, `None` otherwise.
+    pub fn run(&mut self, ast: &Ast) -> Result<Option<Value>, CompilerError> {
+        let mut result = None;
+        for stmt in ast {
+            result = self.execute_stmt(stmt)?;
+            if self.returning {
+                break;
+            }
+        }
+        Ok(result)
+    }
+
+    /// Current value of a variable, looked up from the innermost scope outwards.
+    pub fn get_variable(&self, name: &str) -> Option<&Value> {
+        self.scopes.iter().rev().find_map(|scope| scope.get(name))
+    }
+
+    fn variable(&self, name: &str, span: &Span) -> Result<Value, CompilerError> {
+        self.get_variable(name).cloned().ok_or_else(|| CompilerError::NameError {
+            name: name.to_string(),
+            self_reference: false,
+            suggestion: None,
+            span: span.clone(),
+        })
+    }
+
+    fn declare(&mut self, name: &str, value: Value) {
+        if let Some(scope) = self.scopes.last_mut() {
+            scope.insert(name.to_string(), value);
+        }
+    }
+
+    /// Overwrites the value of the innermost variable with the given name, converted to its type.
+    fn assign(&mut self, name: &str, value: Value, span: &Span) -> Result<Value, CompilerError> {
+        let primitive = self.variable(name, span)?.primitive();
+        let value = self.convert(value, &primitive, span)?;
+        if let Some(scope) = self.scopes.iter_mut().rev().find(|scope| scope.contains_key(name)) {
+            scope.insert(name.to_string(), value.clone());
+        }
+        Ok(value)
+    }
+
+    fn convert(&self, value: Value, to: &Primitive, span: &Span) -> Result<Value, CompilerError> {
+        Ok(match (Conversion::between(&value.primitive(), to, span)?, value) {
+            (Conversion::IntToFloat, Value::Int(value)) => Value::Float(value as f64),
+            // Like Rust's `as`, a float beyond the range of the int width saturates.
+            (Conversion::FloatToInt, Value::Float(value)) => Value::Int(match self.int_width {
+                IntWidth::I32 => value as i32 as i64,
+                IntWidth::I64 => value as i64,
+            }),
+            (_, value) => value,
+        })
+    }
+
+    fn runtime_error(message: &str, span: &Span) -> CompilerError {
+        CompilerError::RuntimeError {
+            message: message.to_string(),
+            span: span.clone(),
+        }
+    }
+
+    fn write_line(output: &mut dyn Write, value: &Value, precision: Option<usize>, span: &Span) -> Result<(), CompilerError> {
+        match precision {
+            Some(precision) => writeln!(output, "{:.*}", precision, value),
+            None => writeln!(output, "{}", value),
+        }
+        .map_err(|err| Self::runtime_error(&format!("Failed to write the output: {}.", err), span))
+    }
+
+    /// Runs the statements in their own scope, which is removed again even if a statement fails.
+    fn execute_block(&mut self, body: &Ast) -> Result<(), CompilerError> {
+        self.scopes.push(HashMap::new());
+        let result = self.run(body);
+        self.scopes.pop();
+        result.map(|_| ())
+    }
+
+    fn execute_for_loop(&mut self, init: &Stmt, cond: &Expr, step: &Stmt, body: &Ast) -> Result<(), CompilerError> {
+        self.execute(init)?;
+        while self.evaluate_condition(cond)? {
+            self.execute_block(body)?;
+            if self.returning {
+                break;
+            }
+            self.execute(step)?;
+        }
+        Ok(())
+    }
+
+    fn evaluate_condition(&mut self, cond: &Expr) -> Result<bool, CompilerError> {
+        match self.evaluate(cond)? {
+            Value::Bool(value) => Ok(value),
+            value => Err(CompilerError::TypeDeclarationError {
+                expected: Primitive::Bool,
+                found: value.primitive(),
+                span: cond.span.clone(),
+            }),
+        }
+    }
+
+    /// Executes a single statement, the variables it declares stay available for the following ones.
+    pub fn execute(&mut self, stmt: &Stmt) -> Result<(), CompilerError> {
+        self.execute_stmt(stmt).map(|_| ())
+    }
+
+    /// Executes a statement and returns the value of a declaration or an expression statement.
+    fn execute_stmt(&mut self, stmt: &Stmt) -> Result<Option<Value>, CompilerError> {
+        match stmt {
+            Stmt::Declare {
+                dtype, name, expr, span, ..
+            } => {
+                let value = self.evaluate(expr)?;
+                let value = self.convert(value, dtype, span)?;
+                self.declare(name, value.clone());
+                return Ok(Some(value));
+            }
+            Stmt::MutAssign { name, expr, span, .. } => {
+                let value = self.evaluate(expr)?;
+                self.assign(name, value, span)?;
+            }
+            Stmt::Print { expr, span, .. } => {
+                let value = self.evaluate(expr)?;
+                Self::write_line(self.stdout, &value, self.float_precision, span)?;
+            }
+            Stmt::EPrint { expr, span, .. } => {
+                let value = self.evaluate(expr)?;
+                Self::write_line(self.stderr, &value, self.float_precision, span)?;
+            }
+            // A function without return type can only be called as a statement of its own.
+            Stmt::Expr {
+                expr: Expr {
+                    node: ExprKind::Call { name, args },
+                    span,
+                    ..
+                },
+                ..
+            } if self.functions.contains_key(name) => {
+                let function = Rc::clone(&self.functions[name]);
+                return self.call(&function, name, args, span);
+            }
+            Stmt::Expr { expr, .. } => return self.evaluate(expr).map(Some),
+            Stmt::Swap { left, right, span, .. } => {
+                let left_value = self.variable(left, span)?;
+                let right_value = self.variable(right, span)?;
+                self.assign(left, right_value, span)?;
+                self.assign(right, left_value, span)?;
+            }
+            Stmt::Block { body, .. } => self.execute_block(body)?,
+            Stmt::If {
+                cond,
+                then_body,
+                else_body,
+                ..
+            } => {
+                if self.evaluate_condition(cond)? {
+                    self.execute_block(then_body)?;
+                } else if let Some(else_body) = else_body {
+                    self.execute_block(else_body)?;
+                }
+            }
+            // The loop variables live in a scope around the whole loop, the body gets a fresh scope per iteration.
+            Stmt::For {
+                init, cond, step, body, ..
+            } => {
+                self.scopes.push(HashMap::new());
+                let result = self.execute_for_loop(init, cond, step, body);
+                self.scopes.pop();
+                result?;
+            }
+            Stmt::FnDecl {
+                name, params, ret, body, ..
+            } => {
+                let function = Function {
+                    params: params.clone(),
+                    ret: ret.clone(),
+                    body: body.clone(),
+                };
+                self.functions.insert(name.clone(), Rc::new(function));
+            }
+            Stmt::Return { expr, .. } => {
+                self.return_value = expr.as_ref().map(|expr| self.evaluate(expr)).transpose()?;
+                self.returning = true;
+            }
+        }
+        Ok(None)
+    }
+
+    /// Calls a declared function and returns its value, `None` for a function without return type. Its
+    /// body sees the global variables and its parameters, but not the local variables of the caller.
+    fn call(&mut self, function: &Function, name: &str, args: &[Expr], span: &Span) -> Result<Option<Value>, CompilerError> {
+        if args.len() != function.params.len() {
+            return Err(CompilerError::ArgumentCountError {
+                name: name.to_string(),
+                expected: function.params.len(),
+                found: args.len(),
+                span: span.clone(),
+            });
+        }
+        if self.call_depth == self.max_call_depth {
+            return Err(Self::runtime_error("Maximum call depth exceeded.", span));
+        }
+
+        let mut params = HashMap::new();
+        for ((param, primitive), arg) in function.params.iter().zip(args) {
+            let value = self.evaluate(arg)?;
+            params.insert(param.clone(), self.convert(value, primitive, &arg.span)?);
+        }
+
+        let caller_scopes = self.scopes.split_off(1);
+        self.scopes.push(params);
+        self.call_depth += 1;
+        let result = self.run(&function.body);
+        self.call_depth -= 1;
+        self.scopes.truncate(1);
+        self.scopes.extend(caller_scopes);
+        self.returning = false;
+        let return_value = self.return_value.take();
+        result?;
+
+        match (return_value, &function.ret) {
+            (Some(value), Some(ret)) => self.convert(value, ret, span).map(Some),
+            (None, Some(_)) => Err(Self::runtime_error(
+                &format!("Function '{}' ended without returning a value.", name),
+                span,
+            )),
+            (_, None) => Ok(None),
+        }
+    }
+
+    pub fn evaluate(&mut self, expr: &Expr) -> Result<Value, CompilerError> {
+        let span = &expr.span;
+        match &expr.node {
+            ExprKind::Literal { value, primitive } => Self::evaluate_literal(value, primitive, false, span),
+            ExprKind::Identifier { name } => self.variable(name, span),
+            ExprKind::UnaryOp { op, expr } => {
+                // Negated int literals are parsed as a whole, `-9223372036854775808` has no positive counterpart.
+                if let (UnaryOpKind::Neg, ExprKind::Literal { value, primitive }) = (op, &expr.node) {
+                    return Self::evaluate_literal(value, primitive, true, span);
+                }
+
+                let value = self.evaluate(expr)?;
+                Self::evaluate_unaryop(op, value, self.int_width, span)
+            }
+            // The right operand of '&&' and '||' is only evaluated if it decides the result.
+            ExprKind::BinOp {
+                op: op @ (BinOpKind::And | BinOpKind::Or),
+                left,
+                right,
+            } => match (op, self.evaluate(left)?) {
+                (BinOpKind::And, Value::Bool(false)) => Ok(Value::Bool(false)),
+                (BinOpKind::Or, Value::Bool(true)) => Ok(Value::Bool(true)),
+                (_, left) => {
+                    let right = self.evaluate(right)?;
+                    Self::evaluate_binop(op, left, right, self.int_width, span)
+                }
+            },
+            ExprKind::BinOp { op, left, right } => {
+                let left = self.evaluate(left)?;
+                let right = self.evaluate(right)?;
+                Self::evaluate_binop(op, left, right, self.int_width, span)
+            }
+            ExprKind::Assign { name, expr } => {
+                let value = self.evaluate(expr)?;
+                self.assign(name, value, span)
+            }
+            ExprKind::TypeOf { expr } => {
+                let value = self.evaluate(expr)?;
+                Ok(Value::Str(value.primitive().name().to_string()))
+            }
+            ExprKind::Call { name, args } => {
+                if let Some(function) = self.functions.get(name).cloned() {
+                    return self.call(&function, name, args, span)?.ok_or_else(|| CompilerError::UnitFunctionValue {
+                        name: name.clone(),
+                        span: span.clone(),
+                    });
+                }
+                if let (name @ ("int" | "char"), [arg]) = (name.as_str(), args.as_slice()) {
+                    return self.evaluate_char_conversion(name, arg);
+                }
+                let args = args
+                    .iter()
+                    .map(|arg| {
+                        let value = self.evaluate(arg)?;
+                        value.as_float().ok_or_else(|| CompilerError::TypeDeclarationError {
+                            expected: Primitive::Float,
+                            found: value.primitive(),
+                            span: arg.span.clone(),
+                        })
+                    })
+                    .collect::<Result<Vec<f64>, CompilerError>>()?;
+                match (name.as_str(), args.as_slice()) {
+                    ("pow", [base, exponent]) => Ok(Value::Float(base.powf(*exponent))),
+                    ("sqrt", [value]) => Ok(Value::Float(value.sqrt())),
+                    _ => Err(CompilerError::NameError {
+                        name: name.clone(),
+                        self_reference: false,
+                        suggestion: None,
+                        span: span.clone(),
+                    }),
+                }
+            }
+        }
+    }
+
+    /// Evaluates `int(c)`, the code point of a char, or `char(code)`, the char with the given code point.
+    fn evaluate_char_conversion(&mut self, name: &str, arg: &Expr) -> Result<Value, CompilerError> {
+        match (name, self.evaluate(arg)?) {
+            ("int", Value::Char(c)) => Ok(Value::Int(c as i64)),
+            ("char", Value::Int(code)) => u32::try_from(code)
+                .ok()
+                .and_then(char::from_u32)
+                .map(Value::Char)
+                .ok_or_else(|| Self::runtime_error(&format!("Invalid char code {}.", code), &arg.span)),
+            (_, value) => Err(CompilerError::TypeDeclarationError {
+                expected: if name == "int" { Primitive::Char } else { Primitive::Int },
+                found: value.primitive(),
+                span: arg.span.clone(),
+            }),
+        }
+    }
+
+    /// Parses the value of a (negated) literal, e.g. to evaluate it or to fold constant expressions.
+    pub(crate) fn evaluate_literal(value: &str, primitive: &Primitive, negated: bool, span: &Span) -> Result<Value, CompilerError> {
+        let sign = if negated { "-" } else { "" };
+        let out_of_range = || CompilerError::LiteralOutOfRange {
+            value: value.to_string(),
+            primitive: primitive.clone(),
+            span: span.clone(),
+        };
+        match primitive {
+            Primitive::Int => format!("{}{}", sign, value)
+                .parse::<i64>()
+                .map(Value::Int)
+                .map_err(|_| out_of_range()),
+            Primitive::Float => format!("{}{}", sign, value)
+                .parse::<f64>()
+                .map(Value::Float)
+                .map_err(|_| out_of_range()),
+            Primitive::Bool if !negated => Ok(Value::Bool(value == "true")),
+            Primitive::Str if !negated => Ok(Value::Str(value.to_string())),
+            Primitive::Char if !negated => Ok(Value::Char(value.chars().next().unwrap_or_default())),
+            primitive => Err(CompilerError::TypeUnaryOpError {
+                op: UnaryOpKind::Neg,
+                operand: primitive.clone(),
+                span: span.clone(),
+            }),
+        }
+    }
+
+    pub(crate) fn evaluate_unaryop(op: &UnaryOpKind, value: Value, int_width: IntWidth, span: &Span) -> Result<Value, CompilerError> {
+        match (op, value) {
+            (UnaryOpKind::Neg, Value::Int(value)) => value
+                .checked_neg()
+                .filter(|value| int_width.contains(*value))
+                .map(Value::Int)
+                .ok_or_else(|| Self::runtime_error("Integer overflow.", span)),
+            (UnaryOpKind::Neg, Value::Float(value)) => Ok(Value::Float(-value)),
+            (UnaryOpKind::Not, Value::Bool(value)) => Ok(Value::Bool(!value)),
+            (UnaryOpKind::BitNot, Value::Int(value)) => Ok(Value::Int(!value)),
+            (op, value) => Err(CompilerError::TypeUnaryOpError {
+                op: op.clone(),
+                operand: value.primitive(),
+                span: span.clone(),
+            }),
+        }
+    }
+
+    /// Applies a binary operator following the typing rules of the semantic analyser, e.g. int
+    /// division returns float. Errors on type mismatches and on failing arithmetic like a division by zero,
+    /// int results have to fit into `int_width`.
+    pub(crate) fn evaluate_binop(
+        op: &BinOpKind,
+        left: Value,
+        right: Value,
+        int_width: IntWidth,
+        span: &Span,
+    ) -> Result<Value, CompilerError> {
+        let overflow = || Self::runtime_error("Integer overflow.", span);
+        match (op, &left, &right) {
+            (BinOpKind::Mod, Value::Int(_), Value::Int(0)) => Err(Self::runtime_error("Modulo by zero.", span)),
+            (BinOpKind::Pow, Value::Int(_), Value::Int(exponent)) if *exponent < 0 => {
+                Err(Self::runtime_error("Negative exponent in integer power.", span))
+            }
+            (BinOpKind::Pow, Value::Int(base), Value::Int(exponent)) => u32::try_from(*exponent)
+                .ok()
+                .and_then(|exponent| base.checked_pow(exponent))
+                .filter(|value| int_width.contains(*value))
+                .map(Value::Int)
+                .ok_or_else(overflow),
+            (BinOpKind::Add | BinOpKind::Sub | BinOpKind::Mult | BinOpKind::Mod, Value::Int(left), Value::Int(right)) => {
+                let result = match op {
+                    BinOpKind::Add => left.checked_add(*right),
+                    BinOpKind::Sub => left.checked_sub(*right),
+                    BinOpKind::Mult => left.checked_mul(*right),
+                    _ => left.checked_rem(*right),
+                };
+                result.filter(|value| int_width.contains(*value)).map(Value::Int).ok_or_else(overflow)
+            }
+            (BinOpKind::BitAnd, Value::Int(left), Value::Int(right)) => Ok(Value::Int(left & right)),
+            (BinOpKind::BitOr, Value::Int(left), Value::Int(right)) => Ok(Value::Int(left | right)),
+            // Bits shifted out are dropped, but the shift amount has to be less than the bit width.
+            (BinOpKind::Shl | BinOpKind::Shr, Value::Int(left), Value::Int(right)) => u32::try_from(*right)
+                .ok()
+                .filter(|amount| *amount < int_width.bits())
+                .and_then(|amount| match op {
+                    BinOpKind::Shl => left.checked_shl(amount),
+                    _ => left.checked_shr(amount),
+                })
+                .map(|value| Value::Int(int_width.wrap(value)))
+                .ok_or_else(|| Self::runtime_error("Shift amount out of range.", span)),
+            (BinOpKind::And, Value::Bool(left), Value::Bool(right)) => Ok(Value::Bool(*left && *right)),
+            (BinOpKind::Or, Value::Bool(left), Value::Bool(right)) => Ok(Value::Bool(*left || *right)),
+            (BinOpKind::Eq, Value::Bool(left), Value::Bool(right)) => Ok(Value::Bool(left == right)),
+            (BinOpKind::Ne, Value::Bool(left), Value::Bool(right)) => Ok(Value::Bool(left != right)),
+            (BinOpKind::Eq, Value::Str(left), Value::Str(right)) => Ok(Value::Bool(left == right)),
+            (BinOpKind::Ne, Value::Str(left), Value::Str(right)) => Ok(Value::Bool(left != right)),
+            (BinOpKind::Eq, Value::Char(left), Value::Char(right)) => Ok(Value::Bool(left == right)),
+            (BinOpKind::Ne, Value::Char(left), Value::Char(right)) => Ok(Value::Bool(left != right)),
+            (
+                BinOpKind::Gt | BinOpKind::Lt | BinOpKind::Ge | BinOpKind::Le | BinOpKind::Eq | BinOpKind::Ne,
+                Value::Int(left),
+                Value::Int(right),
+            ) => Ok(Value::Bool(match op {
+                    BinOpKind::Eq => left == right,
+                    BinOpKind::Ne => left != right,
+                    BinOpKind::Gt => left > right,
+                    BinOpKind::Lt => left < right,
+                    BinOpKind::Ge => left >= right,
+                    _ => left <= right,
+                })),
+            // Any other arithmetic or comparison is done on floats, division also for int operands. A float
+            // division or modulo by zero is an error rather than an infinite or NaN result.
+            _ => match (op, left.as_float(), right.as_float()) {
+                (BinOpKind::Div, Some(_), Some(0.0)) => Err(Self::runtime_error("Division by zero.", span)),
+                (BinOpKind::Mod, Some(_), Some(0.0)) => Err(Self::runtime_error("Modulo by zero.", span)),
+                (BinOpKind::Add, Some(left), Some(right)) => Ok(Value::Float(left + right)),
+                (BinOpKind::Sub, Some(left), Some(right)) => Ok(Value::Float(left - right)),
+                (BinOpKind::Mult, Some(left), Some(right)) => Ok(Value::Float(left * right)),
+                (BinOpKind::Div, Some(left), Some(right)) => Ok(Value::Float(left / right)),
+                (BinOpKind::Mod, Some(left), Some(right)) => Ok(Value::Float(left % right)),
+                (BinOpKind::Pow, Some(left), Some(right)) => Ok(Value::Float(left.powf(right))),
+                (BinOpKind::Eq, Some(left), Some(right)) => Ok(Value::Bool(left == right)),
+                (BinOpKind::Ne, Some(left), Some(right)) => Ok(Value::Bool(left != right)),
+                (BinOpKind::Gt, Some(left), Some(right)) => Ok(Value::Bool(left > right)),
+                (BinOpKind::Lt, Some(left), Some(right)) => Ok(Value::Bool(left < right)),
+                (BinOpKind::Ge, Some(left), Some(right)) => Ok(Value::Bool(left >= right)),
+                (BinOpKind::Le, Some(left), Some(right)) => Ok(Value::Bool(left <= right)),
+                _ => Err(CompilerError::TypeBinOpError {
+                    op: op.clone(),
+                    left: left.primitive(),
+                    right: right.primitive(),
+                    span: span.clone(),
+                }),
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::compile_str;
+
+    /// Checks and runs the program, returning what it printed to stdout and stderr.
+    fn run_with_stderr(input: &str) -> Result<(String, String), CompilerError> {
+        let ast = compile_str(input)?;
+
+        let (mut stdout, mut stderr) = (vec![], vec![]);
+        Interpreter::new(&mut stdout, &mut stderr).run(&ast)?;
+        Ok((String::from_utf8(stdout).unwrap(), String::from_utf8(stderr).unwrap()))
+    }
+
+    fn run(input: &str) -> Result<String, CompilerError> {
+        run_with_stderr(input).map(|(stdout, _)| stdout)
+    }
+
+    #[test]
+    fn test_arithmetic() {
+        let output = run("int a = (1 * (2 + 3)) + 3;\nfloat b = -a / 5;\nprint(a);\nprint(b + 3);\nprint(7 % 3);\nprint(2 * 1.5);\nint c = 10 / 4;\nprint(c);")
+            .unwrap();
+        assert_eq!(output, "8\n1.4\n1\n3.0\n2\n");
+
+        assert_eq!(run("print(-9223372036854775808);").unwrap(), "-9223372036854775808\n");
+    }
+
+    #[test]
+    fn test_long_operator_chain() {
+        let chain = |terms: usize| vec!["1"; terms].join(" + ");
+        let depth = crate::semantic::DEFAULT_MAX_EXPRESSION_DEPTH;
+        assert_eq!(run(&format!("print({});", chain(depth))).unwrap(), format!("{}\n", depth));
+
+        let result = run(&format!("int a = {};\nprint(a);", chain(1500)));
+        assert!(matches!(result, Err(CompilerError::ExpressionDepthError { depth: 1500, .. })));
+    }
+
+    #[test]
+    fn test_int_width() {
+        let run_with_width = |input: &str, int_width: IntWidth| {
+            let ast = crate::compile_str_with_int_width(input, int_width)?;
+            let (mut stdout, mut stderr) = (vec![], vec![]);
+            let mut interpreter = Interpreter::new(&mut stdout, &mut stderr);
+            interpreter.int_width(int_width);
+            interpreter.run(&ast)?;
+            drop(interpreter);
+            Ok::<_, CompilerError>(String::from_utf8(stdout).unwrap())
+        };
+
+        let input = "int a = 2147483647;\nprint(a + 1);";
+        assert_eq!(run_with_width(input, IntWidth::I64).unwrap(), "2147483648\n");
+        let result = run_with_width(input, IntWidth::I32);
+        assert!(matches!(result, Err(CompilerError::RuntimeError { message, .. }) if message == "Integer overflow."));
+
+        for input in ["int a = -2147483648;\nprint(-a);", "print(46341 * 46341);", "print(2 ^ 31);"] {
+            let result = run_with_width(input, IntWidth::I32);
+            assert!(matches!(result, Err(CompilerError::RuntimeError { message, .. }) if message == "Integer overflow."), "{}", input);
+        }
+
+        // Shifts drop the bits beyond the width, floats saturate at its bounds.
+        let output = run_with_width("print(1 << 31);\nprint(3 << 31);\nfloat f = 3e9;\nint a = f;\nprint(a);", IntWidth::I32).unwrap();
+        assert_eq!(output, "-2147483648\n-2147483648\n2147483647\n");
+        let result = run_with_width("print(1 << 32);", IntWidth::I32);
+        assert!(matches!(result, Err(CompilerError::RuntimeError { message, .. }) if message == "Shift amount out of range."));
+    }
+
+    #[test]
+    fn test_float_modulo() {
+        let output = run("print(7 % 2.5);\nprint(7.5 % 2);\nprint(7.5 % 2.5);\nprint(-7.5 % 2);\nprint(7.5 % -2);").unwrap();
+        assert_eq!(output, "2.0\n1.5\n0.0\n-1.5\n1.5\n");
+
+        // Unlike Rust's `%`, which gives NaN, a modulo by zero is an error, for a negative zero too.
+        for input in ["float a = 0.0;\nprint(5 % a);", "float a = -0.0;\nprint(5.5 % a);"] {
+            let result = run(input);
+            assert!(matches!(result, Err(CompilerError::RuntimeError { message, .. }) if message == "Modulo by zero."), "{}", input);
+        }
+    }
+
+    #[test]
+    fn test_power() {
+        assert_eq!(run("print(2 ^ 3 ^ 2);\nprint(2 ^ -1.0);\nprint(4 ^ 0.5);").unwrap(), "512\n0.5\n2.0\n");
+
+        let result = run("int a = -1;\nprint(2 ^ a);");
+        assert!(matches!(result, Err(CompilerError::RuntimeError { message, .. }) if message == "Negative exponent in integer power."));
+
+        let result = run("print(2 ^ 63);");
+        assert!(matches!(result, Err(CompilerError::RuntimeError { message, .. }) if message == "Integer overflow."));
+    }
+
+    #[test]
+    fn test_bitwise_operators() {
+        let output = run("int a = 0xF0;\nprint(a >> 4);\nprint(a & 0x3C | 1);\nprint(~a);\nprint(-16 >> 2);\nprint(1 << 62);").unwrap();
+        assert_eq!(output, "15\n49\n-241\n-4\n4611686018427387904\n");
+
+        let result = run("int a = 64;\nprint(1 << a);");
+        assert!(matches!(result, Err(CompilerError::RuntimeError { message, .. }) if message == "Shift amount out of range."));
+    }
+
+    #[test]
+    fn test_booleans() {
+        let output = run("int d = 1;\nfloat e = 1.0;\nprint(true && false);\nprint(!(true && (2 > 0.5)) || (d != e) && (10 <= 200));\nprint(d == e);\nprint(\"a\" != \"b\");\nprint('a' == 'b');")
+            .unwrap();
+        assert_eq!(output, "false\nfalse\ntrue\ntrue\nfalse\n");
+    }
+
+    #[test]
+    fn test_variables_and_control_flow() {
+        let output = run(
+            "mut int a = 1;\nmut int b = 2;\nswap(a, b);\nprint(a);\n{\n  int a = 10;\n  print(a);\n}\nprint(a);\nif (a > b) { print(\"greater\"); } else { print(\"smaller\"); }\nmut int sum = 0;\nfor (mut int i = 1; i <= 4; i += 1) { sum += i; }\nprint(sum);",
+        )
+        .unwrap();
+        assert_eq!(output, "2\n10\n2\ngreater\n10\n");
+    }
+
+    #[test]
+    fn test_builtins_and_typeof() {
+        let (stdout, stderr) = run_with_stderr("print(sqrt(16.0));\nprint(pow(2, 10));\nprint(typeof(1 / 2));\neprint(typeof(true));\nprint('x');\nprint(typeof('x'));").unwrap();
+        assert_eq!(stdout, "4.0\n1024.0\nfloat\nx\nchar\n");
+        assert_eq!(stderr, "bool\n");
+    }
+
+    #[test]
+    fn test_char_conversion() {
+        let output = run("char c = 'A';\nint code = int(c);\nprint(code);\nprint(char(code));\nprint(char(int('a') + 1));").unwrap();
+        assert_eq!(output, "65\nA\nb\n");
+
+        for code in ["-1", "55296", "1114112"] {
+            let result = run(&format!("int code = {};\nprint(char(code));", code));
+            assert!(
+                matches!(&result, Err(CompilerError::RuntimeError { message, span }) if *message == format!("Invalid char code {}.", code) && span.col == 12),
+                "{:?}",
+                result
+            );
+        }
+    }
+
+    #[test]
+    fn test_runtime_errors() {
+        let result = run("int a = 0;\nprint(1);\nprint(5 / a);");
+        assert!(matches!(
+            result,
+            Err(CompilerError::RuntimeError { message, span }) if message == "Division by zero." && span.line == 3 && span.col == 7
+        ));
+
+        let result = run("int a = 0;\nprint(5 % a);");
+        assert!(matches!(result, Err(CompilerError::RuntimeError { message, .. }) if message == "Modulo by zero."));
+
+        let result = run("int a = 9223372036854775807;\nprint(a + 1);");
+        assert!(matches!(result, Err(CompilerError::RuntimeError { message, .. }) if message == "Integer overflow."));
+    }
+
+    #[test]
+    fn test_float_precision() {
+        let run_with_precision = |input: &str, precision: Option<usize>| {
+            let ast = compile_str(input).unwrap();
+            let mut stdout = vec![];
+            let mut stderr = vec![];
+            let mut interpreter = Interpreter::new(&mut stdout, &mut stderr);
+            interpreter.float_precision(precision);
+            interpreter.run(&ast).unwrap();
+            String::from_utf8(stdout).unwrap()
+        };
+
+        let program = "print(1.0 / 3.0);\nprint(2.0);\nprint(7);\nprint(true);";
+        assert_eq!(run_with_precision(program, None), "0.3333333333333333\n2.0\n7\ntrue\n");
+        assert_eq!(run_with_precision(program, Some(4)), "0.3333\n2.0000\n7\ntrue\n");
+    }
+
+    #[test]
+    fn test_program_result() {
+        let run_result = |input: &str| {
+            let ast = compile_str(input).unwrap();
+            let (mut stdout, mut stderr) = (vec![], vec![]);
+            Interpreter::new(&mut stdout, &mut stderr).run(&ast).unwrap()
+        };
+
+        assert_eq!(run_result("int a = 2;\nfloat b = a * 1.5;"), Some(Value::Float(3.0)));
+        assert_eq!(run_result("int a = 2;\na + 1;"), Some(Value::Int(3)));
+        assert_eq!(run_result("int a = 2;\nprint(a);"), None);
+        assert_eq!(run_result(""), None);
+    }
+
+    #[test]
+    fn test_functions() {
+        let output = run(
+            "mut int calls = 0;\nfn fib(int n) -> int {\n  calls += 1;\n  if (n < 2) { return n; }\n  return fib(n - 1) + fib(n - 2);\n}\nfn half(int x) -> float {\n  return x / 2;\n}\nint n = 3;\nprint(fib(10));\nprint(calls);\nprint(half(n));\nprint(n);",
+        )
+        .unwrap();
+        assert_eq!(output, "55\n177\n1.5\n3\n");
+
+        // A return inside of a loop leaves the whole function.
+        let output = run("fn first(int limit) -> int {\n  for (mut int i = 1; i < limit; i += 1) {\n    if (i * i > 10) { return i; }\n  }\n  return -1;\n}\nprint(first(100));\nprint(first(3));").unwrap();
+        assert_eq!(output, "4\n-1\n");
+
+        let output = run("fn log(int x) {\n  if (x < 0) { return; }\n  print(x);\n}\nlog(-1);\nlog(2);").unwrap();
+        assert_eq!(output, "2\n");
+    }
+
+    #[test]
+    fn test_deep_recursion() {
+        let run_with_stack = |input: &'static str| {
+            std::thread::Builder::new()
+                .stack_size(STACK_SIZE)
+                .spawn(move || {
+                    let ast = compile_str(input)?;
+                    let (mut stdout, mut stderr) = (vec![], vec![]);
+                    let mut interpreter = Interpreter::new(&mut stdout, &mut stderr);
+                    interpreter.max_call_depth(DEEP_MAX_CALL_DEPTH);
+                    interpreter.run(&ast)?;
+                    drop(interpreter);
+                    Ok::<_, CompilerError>(String::from_utf8(stdout).unwrap())
+                })
+                .unwrap()
+                .join()
+                .unwrap()
+        };
+
+        let output = run_with_stack("fn sum(int n) -> int {\n  if (n == 0) { return 0; }\n  return n + sum(n - 1);\n}\nprint(sum(1000));");
+        assert_eq!(output.unwrap(), "500500\n");
+
+        let result = run_with_stack("fn down(int n) -> int {\n  return down(n + 1);\n}\nprint(down(0));");
+        assert!(matches!(result, Err(CompilerError::RuntimeError { message, .. }) if message == "Maximum call depth exceeded."));
+    }
+
+    #[test]
+    fn test_recursion_on_default_stack() {
+        let program = |n: usize| format!("fn f(int n) -> int {{\n  if (n == 0) {{ return 0; }}\n  return f(n - 1) + 1;\n}}\nprint(f({}));", n);
+        assert_eq!(run(&program(DEFAULT_MAX_CALL_DEPTH - 1)).unwrap(), format!("{}\n", DEFAULT_MAX_CALL_DEPTH - 1));
+
+        // A deep recursion stops with an error instead of overflowing the stack of the test thread.
+        let result = run(&program(4900));
+        assert!(matches!(result, Err(CompilerError::RuntimeError { message, .. }) if message == "Maximum call depth exceeded."));
+    }
+
+    #[test]
+    fn test_short_circuit() {
+        let output = run("int a = 0;\nprint(false && 1 / a > 0);\nprint(true || 1 / a > 0);").unwrap();
+        assert_eq!(output, "false\ntrue\n");
+    }
+}
@@ -1,27 +1,255 @@
-use crate::{errors::CompilerError, schemas::*};
+use crate::{
+    errors::{CompilerError, CompilerWarning, FixIt},
+    schemas::*,
+};
+use std::{collections::HashMap, rc::Rc};
+
+/// Maps keyword spellings to the token they produce, e.g. "int" to `TokenKind::Declare(Primitive::Int)`.
+pub type Keywords = HashMap<String, TokenKind>;
+
+pub fn default_keywords() -> Keywords {
+    HashMap::from([
+        ("int".to_string(), TokenKind::Declare(Primitive::Int)),
+        ("float".to_string(), TokenKind::Declare(Primitive::Float)),
+        ("bool".to_string(), TokenKind::Declare(Primitive::Bool)),
+        ("string".to_string(), TokenKind::Declare(Primitive::Str)),
+        ("char".to_string(), TokenKind::Declare(Primitive::Char)),
+        ("mut".to_string(), TokenKind::Mut),
+        ("print".to_string(), TokenKind::Print),
+        ("eprint".to_string(), TokenKind::EPrint),
+        ("swap".to_string(), TokenKind::Swap),
+        ("typeof".to_string(), TokenKind::TypeOf),
+        ("if".to_string(), TokenKind::If),
+        ("else".to_string(), TokenKind::Else),
+        ("for".to_string(), TokenKind::For),
+        ("fn".to_string(), TokenKind::Fn),
+        ("return".to_string(), TokenKind::Return),
+        (
+            "true".to_string(),
+            TokenKind::Literal(Literal {
+                value: "true".to_string(),
+                primitive: Primitive::Bool,
+            }),
+        ),
+        (
+            "false".to_string(),
+            TokenKind::Literal(Literal {
+                value: "false".to_string(),
+                primitive: Primitive::Bool,
+            }),
+        ),
+    ])
+}
+
+/// Style lint over the raw source, warns about every line whose indentation contains both tabs and spaces.
+pub fn check_mixed_indentation(program: &str) -> Vec<CompilerWarning> {
+    program
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| {
+            let indentation: Vec<char> = line.chars().take_while(|c| *c == ' ' || *c == '\t').collect();
+            indentation.contains(&' ') && indentation.contains(&'\t')
+        })
+        .map(|(index, _)| CompilerWarning::MixedIndentation {
+            span: Span { line: index + 1, col: 1, end_line: index + 1, end_col: 1, file_id: None },
+        })
+        .collect()
+}
+
+/// Scans number literals, so that alternative number syntaxes (e.g. a ',' decimal separator) can be
+/// plugged into the `Lexer` without forking it, see `Lexer::number_lexer`.
+pub trait NumberLexer {
+    /// Called when the next character is a digit or a '.', consumes the number through the lexer and
+    /// returns its literal. The value of a float literal has to use '.' as decimal point.
+    fn scan_number(&self, lexer: &mut Lexer) -> Result<Literal, CompilerError>;
+}
+
+/// The built-in number syntax: ints, hexadecimal ints, floats with a '.' decimal point, scientific
+/// notation and `_` digit separators.
+pub struct DefaultNumberLexer;
+
+impl DefaultNumberLexer {
+    /// Consumes the exponent of a float literal in scientific notation, e.g. the `e-10` in `1.5e-10`.
+    fn scan_exponent(lexer: &mut Lexer, token: &mut String) -> Result<(), CompilerError> {
+        let marker_span = lexer.current_span();
+        token.push(lexer.consume_next());
+        if matches!(lexer.peek_next(), '+' | '-') {
+            token.push(lexer.consume_next());
+        }
+        if !lexer.peek_next().is_ascii_digit() {
+            return Err(CompilerError::SyntaxError {
+                message: "Expected digits after the exponent marker of float literal.".to_string(),
+                span: marker_span,
+                fixit: None,
+            });
+        }
+        while lexer.peek_next().is_ascii_digit() {
+            token.push(lexer.consume_next());
+        }
+        Ok(())
+    }
+
+    /// Consumes a hexadecimal int literal like `0xF0`, its value is converted to decimal (`240`).
+    fn scan_hex(lexer: &mut Lexer) -> Result<Literal, CompilerError> {
+        let literal_span = lexer.current_span();
+        let mut token = String::new();
+        token.push(lexer.consume_next());
+        token.push(lexer.consume_next());
+        while lexer.peek_next().is_ascii_hexdigit() {
+            token.push(lexer.consume_next());
+        }
+        if token.len() == 2 {
+            return Err(CompilerError::SyntaxError {
+                message: format!("Expected hex digits after '{}'.", token),
+                span: literal_span,
+                fixit: None,
+            });
+        }
+
+        match u128::from_str_radix(&token[2..], 16) {
+            Ok(value) => Ok(Literal {
+                value: value.to_string(),
+                primitive: Primitive::Int,
+            }),
+            Err(_) => Err(CompilerError::LiteralOutOfRange {
+                value: token,
+                primitive: Primitive::Int,
+                span: literal_span.to(&lexer.current_span()),
+            }),
+        }
+    }
+}
+
+impl NumberLexer for DefaultNumberLexer {
+    /// Lexes an int or float literal. Underscores may be used as digit separators (`1_000_000`), they
+    /// have to sit between two digits and are stripped from the literal value.
+    fn scan_number(&self, lexer: &mut Lexer) -> Result<Literal, CompilerError> {
+        if lexer.peek_next() == '0' && matches!(lexer.peek_second(), 'x' | 'X') {
+            return Self::scan_hex(lexer);
+        }
+
+        let mut token = String::new();
+        let mut last_char = '\0';
+        loop {
+            let next_char = lexer.peek_next();
+            if next_char == '_' {
+                let separator_span = lexer.current_span();
+                lexer.consume_next();
+                let message = if last_char == '_' {
+                    "Consecutive digit separators in numeric literal."
+                } else if !last_char.is_numeric() {
+                    "Digit separator '_' must follow a digit."
+                } else if !lexer.peek_next().is_numeric() && lexer.peek_next() != '_' {
+                    "Digit separator '_' must be followed by a digit."
+                } else {
+                    last_char = next_char;
+                    continue;
+                };
+                return Err(CompilerError::SyntaxError {
+                    message: message.to_string(),
+                    span: separator_span,
+                    fixit: None,
+                });
+            }
+            if matches!(next_char, 'e' | 'E') && token.chars().any(|c| c.is_numeric()) {
+                Self::scan_exponent(lexer, &mut token)?;
+                break;
+            }
+            if next_char == '.' && token.contains('.') {
+                return Err(CompilerError::SyntaxError {
+                    message: "Invalid float literal: multiple decimal points.".to_string(),
+                    span: lexer.current_span(),
+                    fixit: None,
+                });
+            }
+            if next_char.is_numeric() || next_char == '.' {
+                token.push(lexer.consume_next());
+                last_char = next_char;
+                continue;
+            }
+            break;
+        }
+
+        let primitive = if token.contains(['.', 'e', 'E']) {
+            Primitive::Float
+        } else {
+            Primitive::Int
+        };
+        Ok(Literal { value: token, primitive })
+    }
+}
 
 pub struct Lexer {
     chars: Vec<char>,
     cur_line: usize,
     cur_col: usize,
     tokens: Vec<Token>,
+    keywords: Keywords,
+    word_operators: bool,
+    file_id: Option<u32>,
+    number_lexer: Rc<dyn NumberLexer>,
 }
 
 impl Lexer {
     pub fn new(program: &str) -> Self {
+        Self::new_with_keywords(program, default_keywords())
+    }
+
+    /// Creates a lexer using a custom keyword table, e.g. to localize the language.
+    pub fn new_with_keywords(program: &str, keywords: Keywords) -> Self {
         Lexer {
             chars: program.chars().rev().collect(),
             cur_line: 1,
             cur_col: 1,
             tokens: vec![],
+            keywords,
+            word_operators: false,
+            file_id: None,
+            number_lexer: Rc::new(DefaultNumberLexer),
         }
     }
 
-    fn peek_next(&self) -> char {
+    /// Tags the spans of all tokens with the id of the lexed file, see `SourceMap::add_file`.
+    pub fn file_id(&mut self, file_id: u32) {
+        self.file_id = Some(file_id);
+    }
+
+    /// Enables the (opt-in) word spellings `and`, `or` and `not` of the operators `&&`, `||` and `!`.
+    pub fn word_operators(&mut self, enabled: bool) {
+        self.word_operators = enabled;
+    }
+
+    /// Replaces the number syntax, which defaults to `DefaultNumberLexer`.
+    pub fn number_lexer(&mut self, number_lexer: impl NumberLexer + 'static) {
+        self.number_lexer = Rc::new(number_lexer);
+    }
+
+    /// Returns '\0' once the input is exhausted, use `is_at_end` to detect the end of the input.
+    pub fn peek_next(&self) -> char {
         self.chars.last().cloned().unwrap_or('\0')
     }
 
-    fn consume_next(&mut self) -> char {
+    pub fn is_at_end(&self) -> bool {
+        self.chars.is_empty()
+    }
+
+    /// Peeks the character after the next one.
+    pub fn peek_second(&self) -> char {
+        self.chars.iter().rev().nth(1).cloned().unwrap_or('\0')
+    }
+
+    /// The span of the next character.
+    pub fn current_span(&self) -> Span {
+        Span {
+            line: self.cur_line,
+            col: self.cur_col,
+            end_line: self.cur_line,
+            end_col: self.cur_col,
+            file_id: self.file_id,
+        }
+    }
+
+    pub fn consume_next(&mut self) -> char {
         let cur_char = self.chars.pop().unwrap_or('\0');
         if cur_char == '\n' {
             self.cur_line += 1;
@@ -33,7 +261,7 @@ impl Lexer {
     }
 
     fn handle_alphanumeric(&mut self) {
-        let cur_span = Span { line: self.cur_line, col: self.cur_col };
+        let cur_span = self.current_span();
 
         let mut token: String = String::new();
         loop {
@@ -45,79 +273,145 @@ impl Lexer {
             break;
         }
 
-        match token.as_str() {
-            "int" => self.tokens.push(Token {
-                kind: TokenKind::Declare(Primitive::Int),
-                span: cur_span,
-            }),
-            "float" => self.tokens.push(Token {
-                kind: TokenKind::Declare(Primitive::Float),
-                span: cur_span,
-            }),
-            "bool" => self.tokens.push(Token {
-                kind: TokenKind::Declare(Primitive::Bool),
-                span: cur_span,
-            }),
-            "mut" => self.tokens.push(Token {
-                kind: TokenKind::Mut,
-                span: cur_span,
-            }),
-            "print" => self.tokens.push(Token {
-                kind: TokenKind::Print,
-                span: cur_span,
-            }),
-            "true" => self.tokens.push(Token {
-                kind: TokenKind::Literal(Literal {
-                    value: "true".to_string(),
-                    primitive: Primitive::Bool,
-                }),
-                span: cur_span,
-            }),
-            "false" => self.tokens.push(Token {
-                kind: TokenKind::Literal(Literal {
-                    value: "false".to_string(),
-                    primitive: Primitive::Bool,
-                }),
-                span: cur_span,
-            }),
-            _ => self.tokens.push(Token {
-                kind: TokenKind::Identifier(token),
-                span: cur_span,
-            }),
-        }
+        let kind = match (self.keywords.get(&token), token.as_str()) {
+            (Some(keyword), _) => keyword.clone(),
+            (None, "and") if self.word_operators => TokenKind::BinOp(BinOpKind::And),
+            (None, "or") if self.word_operators => TokenKind::BinOp(BinOpKind::Or),
+            (None, "not") if self.word_operators => TokenKind::BinOp(BinOpKind::Not),
+            (None, _) => TokenKind::Identifier(token),
+        };
+        self.tokens.push(Token {
+            kind,
+            span: cur_span,
+        });
     }
 
-    fn handle_numeric(&mut self) {
-        let cur_span = Span { line: self.cur_line, col: self.cur_col };
+    /// Lexes a number literal using the configured `NumberLexer`.
+    fn handle_numeric(&mut self) -> Result<(), CompilerError> {
+        let cur_span = self.current_span();
+        let number_lexer = Rc::clone(&self.number_lexer);
+        let literal = number_lexer.scan_number(self)?;
+        self.tokens.push(Token {
+            kind: TokenKind::Literal(literal),
+            span: cur_span,
+        });
+        Ok(())
+    }
 
-        let mut token = String::new();
+    /// Consumes an escape sequence like `\n` inside a string or char literal and returns the character it denotes.
+    fn scan_escaped(&mut self) -> Result<char, CompilerError> {
+        let cur_span = self.current_span();
+        self.consume_next();
+
+        let escaped = match self.peek_next() {
+            _ if self.is_at_end() => {
+                return Err(CompilerError::SyntaxError {
+                    message: "Unterminated escape sequence.".to_string(),
+                    span: cur_span,
+                    fixit: None,
+                });
+            }
+            'n' => '\n',
+            't' => '\t',
+            '\\' => '\\',
+            '"' => '"',
+            '\'' => '\'',
+            '0' => '\0',
+            c => {
+                return Err(CompilerError::SyntaxError {
+                    message: format!("Unknown escape sequence '\\{}'.", c),
+                    span: cur_span,
+                    fixit: None,
+                });
+            }
+        };
+        self.consume_next();
+        Ok(escaped)
+    }
+
+    fn handle_string(&mut self) -> Result<(), CompilerError> {
+        let cur_span = self.current_span();
+        self.consume_next();
+
+        let mut value = String::new();
         loop {
-            let next_char = self.peek_next();
-            if next_char.is_numeric() || next_char == '.' {
-                token.push(self.consume_next());
-                continue;
+            match self.peek_next() {
+                _ if self.is_at_end() => {
+                    return Err(CompilerError::SyntaxError {
+                        message: "Unterminated string literal.".to_string(),
+                        span: cur_span,
+                        fixit: None,
+                    });
+                }
+                '"' => break,
+                '\\' => value.push(self.scan_escaped()?),
+                _ => value.push(self.consume_next()),
             }
-            break;
         }
+        self.consume_next();
 
         self.tokens.push(Token {
-            kind: TokenKind::Literal(if token.contains('.') {
-                Literal {
-                    value: token,
-                    primitive: Primitive::Float,
-                }
-            } else {
-                Literal {
-                    value: token,
-                    primitive: Primitive::Int,
+            kind: TokenKind::Literal(Literal {
+                value,
+                primitive: Primitive::Str,
+            }),
+            span: cur_span,
+        });
+        Ok(())
+    }
+
+    fn handle_char(&mut self) -> Result<(), CompilerError> {
+        let cur_span = self.current_span();
+        self.consume_next();
+
+        let mut value = String::new();
+        loop {
+            match self.peek_next() {
+                _ if self.is_at_end() => {
+                    return Err(CompilerError::SyntaxError {
+                        message: "Unterminated char literal.".to_string(),
+                        span: cur_span,
+                        fixit: None,
+                    });
                 }
+                '\'' => break,
+                '\\' => value.push(self.scan_escaped()?),
+                _ => value.push(self.consume_next()),
+            }
+        }
+        self.consume_next();
+
+        let span = cur_span.to(&self.current_span());
+        match value.chars().count() {
+            1 => (),
+            0 => {
+                return Err(CompilerError::SyntaxError {
+                    message: "Empty char literal.".to_string(),
+                    span,
+                    fixit: None,
+                });
+            }
+            _ => {
+                return Err(CompilerError::SyntaxError {
+                    message: format!("Char literal '{}' must contain a single character.", value),
+                    span,
+                    fixit: None,
+                });
+            }
+        }
+
+        self.tokens.push(Token {
+            kind: TokenKind::Literal(Literal {
+                value,
+                primitive: Primitive::Char,
             }),
             span: cur_span,
         });
+        Ok(())
     }
 
     fn handle_boolean(&mut self) -> Result<(), CompilerError> {
-        let cur_span = Span { line: self.cur_line, col: self.cur_col };
+        let cur_span = self.current_span();
 
         let token = self.consume_next();
         match token {
@@ -129,12 +423,30 @@ impl Lexer {
                     });
                     self.consume_next();
                 }
+                '>' => {
+                    return Err(CompilerError::SyntaxError {
+                        message: "Unknown operator '=>', did you mean '>='?".to_string(),
+                        span: cur_span.clone(),
+                        fixit: Some(Box::new(FixIt {
+                            span: cur_span,
+                            len: 2,
+                            replacement: ">=".to_string(),
+                        })),
+                    });
+                }
                 _ => self.tokens.push(Token {
                     kind: TokenKind::BinOp(BinOpKind::Assign),
                     span: cur_span,
                 }),
             },
             '<' => match self.peek_next() {
+                '<' => {
+                    self.tokens.push(Token {
+                        kind: TokenKind::BinOp(BinOpKind::Shl),
+                        span: cur_span,
+                    });
+                    self.consume_next();
+                }
                 '=' => {
                     self.tokens.push(Token {
                         kind: TokenKind::BinOp(BinOpKind::Le),
@@ -148,6 +460,13 @@ impl Lexer {
                 }),
             },
             '>' => match self.peek_next() {
+                '>' => {
+                    self.tokens.push(Token {
+                        kind: TokenKind::BinOp(BinOpKind::Shr),
+                        span: cur_span,
+                    });
+                    self.consume_next();
+                }
                 '=' => {
                     self.tokens.push(Token {
                         kind: TokenKind::BinOp(BinOpKind::Ge),
@@ -168,12 +487,10 @@ impl Lexer {
                     });
                     self.consume_next();
                 }
-                _ => {
-                    return Err(CompilerError::SyntaxError {
-                        message: "Unexpected single character '&', did you mean '&&'?".to_string(),
-                        span: cur_span,
-                    });
-                }
+                _ => self.tokens.push(Token {
+                    kind: TokenKind::BinOp(BinOpKind::BitAnd),
+                    span: cur_span,
+                }),
             },
             '|' => match self.peek_next() {
                 '|' => {
@@ -183,12 +500,10 @@ impl Lexer {
                     });
                     self.consume_next();
                 }
-                _ => {
-                    return Err(CompilerError::SyntaxError {
-                        message: "Unexpected single character '|', did you mean '||'?".to_string(),
-                        span: cur_span,
-                    });
-                }
+                _ => self.tokens.push(Token {
+                    kind: TokenKind::BinOp(BinOpKind::BitOr),
+                    span: cur_span,
+                }),
             },
             '!' => {
                 match self.peek_next() {
@@ -209,6 +524,7 @@ impl Lexer {
                 return Err(CompilerError::SyntaxError {
                     message: format!("Unexpected character '{}'.", t),
                     span: cur_span,
+                    fixit: None,
                 });
             }
         }
@@ -216,8 +532,70 @@ impl Lexer {
         Ok(())
     }
 
+    /// Skips a `#!...` line at the very start of the program, so that scripts can be made executable.
+    fn skip_shebang(&mut self) {
+        if self.cur_line == 1 && self.cur_col == 1 && self.peek_next() == '#' && self.peek_second() == '!' {
+            self.skip_line_comment();
+        }
+    }
+
+    /// Skips a `//` comment up to, but not including, the end of the line.
+    fn skip_line_comment(&mut self) {
+        while !self.is_at_end() && self.peek_next() != '\n' {
+            self.consume_next();
+        }
+    }
+
+    /// Skips a `/* ... */` comment, which may contain nested block comments.
+    fn skip_block_comment(&mut self) -> Result<(), CompilerError> {
+        let cur_span = self.current_span();
+
+        let mut depth = 0;
+        loop {
+            if self.is_at_end() {
+                return Err(CompilerError::SyntaxError {
+                    message: "Unterminated block comment.".to_string(),
+                    span: cur_span,
+                    fixit: None,
+                });
+            }
+            match (self.peek_next(), self.peek_second()) {
+                ('/', '*') => depth += 1,
+                ('*', '/') => depth -= 1,
+                _ => {
+                    self.consume_next();
+                    continue;
+                }
+            }
+            self.consume_next();
+            self.consume_next();
+            if depth == 0 {
+                return Ok(());
+            }
+        }
+    }
+
     pub fn tokenize(&mut self) -> Result<(), CompilerError> {
+        self.skip_shebang();
+        let mut token_count = self.tokens.len();
         loop {
+            // Every iteration consumes exactly the characters of the token it produces, so a new token
+            // ends where the next iteration starts.
+            if self.tokens.len() > token_count {
+                let end = self.current_span();
+                let span = &mut self.tokens.last_mut().expect("a token was just pushed").span;
+                (span.end_line, span.end_col) = (end.line, end.col);
+                token_count = self.tokens.len();
+            }
+
+            // The EOF token is placed one past the last character of the input.
+            if self.is_at_end() {
+                self.tokens.push(Token {
+                    kind: TokenKind::EOF,
+                    span: self.current_span(),
+                });
+                break;
+            }
             let cur_char: char = self.peek_next();
 
             match cur_char {
@@ -227,7 +605,22 @@ impl Lexer {
                     continue;
                 }
                 c if c.is_numeric() || cur_char == '.' => {
-                    self.handle_numeric();
+                    self.handle_numeric()?;
+                    continue;
+                }
+                '_' if self.peek_second().is_numeric() => {
+                    return Err(CompilerError::SyntaxError {
+                        message: "Digit separator '_' must follow a digit.".to_string(),
+                        span: self.current_span(),
+                        fixit: None,
+                    });
+                }
+                '"' => {
+                    self.handle_string()?;
+                    continue;
+                }
+                '\'' => {
+                    self.handle_char()?;
                     continue;
                 }
                 '<' | '>' | '=' | '&' | '!' | '|' => {
@@ -236,47 +629,98 @@ impl Lexer {
                         Err(err) => return Err(err),
                     }
                 }
+                '+' | '-' | '*' | '/' if self.peek_second() == '=' => {
+                    let op = match cur_char {
+                        '+' => BinOpKind::Add,
+                        '-' => BinOpKind::Sub,
+                        '*' => BinOpKind::Mult,
+                        _ => BinOpKind::Div,
+                    };
+                    self.tokens.push(Token {
+                        kind: TokenKind::CompoundAssign(op),
+                        span: self.current_span(),
+                    });
+                    self.consume_next();
+                }
                 '+' => self.tokens.push(Token {
                     kind: TokenKind::BinOp(BinOpKind::Add),
-                    span: Span { line: self.cur_line, col: self.cur_col },
+                    span: self.current_span(),
                 }),
+                '-' if self.peek_second() == '>' => {
+                    self.tokens.push(Token {
+                        kind: TokenKind::Arrow,
+                        span: self.current_span(),
+                    });
+                    self.consume_next();
+                }
                 '-' => self.tokens.push(Token {
                     kind: TokenKind::BinOp(BinOpKind::Sub),
-                    span: Span { line: self.cur_line, col: self.cur_col },
+                    span: self.current_span(),
                 }),
                 '*' => self.tokens.push(Token {
                     kind: TokenKind::BinOp(BinOpKind::Mult),
-                    span: Span { line: self.cur_line, col: self.cur_col },
+                    span: self.current_span(),
                 }),
+                '/' if self.peek_second() == '/' => {
+                    self.skip_line_comment();
+                    continue;
+                }
+                '/' if self.peek_second() == '*' => {
+                    self.skip_block_comment()?;
+                    continue;
+                }
                 '/' => self.tokens.push(Token {
                     kind: TokenKind::BinOp(BinOpKind::Div),
-                    span: Span { line: self.cur_line, col: self.cur_col },
+                    span: self.current_span(),
+                }),
+                '%' => self.tokens.push(Token {
+                    kind: TokenKind::BinOp(BinOpKind::Mod),
+                    span: self.current_span(),
+                }),
+                '^' => self.tokens.push(Token {
+                    kind: TokenKind::BinOp(BinOpKind::Pow),
+                    span: self.current_span(),
+                }),
+                '~' => self.tokens.push(Token {
+                    kind: TokenKind::BinOp(BinOpKind::BitNot),
+                    span: self.current_span(),
                 }),
                 '(' => self.tokens.push(Token {
                     kind: TokenKind::LParen,
-                    span: Span { line: self.cur_line, col: self.cur_col },
+                    span: self.current_span(),
                 }),
                 ')' => self.tokens.push(Token {
                     kind: TokenKind::RParen,
-                    span: Span { line: self.cur_line, col: self.cur_col },
+                    span: self.current_span(),
+                }),
+                '{' => self.tokens.push(Token {
+                    kind: TokenKind::LBrace,
+                    span: self.current_span(),
+                }),
+                '}' => self.tokens.push(Token {
+                    kind: TokenKind::RBrace,
+                    span: self.current_span(),
+                }),
+                ',' => self.tokens.push(Token {
+                    kind: TokenKind::Comma,
+                    span: self.current_span(),
                 }),
                 ';' => self.tokens.push(Token {
                     kind: TokenKind::EOS,
-                    span: Span { line: self.cur_line, col: self.cur_col },
+                    span: self.current_span(),
                 }),
                 '\0' => {
-                    self.tokens.push(Token {
-                        kind: TokenKind::EOF,
-                        span: Span { line: self.cur_line, col: self.cur_col },
-
+                    return Err(CompilerError::SyntaxError {
+                        message: "Unexpected null character.".to_string(),
+                        span: self.current_span(),
+                        fixit: None,
                     });
-                    self.consume_next();
-                    break;
                 }
                 _ => {
                     return Err(CompilerError::SyntaxError {
                         message: format!("Unexpected character '{}'.", cur_char),
-                        span: Span { line: self.cur_line, col: self.cur_col },
+                        span: self.current_span(),
+                        fixit: None,
                     });
                 }
             }
@@ -296,13 +740,13 @@ mod tests {
     use super::*;
 
     fn tokenize(input: &str) -> Result<Vec<TokenKind>, CompilerError> {
-        let mut lexer = Lexer::new(&(input.to_owned() + "\0"));
+        let mut lexer = Lexer::new(input);
         lexer.tokenize()?;
         Ok(lexer.get_tokens().iter().map(|t| t.kind.clone()).collect())
     }
 
     fn get_token_spans(input: &str) -> Result<Vec<Span>, CompilerError> {
-        let mut lexer = Lexer::new(&(input.to_owned() + "\0"));
+        let mut lexer = Lexer::new(input);
         lexer.tokenize()?;
         Ok(lexer.get_tokens().iter().map(|t| t.span.clone()).collect())
     }
@@ -345,6 +789,56 @@ mod tests {
         );
     }
 
+    /// Accepts ',' as decimal separator, e.g. `3,14`.
+    struct CommaDecimalLexer;
+
+    impl NumberLexer for CommaDecimalLexer {
+        fn scan_number(&self, lexer: &mut Lexer) -> Result<Literal, CompilerError> {
+            let mut value = String::new();
+            let mut primitive = Primitive::Int;
+            while lexer.peek_next().is_ascii_digit() || (lexer.peek_next() == ',' && primitive == Primitive::Int) {
+                match lexer.consume_next() {
+                    ',' => {
+                        value.push('.');
+                        primitive = Primitive::Float;
+                    }
+                    digit => value.push(digit),
+                }
+            }
+            Ok(Literal { value, primitive })
+        }
+    }
+
+    #[test]
+    fn test_custom_number_lexer() {
+        let mut lexer = Lexer::new("float pi = 3,14;\nint a = 42;");
+        lexer.number_lexer(CommaDecimalLexer);
+        lexer.tokenize().unwrap();
+        let tokens: Vec<TokenKind> = lexer.get_tokens().iter().map(|token| token.kind.clone()).collect();
+        assert_eq!(
+            tokens,
+            vec![
+                TokenKind::Declare(Primitive::Float),
+                TokenKind::Identifier("pi".into()),
+                TokenKind::BinOp(BinOpKind::Assign),
+                TokenKind::Literal(Literal {
+                    value: "3.14".to_string(),
+                    primitive: Primitive::Float
+                }),
+                TokenKind::EOS,
+                TokenKind::Declare(Primitive::Int),
+                TokenKind::Identifier("a".into()),
+                TokenKind::BinOp(BinOpKind::Assign),
+                TokenKind::Literal(Literal {
+                    value: "42".to_string(),
+                    primitive: Primitive::Int
+                }),
+                TokenKind::EOS,
+                TokenKind::EOF,
+            ]
+        );
+    }
+
     #[test]
     fn test_print_statement() {
         let tokens = tokenize("print(x);").unwrap();
@@ -362,13 +856,29 @@ mod tests {
     }
 
     #[test]
-    fn test_simple_expression() {
-        let tokens = tokenize("int a = 3 + 5;").unwrap();
+    fn test_eprint_statement() {
+        let tokens = tokenize("eprint(x);").unwrap();
         assert_eq!(
             tokens,
             vec![
-                TokenKind::Declare(Primitive::Int),
-                TokenKind::Identifier("a".into()),
+                TokenKind::EPrint,
+                TokenKind::LParen,
+                TokenKind::Identifier("x".into()),
+                TokenKind::RParen,
+                TokenKind::EOS,
+                TokenKind::EOF,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_simple_expression() {
+        let tokens = tokenize("int a = 3 + 5;").unwrap();
+        assert_eq!(
+            tokens,
+            vec![
+                TokenKind::Declare(Primitive::Int),
+                TokenKind::Identifier("a".into()),
                 TokenKind::BinOp(BinOpKind::Assign),
                 TokenKind::Literal(Literal {
                     value: "3".to_string(),
@@ -412,6 +922,139 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_modulo() {
+        let tokens = tokenize("a%2").unwrap();
+        assert_eq!(
+            tokens,
+            vec![
+                TokenKind::Identifier("a".into()),
+                TokenKind::BinOp(BinOpKind::Mod),
+                TokenKind::Literal(Literal {
+                    value: "2".to_string(),
+                    primitive: Primitive::Int
+                }),
+                TokenKind::EOF,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_digit_separators() {
+        let tokens = tokenize("1_000_000 3.141_592 1_0.5").unwrap();
+        assert_eq!(
+            tokens,
+            vec![
+                TokenKind::Literal(Literal {
+                    value: "1000000".to_string(),
+                    primitive: Primitive::Int
+                }),
+                TokenKind::Literal(Literal {
+                    value: "3.141592".to_string(),
+                    primitive: Primitive::Float
+                }),
+                TokenKind::Literal(Literal {
+                    value: "10.5".to_string(),
+                    primitive: Primitive::Float
+                }),
+                TokenKind::EOF,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_invalid_digit_separators() {
+        let result = tokenize("int a = _5;");
+        assert!(matches!(
+            result,
+            Err(CompilerError::SyntaxError { message, span, .. }) if message == "Digit separator '_' must follow a digit." && span.col == 9
+        ));
+
+        let result = tokenize("float a = 1._5;");
+        assert!(matches!(
+            result,
+            Err(CompilerError::SyntaxError { message, span, .. }) if message == "Digit separator '_' must follow a digit." && span.col == 13
+        ));
+
+        let result = tokenize("int a = 5_;");
+        assert!(matches!(
+            result,
+            Err(CompilerError::SyntaxError { message, span, .. }) if message == "Digit separator '_' must be followed by a digit." && span.col == 10
+        ));
+
+        let result = tokenize("int a = 5__0;");
+        assert!(matches!(
+            result,
+            Err(CompilerError::SyntaxError { message, span, .. }) if message == "Consecutive digit separators in numeric literal." && span.col == 11
+        ));
+    }
+
+    #[test]
+    fn test_float_literal_edge_forms() {
+        let tokens = tokenize(".5 5.").unwrap();
+        assert_eq!(
+            tokens,
+            vec![
+                TokenKind::Literal(Literal {
+                    value: ".5".to_string(),
+                    primitive: Primitive::Float
+                }),
+                TokenKind::Literal(Literal {
+                    value: "5.".to_string(),
+                    primitive: Primitive::Float
+                }),
+                TokenKind::EOF,
+            ]
+        );
+
+        let result = tokenize("float a = 1.2.3;");
+        assert!(matches!(
+            result,
+            Err(CompilerError::SyntaxError { message, span, .. }) if message == "Invalid float literal: multiple decimal points." && span.col == 14
+        ));
+    }
+
+    #[test]
+    fn test_scientific_notation() {
+        let tokens = tokenize("6.02e23 1.5e-10 2E+3 1e5").unwrap();
+        let float = |value: &str| {
+            TokenKind::Literal(Literal {
+                value: value.to_string(),
+                primitive: Primitive::Float,
+            })
+        };
+        assert_eq!(
+            tokens,
+            vec![float("6.02e23"), float("1.5e-10"), float("2E+3"), float("1e5"), TokenKind::EOF]
+        );
+
+        let result = tokenize("float a = 1e;");
+        assert!(matches!(
+            result,
+            Err(CompilerError::SyntaxError { message, span, .. }) if message == "Expected digits after the exponent marker of float literal." && span.col == 12
+        ));
+        assert!(tokenize("float a = 1e-;").is_err());
+    }
+
+    #[test]
+    fn test_compound_assign() {
+        let tokens = tokenize("a += 1; a -= 1; a *= 2; a /= 2; a = 1;").unwrap();
+        let operators: Vec<TokenKind> = tokens.into_iter().skip(1).step_by(4).collect();
+        assert_eq!(
+            operators,
+            vec![
+                TokenKind::CompoundAssign(BinOpKind::Add),
+                TokenKind::CompoundAssign(BinOpKind::Sub),
+                TokenKind::CompoundAssign(BinOpKind::Mult),
+                TokenKind::CompoundAssign(BinOpKind::Div),
+                TokenKind::BinOp(BinOpKind::Assign),
+            ]
+        );
+        assert_eq!(tokenize("a=-1").unwrap()[2], TokenKind::BinOp(BinOpKind::Sub));
+        let spans = get_token_spans("a /= 2;").unwrap();
+        assert_eq!(spans[2], Span { line: 1, col: 6, end_line: 1, end_col: 7, file_id: None });
+    }
+
     #[test]
     fn test_parentheses_and_mult_div() {
         let tokens = tokenize("(2 * 4) / .5;").unwrap();
@@ -503,6 +1146,73 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_bitwise_operators() {
+        let tokens = tokenize("int a = ~b & c | d << 1 >> e;").unwrap();
+        assert_eq!(
+            tokens[3..12],
+            [
+                TokenKind::BinOp(BinOpKind::BitNot),
+                TokenKind::Identifier("b".into()),
+                TokenKind::BinOp(BinOpKind::BitAnd),
+                TokenKind::Identifier("c".into()),
+                TokenKind::BinOp(BinOpKind::BitOr),
+                TokenKind::Identifier("d".into()),
+                TokenKind::BinOp(BinOpKind::Shl),
+                TokenKind::Literal(Literal {
+                    value: "1".to_string(),
+                    primitive: Primitive::Int
+                }),
+                TokenKind::BinOp(BinOpKind::Shr),
+            ]
+        );
+
+        // Doubled '&' and '|' are still the boolean operators.
+        let tokens = tokenize("a&&b||c&d").unwrap();
+        assert_eq!(
+            tokens,
+            vec![
+                TokenKind::Identifier("a".into()),
+                TokenKind::BinOp(BinOpKind::And),
+                TokenKind::Identifier("b".into()),
+                TokenKind::BinOp(BinOpKind::Or),
+                TokenKind::Identifier("c".into()),
+                TokenKind::BinOp(BinOpKind::BitAnd),
+                TokenKind::Identifier("d".into()),
+                TokenKind::EOF,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_hex_literal() {
+        let hex = |input: &str| match tokenize(input)?.remove(0) {
+            TokenKind::Literal(literal) => Ok(literal),
+            t => panic!("Expected a literal, found {:?}.", t),
+        };
+        assert_eq!(
+            hex("0xF0").unwrap(),
+            Literal {
+                value: "240".to_string(),
+                primitive: Primitive::Int
+            }
+        );
+        assert_eq!(hex("0XfF").unwrap().value, "255");
+        assert_eq!(hex("0x0").unwrap().value, "0");
+
+        let spans = get_token_spans("0x1F >> 4").unwrap();
+        assert_eq!(spans[0], Span { line: 1, col: 1, end_line: 1, end_col: 5, file_id: None });
+
+        let result = hex("0x;");
+        assert!(matches!(
+            result,
+            Err(CompilerError::SyntaxError { message, span, .. }) if message == "Expected hex digits after '0x'." && span.col == 1
+        ));
+
+        let result = hex(&format!("0x1{}", "0".repeat(32)));
+        assert!(matches!(result, Err(CompilerError::LiteralOutOfRange { primitive: Primitive::Int, .. })));
+    }
+
     #[test]
     fn test_number_comparison() {
         let tokens = tokenize("int a = 1 > 1 && 2 < 2 && 3 >= 3 && 4 <= 4;").unwrap();
@@ -595,6 +1305,352 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_custom_keywords() {
+        let mut keywords = default_keywords();
+        keywords.remove("int");
+        keywords.insert("entier".to_string(), TokenKind::Declare(Primitive::Int));
+        keywords.insert(
+            "vrai".to_string(),
+            TokenKind::Literal(Literal {
+                value: "true".to_string(),
+                primitive: Primitive::Bool,
+            }),
+        );
+
+        let mut lexer = Lexer::new_with_keywords("entier int = 1;\nbool b = vrai;", keywords);
+        lexer.tokenize().unwrap();
+        let tokens: Vec<TokenKind> = lexer.get_tokens().iter().map(|t| t.kind.clone()).collect();
+        assert_eq!(
+            tokens,
+            vec![
+                TokenKind::Declare(Primitive::Int),
+                TokenKind::Identifier("int".into()),
+                TokenKind::BinOp(BinOpKind::Assign),
+                TokenKind::Literal(Literal {
+                    value: "1".to_string(),
+                    primitive: Primitive::Int
+                }),
+                TokenKind::EOS,
+                TokenKind::Declare(Primitive::Bool),
+                TokenKind::Identifier("b".into()),
+                TokenKind::BinOp(BinOpKind::Assign),
+                TokenKind::Literal(Literal {
+                    value: "true".to_string(),
+                    primitive: Primitive::Bool
+                }),
+                TokenKind::EOS,
+                TokenKind::EOF,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_swap_statement() {
+        let tokens = tokenize("swap(a, b);").unwrap();
+        assert_eq!(
+            tokens,
+            vec![
+                TokenKind::Swap,
+                TokenKind::LParen,
+                TokenKind::Identifier("a".into()),
+                TokenKind::Comma,
+                TokenKind::Identifier("b".into()),
+                TokenKind::RParen,
+                TokenKind::EOS,
+                TokenKind::EOF,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_if_else() {
+        assert_eq!(tokenize("for").unwrap(), vec![TokenKind::For, TokenKind::EOF]);
+
+        let tokens = tokenize("if (a) { } else {}").unwrap();
+        assert_eq!(
+            tokens,
+            vec![
+                TokenKind::If,
+                TokenKind::LParen,
+                TokenKind::Identifier("a".into()),
+                TokenKind::RParen,
+                TokenKind::LBrace,
+                TokenKind::RBrace,
+                TokenKind::Else,
+                TokenKind::LBrace,
+                TokenKind::RBrace,
+                TokenKind::EOF,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_function_declaration() {
+        let tokens = tokenize("fn f(int x) -> int { return x-1; }").unwrap();
+        assert_eq!(
+            tokens,
+            vec![
+                TokenKind::Fn,
+                TokenKind::Identifier("f".into()),
+                TokenKind::LParen,
+                TokenKind::Declare(Primitive::Int),
+                TokenKind::Identifier("x".into()),
+                TokenKind::RParen,
+                TokenKind::Arrow,
+                TokenKind::Declare(Primitive::Int),
+                TokenKind::LBrace,
+                TokenKind::Return,
+                TokenKind::Identifier("x".into()),
+                TokenKind::BinOp(BinOpKind::Sub),
+                TokenKind::Literal(Literal {
+                    value: "1".into(),
+                    primitive: Primitive::Int,
+                }),
+                TokenKind::EOS,
+                TokenKind::RBrace,
+                TokenKind::EOF,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_string_literal() {
+        let tokens = tokenize("string s = \"hello world\";\nprint(s);").unwrap();
+        assert_eq!(
+            tokens[..4],
+            [
+                TokenKind::Declare(Primitive::Str),
+                TokenKind::Identifier("s".into()),
+                TokenKind::BinOp(BinOpKind::Assign),
+                TokenKind::Literal(Literal {
+                    value: "hello world".to_string(),
+                    primitive: Primitive::Str
+                }),
+            ]
+        );
+
+        let spans = get_token_spans("string s = \"a\nb\"; s;").unwrap();
+        assert_eq!(
+            spans,
+            vec![
+                Span { line: 1, col: 1, end_line: 1, end_col: 7, file_id: None },   // string
+                Span { line: 1, col: 8, end_line: 1, end_col: 9, file_id: None },   // s
+                Span { line: 1, col: 10, end_line: 1, end_col: 11, file_id: None }, // =
+                Span { line: 1, col: 12, end_line: 2, end_col: 3, file_id: None },  // "a\nb"
+                Span { line: 2, col: 3, end_line: 2, end_col: 4, file_id: None },   // ;
+                Span { line: 2, col: 5, end_line: 2, end_col: 6, file_id: None },   // s
+                Span { line: 2, col: 6, end_line: 2, end_col: 7, file_id: None },   // ;
+                Span { line: 2, col: 7, end_line: 2, end_col: 7, file_id: None },   // EOF
+            ]
+        );
+    }
+
+    fn string_value(input: &str) -> Result<String, CompilerError> {
+        match tokenize(input)?.remove(0) {
+            TokenKind::Literal(literal) => Ok(literal.value),
+            t => panic!("Expected a literal, found {:?}.", t),
+        }
+    }
+
+    #[test]
+    fn test_string_escape_sequences() {
+        assert_eq!(string_value(r#""a\tb\n""#).unwrap(), "a\tb\n");
+        assert_eq!(string_value(r#""back\\slash""#).unwrap(), "back\\slash");
+        assert_eq!(string_value(r#""say \"hi\"""#).unwrap(), "say \"hi\"");
+        assert_eq!(string_value(r#""nul\0""#).unwrap(), "nul\0");
+
+        let result = string_value(r#""a\qb""#);
+        assert!(matches!(
+            result,
+            Err(CompilerError::SyntaxError { message, span, .. }) if message == "Unknown escape sequence '\\q'." && span.col == 3
+        ));
+    }
+
+    #[test]
+    fn test_char_literal() {
+        let tokens = tokenize("char c = 'a';").unwrap();
+        assert_eq!(
+            tokens[..4],
+            [
+                TokenKind::Declare(Primitive::Char),
+                TokenKind::Identifier("c".into()),
+                TokenKind::BinOp(BinOpKind::Assign),
+                TokenKind::Literal(Literal {
+                    value: "a".to_string(),
+                    primitive: Primitive::Char
+                }),
+            ]
+        );
+        assert_eq!(string_value(r"'\n'").unwrap(), "\n");
+        assert_eq!(string_value(r"'\t'").unwrap(), "\t");
+        assert_eq!(string_value(r"'\\'").unwrap(), "\\");
+        assert_eq!(string_value(r"'\''").unwrap(), "'");
+        assert_eq!(string_value("'\"'").unwrap(), "\"");
+
+        let spans = get_token_spans("c == '\\n';").unwrap();
+        assert_eq!(spans[2], Span { line: 1, col: 6, end_line: 1, end_col: 10, file_id: None });
+    }
+
+    #[test]
+    fn test_invalid_char_literal() {
+        let result = tokenize("char c = '';");
+        assert!(matches!(
+            result,
+            Err(CompilerError::SyntaxError { message, span, .. })
+                if message == "Empty char literal." && span == Span { line: 1, col: 10, end_line: 1, end_col: 12, file_id: None }
+        ));
+
+        let result = tokenize("char c = 'ab';");
+        assert!(matches!(
+            result,
+            Err(CompilerError::SyntaxError { message, span, .. })
+                if message == "Char literal 'ab' must contain a single character." && span == Span { line: 1, col: 10, end_line: 1, end_col: 14, file_id: None }
+        ));
+
+        let result = tokenize("char c = 'a");
+        assert!(matches!(
+            result,
+            Err(CompilerError::SyntaxError { message, .. }) if message == "Unterminated char literal."
+        ));
+    }
+
+    #[test]
+    fn test_unterminated_string_literal() {
+        let result = tokenize("int a = 1;\nstring s = \"hello;");
+        assert!(matches!(
+            result,
+            Err(CompilerError::SyntaxError { message, span, .. }) if message == "Unterminated string literal." && span == Span { line: 2, col: 12, end_line: 2, end_col: 12, file_id: None }
+        ));
+    }
+
+    #[test]
+    fn test_shebang_is_skipped() {
+        let tokens = tokenize("#!/usr/bin/env lang\nint a = 1;").unwrap();
+        assert_eq!(tokens, tokenize("int a = 1;").unwrap());
+
+        let spans = get_token_spans("#!/usr/bin/env lang\nint a = 1;").unwrap();
+        assert_eq!(spans[0], Span { line: 2, col: 1, end_line: 2, end_col: 4, file_id: None });
+        assert_eq!(spans[3], Span { line: 2, col: 9, end_line: 2, end_col: 10, file_id: None });
+
+        // A shebang is only allowed on the first line.
+        assert!(tokenize("int a = 1;\n#!/usr/bin/env lang").is_err());
+    }
+
+    #[test]
+    fn test_line_comments() {
+        let tokens = tokenize("int a = 5; // set a\nint b = 6;").unwrap();
+        assert_eq!(tokens, tokenize("int a = 5;\nint b = 6;").unwrap());
+
+        let spans = get_token_spans("// header\nint a = 5 / 2; // half\n// trailing").unwrap();
+        assert_eq!(
+            spans,
+            vec![
+                Span { line: 2, col: 1, end_line: 2, end_col: 4, file_id: None },   // int
+                Span { line: 2, col: 5, end_line: 2, end_col: 6, file_id: None },   // a
+                Span { line: 2, col: 7, end_line: 2, end_col: 8, file_id: None },   // =
+                Span { line: 2, col: 9, end_line: 2, end_col: 10, file_id: None },  // 5
+                Span { line: 2, col: 11, end_line: 2, end_col: 12, file_id: None }, // /
+                Span { line: 2, col: 13, end_line: 2, end_col: 14, file_id: None }, // 2
+                Span { line: 2, col: 14, end_line: 2, end_col: 15, file_id: None }, // ;
+                Span { line: 3, col: 12, end_line: 3, end_col: 12, file_id: None }, // EOF
+            ]
+        );
+    }
+
+    #[test]
+    fn test_block_comments() {
+        let tokens = tokenize("int a /* the answer */ = 42;").unwrap();
+        assert_eq!(tokens, tokenize("int a = 42;").unwrap());
+
+        let spans = get_token_spans("/* first\n   second */ int a = 1;").unwrap();
+        assert_eq!(spans[0], Span { line: 2, col: 14, end_line: 2, end_col: 17, file_id: None });
+
+        let tokens = tokenize("int a = /* outer /* inner */ still comment */ 1;").unwrap();
+        assert_eq!(tokens, tokenize("int a = 1;").unwrap());
+    }
+
+    #[test]
+    fn test_unterminated_block_comment() {
+        let result = tokenize("int a = 1;\n  /* outer /* inner */ a = 2;");
+        assert!(matches!(
+            result,
+            Err(CompilerError::SyntaxError { message, span, .. }) if message == "Unterminated block comment." && span == Span { line: 2, col: 3, end_line: 2, end_col: 3, file_id: None }
+        ));
+    }
+
+    #[test]
+    fn test_greater_equal_typo() {
+        let err = tokenize("bool b = a => 2;").unwrap_err();
+        assert!(matches!(
+            &err,
+            CompilerError::SyntaxError { message, span, .. } if message == "Unknown operator '=>', did you mean '>='?" && span.col == 12
+        ));
+        assert_eq!(
+            err.fixit(),
+            Some(&FixIt {
+                span: Span { line: 1, col: 12, end_line: 1, end_col: 12, file_id: None },
+                len: 2,
+                replacement: ">=".to_string(),
+            })
+        );
+    }
+
+    #[test]
+    fn test_embedded_null() {
+        let result = tokenize("int a = 1;\n\0int b = 2;");
+        assert!(matches!(
+            result,
+            Err(CompilerError::SyntaxError { message, span, .. }) if message == "Unexpected null character." && span == Span { line: 2, col: 1, end_line: 2, end_col: 1, file_id: None }
+        ));
+
+        // A trailing null is no longer needed to mark the end, so it is rejected as well.
+        let result = tokenize("int a = 1;\0");
+        assert!(matches!(result, Err(CompilerError::SyntaxError { span, .. }) if span.col == 11));
+
+        // The real end of the input produces EOF, one past the last column.
+        let spans = get_token_spans("int a = 1;").unwrap();
+        assert_eq!(spans.last(), Some(&Span { line: 1, col: 11, end_line: 1, end_col: 11, file_id: None }));
+        assert_eq!(tokenize("").unwrap(), vec![TokenKind::EOF]);
+    }
+
+    #[test]
+    fn test_word_operators() {
+        let mut lexer = Lexer::new("a and not b or c");
+        lexer.word_operators(true);
+        lexer.tokenize().unwrap();
+        let tokens: Vec<TokenKind> = lexer.get_tokens().iter().map(|t| t.kind.clone()).collect();
+        assert_eq!(
+            tokens,
+            vec![
+                TokenKind::Identifier("a".into()),
+                TokenKind::BinOp(BinOpKind::And),
+                TokenKind::BinOp(BinOpKind::Not),
+                TokenKind::Identifier("b".into()),
+                TokenKind::BinOp(BinOpKind::Or),
+                TokenKind::Identifier("c".into()),
+                TokenKind::EOF,
+            ]
+        );
+
+        // Without the flag the words are plain identifiers.
+        let tokens = tokenize("a and b").unwrap();
+        assert_eq!(tokens[1], TokenKind::Identifier("and".into()));
+    }
+
+    #[test]
+    fn test_mixed_indentation() {
+        let warnings = check_mixed_indentation("int a = 1;\n\t print(a);\n    print(a);\n\t\tprint(a);");
+        assert_eq!(
+            warnings,
+            vec![CompilerWarning::MixedIndentation {
+                span: Span { line: 2, col: 1, end_line: 2, end_col: 1, file_id: None }
+            }]
+        );
+
+        assert!(check_mixed_indentation("int a = 1;\n\tprint(a);\n\t\tprint(a);\nprint(a \t+ 1);").is_empty());
+    }
+
     #[test]
     fn test_invalid_character() {
         let result = tokenize("int a = 5 $ 2;");
@@ -607,24 +1663,24 @@ mod tests {
         assert_eq!(
             spans,
             vec![
-                Span { line: 1, col: 1 },    // int
-                Span { line: 1, col: 5 },    // a
-                Span { line: 1, col: 7 },    // =
-                Span { line: 1, col: 9 },    // 5
-                Span { line: 1, col: 11 },   // -
-                Span { line: 1, col: 13 },   // 0.2
-                Span { line: 1, col: 16 },   // ;
-                Span { line: 2, col: 1 },    // bool
-                Span { line: 2, col: 6 },    // b
-                Span { line: 2, col: 8 },    // =
-                Span { line: 2, col: 10 },   // !
-                Span { line: 2, col: 11 },   // (
-                Span { line: 2, col: 12 },   // a
-                Span { line: 2, col: 14 },   // >=
-                Span { line: 2, col: 17 },   // 17
-                Span { line: 2, col: 19 },   // )
-                Span { line: 2, col: 20 },   // ;
-                Span { line: 2, col: 21 },   // EOF
+                Span { line: 1, col: 1, end_line: 1, end_col: 4, file_id: None },   // int
+                Span { line: 1, col: 5, end_line: 1, end_col: 6, file_id: None },   // a
+                Span { line: 1, col: 7, end_line: 1, end_col: 8, file_id: None },   // =
+                Span { line: 1, col: 9, end_line: 1, end_col: 10, file_id: None },  // 5
+                Span { line: 1, col: 11, end_line: 1, end_col: 12, file_id: None }, // -
+                Span { line: 1, col: 13, end_line: 1, end_col: 16, file_id: None }, // 0.2
+                Span { line: 1, col: 16, end_line: 1, end_col: 17, file_id: None }, // ;
+                Span { line: 2, col: 1, end_line: 2, end_col: 5, file_id: None },   // bool
+                Span { line: 2, col: 6, end_line: 2, end_col: 7, file_id: None },   // b
+                Span { line: 2, col: 8, end_line: 2, end_col: 9, file_id: None },   // =
+                Span { line: 2, col: 10, end_line: 2, end_col: 11, file_id: None }, // !
+                Span { line: 2, col: 11, end_line: 2, end_col: 12, file_id: None }, // (
+                Span { line: 2, col: 12, end_line: 2, end_col: 13, file_id: None }, // a
+                Span { line: 2, col: 14, end_line: 2, end_col: 16, file_id: None }, // >=
+                Span { line: 2, col: 17, end_line: 2, end_col: 19, file_id: None }, // 17
+                Span { line: 2, col: 19, end_line: 2, end_col: 20, file_id: None }, // )
+                Span { line: 2, col: 20, end_line: 2, end_col: 21, file_id: None }, // ;
+                Span { line: 2, col: 21, end_line: 2, end_col: 21, file_id: None }, // EOF
             ]
         );
     }
@@ -635,24 +1691,24 @@ mod tests {
         assert_eq!(
             spans,
             vec![
-                Span { line: 1, col: 1 },   // int
-                Span { line: 1, col: 5 },   // a
-                Span { line: 1, col: 6 },   // =
-                Span { line: 1, col: 7 },   // 5
-                Span { line: 1, col: 8 },   // -
-                Span { line: 1, col: 9 },   // 0.2
-                Span { line: 1, col: 12 },  // ;
-                Span { line: 2, col: 1 },   // bool
-                Span { line: 2, col: 6 },   // b
-                Span { line: 2, col: 7 },   // =
-                Span { line: 2, col: 8 },   // !
-                Span { line: 2, col: 9 },   // (
-                Span { line: 2, col: 10 },  // a
-                Span { line: 2, col: 11 },  // >=
-                Span { line: 2, col: 13 },  // 17
-                Span { line: 2, col: 15 },  // )
-                Span { line: 2, col: 16 },  // ;
-                Span { line: 2, col: 17 },  // EOF
+                Span { line: 1, col: 1, end_line: 1, end_col: 4, file_id: None },   // int
+                Span { line: 1, col: 5, end_line: 1, end_col: 6, file_id: None },   // a
+                Span { line: 1, col: 6, end_line: 1, end_col: 7, file_id: None },   // =
+                Span { line: 1, col: 7, end_line: 1, end_col: 8, file_id: None },   // 5
+                Span { line: 1, col: 8, end_line: 1, end_col: 9, file_id: None },   // -
+                Span { line: 1, col: 9, end_line: 1, end_col: 12, file_id: None },  // 0.2
+                Span { line: 1, col: 12, end_line: 1, end_col: 13, file_id: None }, // ;
+                Span { line: 2, col: 1, end_line: 2, end_col: 5, file_id: None },   // bool
+                Span { line: 2, col: 6, end_line: 2, end_col: 7, file_id: None },   // b
+                Span { line: 2, col: 7, end_line: 2, end_col: 8, file_id: None },   // =
+                Span { line: 2, col: 8, end_line: 2, end_col: 9, file_id: None },   // !
+                Span { line: 2, col: 9, end_line: 2, end_col: 10, file_id: None },  // (
+                Span { line: 2, col: 10, end_line: 2, end_col: 11, file_id: None }, // a
+                Span { line: 2, col: 11, end_line: 2, end_col: 13, file_id: None }, // >=
+                Span { line: 2, col: 13, end_line: 2, end_col: 15, file_id: None }, // 17
+                Span { line: 2, col: 15, end_line: 2, end_col: 16, file_id: None }, // )
+                Span { line: 2, col: 16, end_line: 2, end_col: 17, file_id: None }, // ;
+                Span { line: 2, col: 17, end_line: 2, end_col: 17, file_id: None }, // EOF
             ]
         );
     }
@@ -663,20 +1719,20 @@ mod tests {
         assert_eq!(
             spans,
             vec![
-                Span { line: 1, col: 1 },   // int
-                Span { line: 1, col: 5 },   // a
-                Span { line: 1, col: 7 },   // =
-                Span { line: 1, col: 9 },   // -
-                Span { line: 1, col: 10 },  // 5
-                Span { line: 1, col: 12 },  // +
-                Span { line: 2, col: 2 },   // 7
-                Span { line: 2, col: 3 },   // ;
-                Span { line: 4, col: 1 },   // bool
-                Span { line: 5, col: 1 },   // b
-                Span { line: 5, col: 3 },   // =
-                Span { line: 5, col: 5 },   // false
-                Span { line: 5, col: 10 },  // ;
-                Span { line: 5, col: 11 },  // EOF
+                Span { line: 1, col: 1, end_line: 1, end_col: 4, file_id: None },   // int
+                Span { line: 1, col: 5, end_line: 1, end_col: 6, file_id: None },   // a
+                Span { line: 1, col: 7, end_line: 1, end_col: 8, file_id: None },   // =
+                Span { line: 1, col: 9, end_line: 1, end_col: 10, file_id: None },  // -
+                Span { line: 1, col: 10, end_line: 1, end_col: 11, file_id: None }, // 5
+                Span { line: 1, col: 12, end_line: 1, end_col: 13, file_id: None }, // +
+                Span { line: 2, col: 2, end_line: 2, end_col: 3, file_id: None },   // 7
+                Span { line: 2, col: 3, end_line: 2, end_col: 4, file_id: None },   // ;
+                Span { line: 4, col: 1, end_line: 4, end_col: 5, file_id: None },   // bool
+                Span { line: 5, col: 1, end_line: 5, end_col: 2, file_id: None },   // b
+                Span { line: 5, col: 3, end_line: 5, end_col: 4, file_id: None },   // =
+                Span { line: 5, col: 5, end_line: 5, end_col: 10, file_id: None },  // false
+                Span { line: 5, col: 10, end_line: 5, end_col: 11, file_id: None }, // ;
+                Span { line: 5, col: 11, end_line: 5, end_col: 11, file_id: None }, // EOF
             ]
         );
     }
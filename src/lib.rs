@@ -0,0 +1,44 @@
+//! Compiler for a small C-like arithmetic language: the source is lexed into tokens, parsed into an
+//! AST and semantically checked, after which it can be interpreted or passed to a backend.
+
+pub mod backend;
+pub mod diagnostic;
+pub mod errors;
+pub mod interpreter;
+pub mod lexer;
+pub mod optimize;
+pub mod outline;
+pub mod parser;
+pub mod pipeline;
+pub mod printer;
+pub mod repl;
+pub mod schemas;
+pub mod semantic;
+pub mod source_map;
+pub mod token_stream;
+
+pub use errors::{CompilerError, CompilerWarning};
+pub use interpreter::Interpreter;
+pub use lexer::Lexer;
+pub use parser::Parser;
+pub use schemas::*;
+pub use semantic::SemanticAnalyser;
+
+/// Lexes, parses and semantically checks the source, returning the checked AST or the first error.
+pub fn compile_str(source: &str) -> Result<Ast, CompilerError> {
+    compile_str_with_int_width(source, IntWidth::I64)
+}
+
+/// Like `compile_str`, but checks the int literals against the given width.
+pub fn compile_str_with_int_width(source: &str, int_width: IntWidth) -> Result<Ast, CompilerError> {
+    let mut lexer = Lexer::new(source);
+    lexer.tokenize()?;
+
+    let mut parser = Parser::from_slice(lexer.get_tokens());
+    parser.parse()?;
+
+    let mut analyser = SemanticAnalyser::new(parser.into_tree());
+    analyser.int_width(int_width);
+    analyser.check()?;
+    Ok(analyser.into_ast())
+}
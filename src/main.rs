@@ -1,46 +1,128 @@
-mod lexer;
-mod parser;
-mod semantic;
-mod schemas;
-mod errors;
-
-use crate::{errors::CompilerError, lexer::*, parser::Parser, semantic::SemanticAnalyser};
+use arith_compiler::{
+    diagnostic::Diagnostic,
+    interpreter::{DEEP_MAX_CALL_DEPTH, STACK_SIZE},
+    repl::Repl,
+    CompilerError, IntWidth, Interpreter, Lexer, Parser, SemanticAnalyser,
+};
+use std::io::{IsTerminal, Read};
 
+/// Compiles and runs the program at the path given as argument, or the one read from stdin. With
+/// `--emit-ast-json` the checked AST is printed as JSON instead of running the program, with `--repl`
+/// the lines read from stdin are evaluated one by one. `--int-width=32` makes `int` a 32 bit integer.
 fn main() {
-    if let Err(err) = compile() {
-        eprintln!("{}", err);
+    // Recursive programs need a deeper stack than the one of the main thread.
+    let cli = std::thread::Builder::new()
+        .stack_size(STACK_SIZE)
+        .spawn(run_cli)
+        .expect("failed to spawn the interpreter thread");
+    if cli.join().is_err() {
+        std::process::exit(101);
+    }
+}
+
+fn run_cli() {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let int_width = match parse_int_width(&args) {
+        Ok(int_width) => int_width,
+        Err(message) => {
+            eprintln!("{}", message);
+            std::process::exit(1);
+        }
+    };
+    if args.iter().any(|arg| arg == "--repl") {
+        repl(int_width);
+        return;
+    }
+    let emit_ast_json = args.iter().any(|arg| arg == "--emit-ast-json");
+    if emit_ast_json && !cfg!(feature = "serde") {
+        eprintln!("The '--emit-ast-json' flag requires the 'serde' feature.");
+        std::process::exit(1);
+    }
+
+    let source = match read_source(args.into_iter().find(|arg| !arg.starts_with("--"))) {
+        Ok(source) => source,
+        Err(message) => {
+            eprintln!("IOError: {}", message);
+            std::process::exit(1);
+        }
+    };
+
+    if let Err(err) = compile(&source, emit_ast_json, int_width) {
+        eprintln!("{}", err.render_colored(&source, std::io::stderr().is_terminal()));
+        std::process::exit(1);
+    }
+}
+
+/// Reads the value of `--int-width=<bits>`, 64 bit if the flag is missing.
+fn parse_int_width(args: &[String]) -> Result<IntWidth, String> {
+    match args.iter().find_map(|arg| arg.strip_prefix("--int-width=")) {
+        None | Some("64") => Ok(IntWidth::I64),
+        Some("32") => Ok(IntWidth::I32),
+        Some(bits) => Err(format!("Invalid int width '{}', expected 32 or 64.", bits)),
+    }
+}
+
+/// The prompt is only shown when stdin is a terminal, so that piped input gives clean output.
+fn repl(int_width: IntWidth) {
+    let (mut stdout, mut stderr, mut console) = (std::io::stdout(), std::io::stderr(), std::io::stderr());
+    let prompt = if std::io::stdin().is_terminal() { "> " } else { "" };
+    let color = console.is_terminal();
+    let mut repl = Repl::new(&mut stdout, &mut stderr);
+    repl.max_call_depth(DEEP_MAX_CALL_DEPTH);
+    repl.int_width(int_width);
+    if let Err(err) = repl.run(std::io::stdin().lock(), &mut console, prompt, color) {
+        eprintln!("IOError: Cannot read from stdin: {}.", err);
         std::process::exit(1);
     }
 }
 
-fn compile() -> Result<(), CompilerError> {
-    /*let code = 
-"int a = (1 * (2 + 3)) + 3;
-float b = -a / 5;
-print(b + 3);
-float c = 0.00001;
-int d = 10 / 10;
-float e = a;
-bool b1 = 2 == 2;
-bool b2 = !(true && (2 > 0.5)) || (d != e) && (10 <= 200);
-print(true && false);\0";*/
-
-    let code = 
-"let a = 2;\0";
-
-    let mut lexer = Lexer::new(code);
+fn read_source(path: Option<String>) -> Result<String, String> {
+    match path {
+        Some(path) => std::fs::read_to_string(&path).map_err(|err| format!("Cannot read file '{}': {}.", path, err)),
+        None => {
+            let mut source = String::new();
+            std::io::stdin()
+                .read_to_string(&mut source)
+                .map_err(|err| format!("Cannot read from stdin: {}.", err))?;
+            Ok(source)
+        }
+    }
+}
+
+fn compile(source: &str, emit_ast_json: bool, int_width: IntWidth) -> Result<(), CompilerError> {
+    let mut lexer = Lexer::new(source);
     lexer.tokenize()?;
     let tokens = lexer.get_tokens();
-    //println!("{:#?}", tokens);
 
-    let mut parser = Parser::new(tokens.to_vec());
+    let color = std::io::stderr().is_terminal();
+    let mut parser = Parser::from_slice(tokens);
     parser.parse()?;
-    let ast = parser.get_tree();
-    println!("{:#?}", parser.get_tree());
+    for warning in parser.get_warnings() {
+        eprintln!("{}", Diagnostic::from(warning).render_colored(source, color));
+    }
 
-    let mut analyser = SemanticAnalyser::new(ast.to_vec());
+    let mut analyser = SemanticAnalyser::new(parser.into_tree());
+    analyser.int_width(int_width);
     analyser.check()?;
-    println!("{:#?}", analyser.get_symbol_table());
+    for warning in analyser.get_warnings() {
+        eprintln!("{}", Diagnostic::from(warning).render_colored(source, color));
+    }
+    let ast = analyser.into_ast();
+
+    if emit_ast_json {
+        #[cfg(feature = "serde")]
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&ast).expect("the AST only consists of JSON-representable values")
+        );
+        return Ok(());
+    }
+
+    let (mut stdout, mut stderr) = (std::io::stdout(), std::io::stderr());
+    let mut interpreter = Interpreter::new(&mut stdout, &mut stderr);
+    interpreter.max_call_depth(DEEP_MAX_CALL_DEPTH);
+    interpreter.int_width(int_width);
+    interpreter.run(&ast)?;
 
     Ok(())
 }
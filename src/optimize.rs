@@ -0,0 +1,1012 @@
+use crate::{
+    interpreter::{Interpreter, Value},
+    schemas::{Ast, Expr, ExprKind, IntWidth, Primitive, Stmt, UnaryOpKind},
+    semantic::SemanticAnalyser,
+};
+use std::collections::HashMap;
+
+/// Removes redundant unary operators of a checked program: `--x` becomes `x` and `!!b` becomes `b`. A
+/// double negation is only removed if the inner negation cannot overflow, i.e. if `x` is a float or an
+/// int that cannot be `i64::MIN`, like a constant declared with an in-range literal. A unary `+` never
+/// reaches the AST since the parser already collapses it into its operand.
+pub fn simplify(ast: Ast) -> Ast {
+    let mut scopes = Scopes::new(HashMap::new());
+    ast.into_iter()
+        .map(|stmt| map_exprs_scoped(stmt, &mut scopes, &simplify_expr, &Operand::declared))
+        .collect()
+}
+
+/// Variables visible at a point of the program, with what a pass knows about them, in scopes which
+/// mirror the ones of the interpreter. Also records the return types of the declared functions.
+struct Scopes<T> {
+    scopes: Vec<HashMap<String, T>>,
+    functions: HashMap<String, Option<Primitive>>,
+}
+
+impl<T> Scopes<T> {
+    fn new(globals: HashMap<String, T>) -> Self {
+        Scopes {
+            scopes: vec![globals],
+            functions: HashMap::new(),
+        }
+    }
+
+    fn get(&self, name: &str) -> Option<&T> {
+        self.scopes.iter().rev().find_map(|scope| scope.get(name))
+    }
+
+    fn insert(&mut self, name: String, info: T) {
+        self.scopes.last_mut().expect("the global scope is never popped").insert(name, info);
+    }
+
+    /// Runs `f` in a new innermost scope, its declarations end with it.
+    fn scoped<R>(&mut self, f: impl FnOnce(&mut Self) -> R) -> R {
+        self.scopes.push(HashMap::new());
+        let result = f(self);
+        self.scopes.pop();
+        result
+    }
+}
+
+/// Like `map_exprs`, but `f` is passed the variables declared before the expression. `declare` tells
+/// what is known about a declared variable from its type and, if it is immutable, its (mapped)
+/// initializer. Parameters and mutable variables are declared without initializer.
+fn map_exprs_scoped<T>(
+    stmt: Stmt,
+    scopes: &mut Scopes<T>,
+    f: &impl Fn(Expr, &Scopes<T>) -> Expr,
+    declare: &impl Fn(&Primitive, Option<&Expr>, &Scopes<T>) -> T,
+) -> Stmt {
+    let map_body = |body: Ast, scopes: &mut Scopes<T>| -> Ast {
+        scopes.scoped(|scopes| body.into_iter().map(|stmt| map_exprs_scoped(stmt, scopes, f, declare)).collect())
+    };
+    match stmt {
+        Stmt::Declare {
+            dtype,
+            mutable,
+            name,
+            expr,
+            node_id,
+            span,
+        } => {
+            // The initializer is mapped before the variable is declared, it may refer to a shadowed one.
+            let expr = f(expr, scopes);
+            let info = declare(&dtype, (!mutable).then_some(&expr), scopes);
+            scopes.insert(name.clone(), info);
+            Stmt::Declare {
+                dtype,
+                mutable,
+                name,
+                expr,
+                node_id,
+                span,
+            }
+        }
+        Stmt::Block { body, node_id, span } => Stmt::Block {
+            body: map_body(body, scopes),
+            node_id,
+            span,
+        },
+        // The loop header gets a scope around the one of the body, so the loop variable ends with the loop.
+        Stmt::For {
+            init,
+            cond,
+            step,
+            body,
+            node_id,
+            span,
+        } => scopes.scoped(|scopes| {
+            let init = map_exprs_scoped(*init, scopes, f, declare);
+            let cond = f(cond, scopes);
+            let step = map_exprs_scoped(*step, scopes, f, declare);
+            Stmt::For {
+                init: Box::new(init),
+                cond,
+                step: Box::new(step),
+                body: map_body(body, scopes),
+                node_id,
+                span,
+            }
+        }),
+        Stmt::If {
+            cond,
+            then_body,
+            else_body,
+            node_id,
+            span,
+        } => Stmt::If {
+            cond: f(cond, scopes),
+            then_body: map_body(then_body, scopes),
+            else_body: else_body.map(|body| map_body(body, scopes)),
+            node_id,
+            span,
+        },
+        // Like in the interpreter, the body only sees the global variables and the parameters.
+        Stmt::FnDecl {
+            name,
+            params,
+            ret,
+            body,
+            node_id,
+            span,
+        } => {
+            scopes.functions.insert(name.clone(), ret.clone());
+            let locals = scopes.scopes.split_off(1);
+            let body = scopes.scoped(|scopes| {
+                for (param, primitive) in &params {
+                    let info = declare(primitive, None, scopes);
+                    scopes.insert(param.clone(), info);
+                }
+                body.into_iter().map(|stmt| map_exprs_scoped(stmt, scopes, f, declare)).collect()
+            });
+            scopes.scopes.extend(locals);
+            Stmt::FnDecl {
+                name,
+                params,
+                ret,
+                body,
+                node_id,
+                span,
+            }
+        }
+        stmt => map_exprs(stmt, &|expr| f(expr, scopes)),
+    }
+}
+
+/// What `simplify` knows about the value of an expression: its type and, for an int, whether it may
+/// be `i64::MIN`, the one value whose negation overflows.
+#[derive(Clone)]
+struct Operand {
+    primitive: Primitive,
+    may_be_int_min: bool,
+}
+
+impl Operand {
+    /// An operand of which only the type is known.
+    fn of_type(primitive: &Primitive) -> Self {
+        Operand {
+            primitive: primitive.clone(),
+            may_be_int_min: primitive.is_integer(),
+        }
+    }
+
+    fn declared(dtype: &Primitive, initializer: Option<&Expr>, scopes: &Scopes<Operand>) -> Self {
+        match initializer.and_then(|expr| Self::of_expr(expr, scopes)) {
+            Some(operand) if operand.primitive == *dtype => operand,
+            _ => Self::of_type(dtype),
+        }
+    }
+
+    /// Describes a checked expression, `None` if it refers to a name that is not declared.
+    fn of_expr(expr: &Expr, scopes: &Scopes<Operand>) -> Option<Self> {
+        match &expr.node {
+            ExprKind::Literal { value, primitive } => Some(Operand {
+                primitive: primitive.clone(),
+                may_be_int_min: primitive.is_integer() && value.parse::<i64>().is_err(),
+            }),
+            ExprKind::Identifier { name } => scopes.get(name).cloned(),
+            // A negated literal is a constant, e.g. `-9223372036854775808` is `i64::MIN`. Any other
+            // negation overflows instead of evaluating to `i64::MIN`.
+            ExprKind::UnaryOp {
+                op: UnaryOpKind::Neg,
+                expr: operand,
+            } => match &operand.node {
+                ExprKind::Literal { value, primitive } => Some(Operand {
+                    primitive: primitive.clone(),
+                    may_be_int_min: primitive.is_integer()
+                        && format!("-{}", value).parse::<i64>().is_ok_and(|value| value == i64::MIN),
+                }),
+                _ => Self::of_expr(operand, scopes).map(|operand| Operand {
+                    may_be_int_min: false,
+                    ..operand
+                }),
+            },
+            ExprKind::UnaryOp { op, .. } => Some(Self::of_type(if *op == UnaryOpKind::Not {
+                &Primitive::Bool
+            } else {
+                &Primitive::Int
+            })),
+            ExprKind::BinOp { op, left, right } => {
+                let (left, right) = (Self::of_expr(left, scopes)?, Self::of_expr(right, scopes)?);
+                SemanticAnalyser::infer_binop_type(op, &left.primitive, &right.primitive, &expr.span)
+                    .ok()
+                    .map(|primitive| Self::of_type(&primitive))
+            }
+            ExprKind::Assign { name, .. } => scopes.get(name).map(|operand| Self::of_type(&operand.primitive)),
+            ExprKind::TypeOf { .. } => Some(Self::of_type(&Primitive::Str)),
+            ExprKind::Call { name, .. } => match (scopes.functions.get(name), name.as_str()) {
+                (Some(ret), _) => ret.as_ref().map(Self::of_type),
+                // A code point is never negative.
+                (None, "int") => Some(Operand {
+                    primitive: Primitive::Int,
+                    may_be_int_min: false,
+                }),
+                (None, "char") => Some(Self::of_type(&Primitive::Char)),
+                (None, "pow" | "sqrt") => Some(Self::of_type(&Primitive::Float)),
+                _ => None,
+            },
+        }
+    }
+}
+
+/// Rebuilds the statement with `f` applied to each of its top-level expressions, recursing into
+/// nested statements like block bodies.
+fn map_exprs(stmt: Stmt, f: &impl Fn(Expr) -> Expr) -> Stmt {
+    let map_body = |body: Ast| -> Ast { body.into_iter().map(|stmt| map_exprs(stmt, f)).collect() };
+    match stmt {
+        Stmt::Declare {
+            dtype,
+            mutable,
+            name,
+            expr,
+            node_id,
+            span,
+        } => Stmt::Declare {
+            dtype,
+            mutable,
+            name,
+            expr: f(expr),
+            node_id,
+            span,
+        },
+        Stmt::MutAssign { name, expr, node_id, span } => Stmt::MutAssign {
+            name,
+            expr: f(expr),
+            node_id,
+            span,
+        },
+        Stmt::Print { expr, node_id, span } => Stmt::Print {
+            expr: f(expr),
+            node_id,
+            span,
+        },
+        Stmt::EPrint { expr, node_id, span } => Stmt::EPrint {
+            expr: f(expr),
+            node_id,
+            span,
+        },
+        Stmt::Expr { expr, node_id, span } => Stmt::Expr {
+            expr: f(expr),
+            node_id,
+            span,
+        },
+        Stmt::Swap { .. } => stmt,
+        Stmt::Block { body, node_id, span } => Stmt::Block {
+            body: map_body(body),
+            node_id,
+            span,
+        },
+        Stmt::For {
+            init,
+            cond,
+            step,
+            body,
+            node_id,
+            span,
+        } => Stmt::For {
+            init: Box::new(map_exprs(*init, f)),
+            cond: f(cond),
+            step: Box::new(map_exprs(*step, f)),
+            body: map_body(body),
+            node_id,
+            span,
+        },
+        Stmt::If {
+            cond,
+            then_body,
+            else_body,
+            node_id,
+            span,
+        } => Stmt::If {
+            cond: f(cond),
+            then_body: map_body(then_body),
+            else_body: else_body.map(map_body),
+            node_id,
+            span,
+        },
+        Stmt::FnDecl {
+            name,
+            params,
+            ret,
+            body,
+            node_id,
+            span,
+        } => Stmt::FnDecl {
+            name,
+            params,
+            ret,
+            body: map_body(body),
+            node_id,
+            span,
+        },
+        Stmt::Return { expr, node_id, span } => Stmt::Return {
+            expr: expr.map(f),
+            node_id,
+            span,
+        },
+    }
+}
+
+fn simplify_expr(expr: Expr, scopes: &Scopes<Operand>) -> Expr {
+    let Expr { node, node_id, span } = expr;
+    let node = match node {
+        ExprKind::Literal { .. } | ExprKind::Identifier { .. } => node,
+        ExprKind::BinOp { op, left, right } => ExprKind::BinOp {
+            op,
+            left: Box::new(simplify_expr(*left, scopes)),
+            right: Box::new(simplify_expr(*right, scopes)),
+        },
+        ExprKind::Assign { name, expr } => ExprKind::Assign {
+            name,
+            expr: Box::new(simplify_expr(*expr, scopes)),
+        },
+        ExprKind::TypeOf { expr } => ExprKind::TypeOf {
+            expr: Box::new(simplify_expr(*expr, scopes)),
+        },
+        ExprKind::Call { name, args } => ExprKind::Call {
+            name,
+            args: args.into_iter().map(|arg| simplify_expr(arg, scopes)).collect(),
+        },
+        ExprKind::UnaryOp { op, expr } => match simplify_expr(*expr, scopes) {
+            Expr {
+                node: ExprKind::UnaryOp {
+                    op: inner_op,
+                    expr: inner_expr,
+                },
+                ..
+            } if inner_op == op
+                && (op != UnaryOpKind::Neg
+                    || Operand::of_expr(&inner_expr, scopes).is_some_and(|operand| !operand.may_be_int_min)) =>
+            {
+                return *inner_expr;
+            }
+            operand => ExprKind::UnaryOp {
+                op,
+                expr: Box::new(operand),
+            },
+        },
+    };
+    Expr { node, node_id, span }
+}
+
+/// Replaces binary and unary operations on literals by the literal they evaluate to, e.g.
+/// `(1 * (2 + 3)) + 3` becomes `8`, following the typing rules of the semantic analyser. Operations
+/// which would fail at runtime, like a division by zero or an overflow, are kept as they are. Ints are
+/// folded with the default `IntWidth` of 64 bit.
+pub fn fold_constants(ast: Ast) -> Ast {
+    ast.into_iter().map(|stmt| map_exprs(stmt, &fold_expr)).collect()
+}
+
+/// Like `fold_constants`, but also propagates constants: a use of an immutable variable declared with
+/// a value that folds to a literal, e.g. `int a = 1;`, is replaced by that literal. `symbols` are the
+/// constants declared before the program, like the ones of earlier REPL inputs. A use always gets the
+/// value of the declaration it refers to, so an inner declaration shadows an outer one.
+pub fn fold_constants_scoped(ast: Ast, symbols: &HashMap<String, Value>) -> Ast {
+    let globals = symbols.iter().map(|(name, value)| (name.clone(), Some(value.clone()))).collect();
+    let mut scopes = Scopes::new(globals);
+    let fold = |expr, scopes: &Scopes<Option<Value>>| fold_expr(propagate_constants(expr, scopes));
+    ast.into_iter()
+        .map(|stmt| map_exprs_scoped(stmt, &mut scopes, &fold, &constant_value))
+        .collect()
+}
+
+/// The value of a declared variable if it is a constant, an initializer of another type, like the int
+/// literal of `float a = 1;`, is not propagated to keep the type of the uses.
+fn constant_value(dtype: &Primitive, initializer: Option<&Expr>, _: &Scopes<Option<Value>>) -> Option<Value> {
+    initializer.and_then(literal_value).filter(|value| value.primitive() == *dtype)
+}
+
+fn propagate_constants(expr: Expr, scopes: &Scopes<Option<Value>>) -> Expr {
+    let Expr { node, node_id, span } = expr;
+    let node = match node {
+        ExprKind::Identifier { name } => match scopes.get(&name).cloned().flatten().and_then(value_to_literal) {
+            Some(literal) => literal,
+            None => ExprKind::Identifier { name },
+        },
+        ExprKind::Literal { .. } => node,
+        ExprKind::BinOp { op, left, right } => ExprKind::BinOp {
+            op,
+            left: Box::new(propagate_constants(*left, scopes)),
+            right: Box::new(propagate_constants(*right, scopes)),
+        },
+        ExprKind::UnaryOp { op, expr } => ExprKind::UnaryOp {
+            op,
+            expr: Box::new(propagate_constants(*expr, scopes)),
+        },
+        ExprKind::Assign { name, expr } => ExprKind::Assign {
+            name,
+            expr: Box::new(propagate_constants(*expr, scopes)),
+        },
+        ExprKind::TypeOf { expr } => ExprKind::TypeOf {
+            expr: Box::new(propagate_constants(*expr, scopes)),
+        },
+        ExprKind::Call { name, args } => ExprKind::Call {
+            name,
+            args: args.into_iter().map(|arg| propagate_constants(arg, scopes)).collect(),
+        },
+    };
+    Expr { node, node_id, span }
+}
+
+pub(crate) fn fold_expr(expr: Expr) -> Expr {
+    let Expr { node, node_id, span } = expr;
+    let node = match node {
+        ExprKind::Literal { .. } | ExprKind::Identifier { .. } => node,
+        ExprKind::BinOp { op, left, right } => {
+            let (left, right) = (fold_expr(*left), fold_expr(*right));
+            let value = match (literal_value(&left), literal_value(&right)) {
+                (Some(left), Some(right)) => Interpreter::evaluate_binop(&op, left, right, IntWidth::I64, &span).ok(),
+                _ => None,
+            };
+            match value.and_then(value_to_literal) {
+                Some(literal) => literal,
+                None => ExprKind::BinOp {
+                    op,
+                    left: Box::new(left),
+                    right: Box::new(right),
+                },
+            }
+        }
+        ExprKind::UnaryOp { op, expr } => {
+            // A negated literal is evaluated as a whole, `9223372036854775808` is only valid when negated.
+            let value = match (&op, &expr.node) {
+                (UnaryOpKind::Neg, ExprKind::Literal { value, primitive }) => {
+                    Interpreter::evaluate_literal(value, primitive, true, &span).ok()
+                }
+                _ => None,
+            };
+            let expr = fold_expr(*expr);
+            let value = value.or_else(|| {
+                literal_value(&expr).and_then(|value| Interpreter::evaluate_unaryop(&op, value, IntWidth::I64, &span).ok())
+            });
+            match value.and_then(value_to_literal) {
+                Some(literal) => literal,
+                None => ExprKind::UnaryOp {
+                    op,
+                    expr: Box::new(expr),
+                },
+            }
+        }
+        ExprKind::Assign { name, expr } => ExprKind::Assign {
+            name,
+            expr: Box::new(fold_expr(*expr)),
+        },
+        ExprKind::TypeOf { expr } => ExprKind::TypeOf {
+            expr: Box::new(fold_expr(*expr)),
+        },
+        ExprKind::Call { name, args } => ExprKind::Call {
+            name,
+            args: args.into_iter().map(fold_expr).collect(),
+        },
+    };
+    Expr { node, node_id, span }
+}
+
+pub(crate) fn literal_value(expr: &Expr) -> Option<Value> {
+    match &expr.node {
+        ExprKind::Literal { value, primitive } => Interpreter::evaluate_literal(value, primitive, false, &expr.span).ok(),
+        _ => None,
+    }
+}
+
+/// Infinite and NaN floats have no literal, so expressions evaluating to them are not folded.
+fn value_to_literal(value: Value) -> Option<ExprKind> {
+    if let Value::Float(float) = value
+        && !float.is_finite()
+    {
+        return None;
+    }
+    Some(ExprKind::Literal {
+        primitive: value.primitive(),
+        value: value.to_string(),
+    })
+}
+
+/// Replaces an `if` statement whose condition folds to a constant by the branch that is taken, e.g.
+/// `if (1 < 2) { A } else { B }` becomes `{ A }`. The branch stays a block to keep its scope, a
+/// false condition without an else branch removes the statement. Other conditions are left intact.
+pub fn prune_dead_branches(ast: Ast) -> Ast {
+    ast.into_iter().filter_map(prune_stmt).collect()
+}
+
+fn prune_stmt(stmt: Stmt) -> Option<Stmt> {
+    let stmt = match stmt {
+        Stmt::If {
+            cond,
+            then_body,
+            else_body,
+            node_id,
+            span,
+        } => match constant_condition(&cond) {
+            Some(true) => Stmt::Block {
+                body: prune_dead_branches(then_body),
+                node_id,
+                span,
+            },
+            Some(false) => Stmt::Block {
+                body: prune_dead_branches(else_body?),
+                node_id,
+                span,
+            },
+            None => Stmt::If {
+                cond,
+                then_body: prune_dead_branches(then_body),
+                else_body: else_body.map(prune_dead_branches),
+                node_id,
+                span,
+            },
+        },
+        Stmt::Block { body, node_id, span } => Stmt::Block {
+            body: prune_dead_branches(body),
+            node_id,
+            span,
+        },
+        Stmt::For {
+            init,
+            cond,
+            step,
+            body,
+            node_id,
+            span,
+        } => Stmt::For {
+            init,
+            cond,
+            step,
+            body: prune_dead_branches(body),
+            node_id,
+            span,
+        },
+        Stmt::FnDecl {
+            name,
+            params,
+            ret,
+            body,
+            node_id,
+            span,
+        } => Stmt::FnDecl {
+            name,
+            params,
+            ret,
+            body: prune_dead_branches(body),
+            node_id,
+            span,
+        },
+        stmt => stmt,
+    };
+    Some(stmt)
+}
+
+fn constant_condition(cond: &Expr) -> Option<bool> {
+    match fold_expr(cond.clone()).node {
+        ExprKind::Literal {
+            value,
+            primitive: Primitive::Bool,
+        } => Some(value == "true"),
+        _ => None,
+    }
+}
+
+/// Turns int literals initializing a float declaration into float literals, so `float x = 5;`
+/// stores the literal `5.0` instead of relying on an implicit int to float coercion. Only (negated)
+/// literals are promoted, any other int expression is left untouched.
+pub fn promote_literals_to_declared_type(ast: Ast) -> Ast {
+    ast.into_iter().map(promote_stmt).collect()
+}
+
+fn promote_stmt(stmt: Stmt) -> Stmt {
+    match stmt {
+        Stmt::Declare {
+            dtype: Primitive::Float,
+            mutable,
+            name,
+            expr,
+            node_id,
+            span,
+        } => Stmt::Declare {
+            dtype: Primitive::Float,
+            mutable,
+            name,
+            expr: promote_int_literal(expr),
+            node_id,
+            span,
+        },
+        Stmt::Block { body, node_id, span } => Stmt::Block {
+            body: promote_literals_to_declared_type(body),
+            node_id,
+            span,
+        },
+        Stmt::If {
+            cond,
+            then_body,
+            else_body,
+            node_id,
+            span,
+        } => Stmt::If {
+            cond,
+            then_body: promote_literals_to_declared_type(then_body),
+            else_body: else_body.map(promote_literals_to_declared_type),
+            node_id,
+            span,
+        },
+        Stmt::For {
+            init,
+            cond,
+            step,
+            body,
+            node_id,
+            span,
+        } => Stmt::For {
+            init: Box::new(promote_stmt(*init)),
+            cond,
+            step,
+            body: promote_literals_to_declared_type(body),
+            node_id,
+            span,
+        },
+        Stmt::FnDecl {
+            name,
+            params,
+            ret,
+            body,
+            node_id,
+            span,
+        } => Stmt::FnDecl {
+            name,
+            params,
+            ret,
+            body: promote_literals_to_declared_type(body),
+            node_id,
+            span,
+        },
+        stmt => stmt,
+    }
+}
+
+fn promote_int_literal(expr: Expr) -> Expr {
+    let Expr { node, node_id, span } = expr;
+    let node = match node {
+        ExprKind::Literal {
+            value,
+            primitive: Primitive::Int,
+        } => ExprKind::Literal {
+            value: format!("{}.0", value),
+            primitive: Primitive::Float,
+        },
+        ExprKind::UnaryOp {
+            op: UnaryOpKind::Neg,
+            expr,
+        } => ExprKind::UnaryOp {
+            op: UnaryOpKind::Neg,
+            expr: Box::new(promote_int_literal(*expr)),
+        },
+        node => node,
+    };
+    Expr { node, node_id, span }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        lexer::Lexer,
+        parser::Parser,
+        schemas::{BinOpKind, NodeId, Span},
+    };
+    use std::ops::Range;
+
+    fn parse(input: &str) -> Ast {
+        let mut lexer = Lexer::new(input);
+        lexer.tokenize().unwrap();
+
+        let mut parser = Parser::new(lexer.get_tokens().to_vec());
+        parser.parse().unwrap();
+        parser.into_tree()
+    }
+
+    fn identifier(name: &str, node_id: usize, col: usize) -> Expr {
+        Expr {
+            node: ExprKind::Identifier { name: name.to_string() },
+            node_id: NodeId(node_id),
+            span: Span { line: 1, col, end_line: 1, end_col: col + name.len(), file_id: None },
+        }
+    }
+
+    #[test]
+    fn test_simplify_double_negation() {
+        // `--x` raises an overflow for `x == i64::MIN`, which the bare `x` would not.
+        for input in ["mut int x = 5; int a = --x;", "fn f(int x) -> int { return --x; }", "int a = --x;"] {
+            let ast = parse(input);
+            assert_eq!(simplify(ast.clone()), ast, "{}", input);
+        }
+        for input in ["float x = 1.5; float a = --x;", "int x = 5; int a = --x;", "int x = -5; int a = --x;"] {
+            let ast = simplify(parse(input));
+            assert_eq!(declared_expr(&ast[1]).node, ExprKind::Identifier { name: "x".to_string() }, "{}", input);
+        }
+
+        let ast = simplify(parse("float a = --2.5;"));
+        assert!(matches!(
+            &ast[0],
+            Stmt::Declare { expr: Expr { node: ExprKind::Literal { value, .. }, .. }, .. } if value == "2.5"
+        ));
+
+        // The innermost negation is kept, it is the one which overflows.
+        let ast = simplify(parse("mut int x = 0; print(- -+-x);"));
+        assert_eq!(
+            ast[1],
+            Stmt::Print {
+                expr: Expr {
+                    node: ExprKind::UnaryOp {
+                        op: UnaryOpKind::Neg,
+                        expr: Box::new(identifier("x", 6, 27)),
+                    },
+                    node_id: NodeId(5),
+                    span: Span { line: 1, col: 26, end_line: 1, end_col: 28, file_id: None },
+                },
+                node_id: NodeId(2),
+                span: Span { line: 1, col: 16, end_line: 1, end_col: 21, file_id: None }
+            }
+        );
+    }
+
+    #[test]
+    fn test_simplify_double_not() {
+        let ast = simplify(parse("print(!!(b && !!!c));"));
+        assert_eq!(
+            ast,
+            [Stmt::Print {
+                expr: Expr {
+                    node: ExprKind::BinOp {
+                        op: BinOpKind::And,
+                        left: Box::new(identifier("b", 3, 10)),
+                        right: Box::new(Expr {
+                            node: ExprKind::UnaryOp {
+                                op: UnaryOpKind::Not,
+                                expr: Box::new(identifier("c", 8, 18)),
+                            },
+                            node_id: NodeId(5),
+                            span: Span { line: 1, col: 15, end_line: 1, end_col: 19, file_id: None },
+                        }),
+                    },
+                    node_id: NodeId(4),
+                    span: Span { line: 1, col: 10, end_line: 1, end_col: 19, file_id: None },
+                },
+                node_id: NodeId(0),
+                span: Span { line: 1, col: 1, end_line: 1, end_col: 6, file_id: None }
+            }]
+        );
+    }
+
+    #[test]
+    fn test_simplify_keeps_int_min_negation() {
+        let ast = parse("int a = - -9223372036854775808;");
+        assert_eq!(simplify(ast.clone()), ast);
+
+        // `-9223372036854775808` is `i64::MIN` itself, negating it twice overflows.
+        let ast = parse("int a = - - -9223372036854775808;");
+        assert_eq!(simplify(ast.clone()), ast);
+        let ast = parse("int x = -9223372036854775808; int a = --x;");
+        assert_eq!(simplify(ast.clone()), ast);
+
+        let ast = simplify(parse("int a = - -9223372036854775807;"));
+        assert!(matches!(
+            &ast[0],
+            Stmt::Declare { expr: Expr { node: ExprKind::Literal { value, .. }, .. }, .. } if value == "9223372036854775807"
+        ));
+    }
+
+    /// A literal spanning the columns `cols` of the first line, the source of a folded literal is the
+    /// whole expression it replaces.
+    fn literal(value: &str, primitive: Primitive, node_id: usize, cols: Range<usize>) -> Expr {
+        Expr {
+            node: ExprKind::Literal {
+                value: value.to_string(),
+                primitive,
+            },
+            node_id: NodeId(node_id),
+            span: Span { line: 1, col: cols.start, end_line: 1, end_col: cols.end, file_id: None },
+        }
+    }
+
+    fn declared_expr(stmt: &Stmt) -> &Expr {
+        match stmt {
+            Stmt::Declare { expr, .. } => expr,
+            stmt => panic!("expected a declaration, found {:?}", stmt),
+        }
+    }
+
+    #[test]
+    fn test_fold_constants() {
+        let ast = fold_constants(parse("int a = (1 * (2 + 3)) + 3;"));
+        assert_eq!(declared_expr(&ast[0]), &literal("8", Primitive::Int, 6, 10..26));
+
+        let ast = fold_constants(parse("float b = 7 / 2 - -1;"));
+        assert_eq!(declared_expr(&ast[0]), &literal("4.5", Primitive::Float, 4, 11..21));
+
+        let ast = fold_constants(parse("bool c = !(1.5 > 2) && 3 % 2 == 1;"));
+        assert_eq!(declared_expr(&ast[0]), &literal("true", Primitive::Bool, 5, 10..34));
+
+        let ast = fold_constants(parse("int d = -9223372036854775808;"));
+        assert_eq!(declared_expr(&ast[0]), &literal("-9223372036854775808", Primitive::Int, 1, 9..29));
+    }
+
+    #[test]
+    fn test_fold_constants_keeps_non_constant_expressions() {
+        let ast = fold_constants(parse("int a = a + 1 * 2;"));
+        assert_eq!(
+            declared_expr(&ast[0]),
+            &Expr {
+                node: ExprKind::BinOp {
+                    op: BinOpKind::Add,
+                    left: Box::new(identifier("a", 1, 9)),
+                    right: Box::new(literal("2", Primitive::Int, 4, 13..18)),
+                },
+                node_id: NodeId(2),
+                span: Span { line: 1, col: 9, end_line: 1, end_col: 18, file_id: None },
+            }
+        );
+
+        // Operations failing at runtime are left for the interpreter to report.
+        let ast = parse("print(1 / 0);\nprint(9223372036854775807 + 1);\nprint(1 + true);");
+        assert_eq!(fold_constants(ast.clone()), ast);
+    }
+
+    #[test]
+    fn test_fold_constants_in_nested_statements() {
+        let ast = fold_constants(parse("if (1 < 2) { print(2 * 3); }"));
+        assert!(matches!(
+            &ast[0],
+            Stmt::If { cond: Expr { node: ExprKind::Literal { value: cond, .. }, .. }, then_body, .. }
+                if cond == "true" && matches!(&then_body[0], Stmt::Print { expr: Expr { node: ExprKind::Literal { value, .. }, .. }, .. } if value == "6")
+        ));
+    }
+
+    fn printed_literal(stmt: &Stmt) -> Option<&str> {
+        match stmt {
+            Stmt::Print { expr: Expr { node: ExprKind::Literal { value, .. }, .. }, .. } => Some(value),
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn test_fold_constants_scoped() {
+        let ast = parse("int a = 2 * 3; char c = 'x'; print(a - 10); print(c);");
+        let ast = fold_constants_scoped(ast, &HashMap::new());
+        assert_eq!(printed_literal(&ast[2]), Some("-4"));
+        assert_eq!(printed_literal(&ast[3]), Some("x"));
+
+        let symbols = HashMap::from([("x".to_string(), Value::Float(1.5))]);
+        let ast = fold_constants_scoped(parse("print(x * 2);"), &symbols);
+        assert_eq!(printed_literal(&ast[0]), Some("3.0"));
+    }
+
+    #[test]
+    fn test_fold_constants_scoped_inner_shadows_outer() {
+        let ast = parse("int a = 1; { int a = 2; print(a + 1); } print(a + 1);");
+        let ast = fold_constants_scoped(ast, &HashMap::new());
+        assert!(matches!(&ast[1], Stmt::Block { body, .. } if printed_literal(&body[1]) == Some("3")));
+        assert_eq!(printed_literal(&ast[2]), Some("2"));
+
+        // The declaration of the loop variable shadows the outer constant in the whole loop only.
+        let ast = fold_constants_scoped(
+            parse("int i = 5; for (mut int i = 0; i < 3; i = i + 1) { print(i); } print(i);"),
+            &HashMap::new(),
+        );
+        assert!(matches!(
+            &ast[1],
+            Stmt::For { cond: Expr { node: ExprKind::BinOp { .. }, .. }, body, .. } if printed_literal(&body[0]).is_none()
+        ));
+        assert_eq!(printed_literal(&ast[2]), Some("5"));
+
+        // A parameter shadows a global constant.
+        let ast = parse("int a = 1; fn f(int a) -> int { return a + 1; }");
+        assert_eq!(fold_constants_scoped(ast.clone(), &HashMap::new())[1], ast[1]);
+    }
+
+    #[test]
+    fn test_fold_constants_scoped_keeps_variables() {
+        // Mutable variables and int initializers of floats are not propagated.
+        let ast = parse("mut int a = 1; print(a + 1); float b = 1; print(b / 2);");
+        assert_eq!(fold_constants_scoped(ast.clone(), &HashMap::new()), ast);
+    }
+
+    #[test]
+    fn test_prune_constant_true_branch() {
+        let ast = prune_dead_branches(parse("if (1 < 2 && true) {\n  print(1);\n} else {\n  print(2);\n}"));
+        assert_eq!(
+            ast,
+            [Stmt::Block {
+                body: vec![Stmt::Print {
+                    expr: Expr {
+                        node: ExprKind::Literal {
+                            value: "1".to_string(),
+                            primitive: Primitive::Int,
+                        },
+                        node_id: NodeId(7),
+                        span: Span { line: 2, col: 9, end_line: 2, end_col: 10, file_id: None },
+                    },
+                    node_id: NodeId(6),
+                    span: Span { line: 2, col: 3, end_line: 2, end_col: 8, file_id: None },
+                }],
+                node_id: NodeId(0),
+                span: Span { line: 1, col: 1, end_line: 1, end_col: 3, file_id: None },
+            }]
+        );
+    }
+
+    #[test]
+    fn test_prune_constant_false_branch() {
+        let ast = prune_dead_branches(parse("if (!true) { print(1); } else if (2 > 3) { print(2); } else { print(3); }"));
+        assert!(matches!(
+            &ast[..],
+            [Stmt::Block { body, .. }] if matches!(
+                &body[..],
+                [Stmt::Block { body, .. }] if matches!(
+                    &body[..],
+                    [Stmt::Print { expr: Expr { node: ExprKind::Literal { value, .. }, .. }, .. }] if value == "3"
+                )
+            )
+        ));
+
+        let ast = prune_dead_branches(parse("print(0);\n{ if (false) { print(1); } }"));
+        assert!(matches!(&ast[..], [Stmt::Print { .. }, Stmt::Block { body, .. }] if body.is_empty()));
+    }
+
+    #[test]
+    fn test_prune_keeps_variable_conditions() {
+        let ast = parse("bool b = true;\nif (b) { print(1); } else { print(2); }\nfor (mut int i = 0; i < 2; i += 1) { if (i > 0) { print(i); } }");
+        assert_eq!(prune_dead_branches(ast.clone()), ast);
+    }
+
+    #[test]
+    fn test_promote_literals_to_declared_type() {
+        let ast = promote_literals_to_declared_type(parse("float x = 5;\nfloat y = -2;"));
+        assert_eq!(
+            ast,
+            [
+                Stmt::Declare {
+                    dtype: Primitive::Float,
+                    mutable: false,
+                    name: "x".to_string(),
+                    expr: Expr {
+                        node: ExprKind::Literal {
+                            value: "5.0".to_string(),
+                            primitive: Primitive::Float,
+                        },
+                        node_id: NodeId(1),
+                        span: Span { line: 1, col: 11, end_line: 1, end_col: 12, file_id: None },
+                    },
+                    node_id: NodeId(0),
+                    span: Span { line: 1, col: 1, end_line: 1, end_col: 6, file_id: None }
+                },
+                Stmt::Declare {
+                    dtype: Primitive::Float,
+                    mutable: false,
+                    name: "y".to_string(),
+                    expr: Expr {
+                        node: ExprKind::UnaryOp {
+                            op: UnaryOpKind::Neg,
+                            expr: Box::new(Expr {
+                                node: ExprKind::Literal {
+                                    value: "2.0".to_string(),
+                                    primitive: Primitive::Float,
+                                },
+                                node_id: NodeId(4),
+                                span: Span { line: 2, col: 12, end_line: 2, end_col: 13, file_id: None },
+                            }),
+                        },
+                        node_id: NodeId(3),
+                        span: Span { line: 2, col: 11, end_line: 2, end_col: 13, file_id: None },
+                    },
+                    node_id: NodeId(2),
+                    span: Span { line: 2, col: 1, end_line: 2, end_col: 6, file_id: None }
+                }
+            ]
+        );
+    }
+
+    #[test]
+    fn test_promote_literals_only_touches_literals() {
+        let ast = parse("int a = 5;\nfloat b = a;\nfloat c = 2 + 3;\nprint(4);");
+        assert_eq!(promote_literals_to_declared_type(ast.clone()), ast);
+    }
+}
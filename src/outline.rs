@@ -0,0 +1,196 @@
+use crate::schemas::{Ast, Expr, ExprKind, Stmt};
+
+/// Renders the AST as an indented outline with one node per line and two spaces of indentation
+/// per level, e.g. `Declare a: int` followed by its initializer. Meant to be read by humans, it is
+/// more compact than the `Debug` output.
+pub fn outline(ast: &Ast) -> String {
+    let mut text = String::new();
+    outline_body(ast, 0, &mut text);
+    text
+}
+
+fn push_line(text: &mut String, depth: usize, line: &str) {
+    text.push_str(&"  ".repeat(depth));
+    text.push_str(line);
+    text.push('\n');
+}
+
+fn outline_body(body: &Ast, depth: usize, text: &mut String) {
+    for stmt in body {
+        outline_stmt(stmt, depth, text);
+    }
+}
+
+fn outline_stmt(stmt: &Stmt, depth: usize, text: &mut String) {
+    match stmt {
+        Stmt::Declare {
+            dtype,
+            mutable,
+            name,
+            expr,
+            ..
+        } => {
+            let mutability = if *mutable { "mut " } else { "" };
+            push_line(text, depth, &format!("Declare {}{}: {}", mutability, name, dtype.name()));
+            outline_expr(expr, depth + 1, text);
+        }
+        Stmt::MutAssign { name, expr, .. } => {
+            push_line(text, depth, &format!("MutAssign {}", name));
+            outline_expr(expr, depth + 1, text);
+        }
+        Stmt::Print { expr, .. } => {
+            push_line(text, depth, "Print");
+            outline_expr(expr, depth + 1, text);
+        }
+        Stmt::EPrint { expr, .. } => {
+            push_line(text, depth, "EPrint");
+            outline_expr(expr, depth + 1, text);
+        }
+        Stmt::Expr { expr, .. } => {
+            push_line(text, depth, "Expr");
+            outline_expr(expr, depth + 1, text);
+        }
+        Stmt::Swap { left, right, .. } => push_line(text, depth, &format!("Swap {} {}", left, right)),
+        Stmt::Block { body, .. } => {
+            push_line(text, depth, "Block");
+            outline_body(body, depth + 1, text);
+        }
+        Stmt::If {
+            cond,
+            then_body,
+            else_body,
+            ..
+        } => {
+            push_line(text, depth, "If");
+            outline_expr(cond, depth + 1, text);
+            push_line(text, depth + 1, "Then");
+            outline_body(then_body, depth + 2, text);
+            if let Some(else_body) = else_body {
+                push_line(text, depth + 1, "Else");
+                outline_body(else_body, depth + 2, text);
+            }
+        }
+        Stmt::For {
+            init, cond, step, body, ..
+        } => {
+            push_line(text, depth, "For");
+            outline_stmt(init, depth + 1, text);
+            outline_expr(cond, depth + 1, text);
+            outline_stmt(step, depth + 1, text);
+            push_line(text, depth + 1, "Body");
+            outline_body(body, depth + 2, text);
+        }
+        Stmt::FnDecl {
+            name, params, ret, body, ..
+        } => {
+            let params: Vec<String> = params
+                .iter()
+                .map(|(name, primitive)| format!("{}: {}", name, primitive.name()))
+                .collect();
+            let ret = ret.as_ref().map_or(String::new(), |ret| format!(" -> {}", ret.name()));
+            push_line(text, depth, &format!("FnDecl {}({}){}", name, params.join(", "), ret));
+            outline_body(body, depth + 1, text);
+        }
+        Stmt::Return { expr, .. } => {
+            push_line(text, depth, "Return");
+            if let Some(expr) = expr {
+                outline_expr(expr, depth + 1, text);
+            }
+        }
+    }
+}
+
+fn outline_expr(expr: &Expr, depth: usize, text: &mut String) {
+    match &expr.node {
+        ExprKind::Literal { value, .. } => push_line(text, depth, &format!("Literal {}", value)),
+        ExprKind::Identifier { name } => push_line(text, depth, &format!("Identifier {}", name)),
+        ExprKind::BinOp { op, left, right } => {
+            push_line(text, depth, &format!("BinOp {}", op.symbol()));
+            outline_expr(left, depth + 1, text);
+            outline_expr(right, depth + 1, text);
+        }
+        ExprKind::UnaryOp { op, expr } => {
+            push_line(text, depth, &format!("UnaryOp {}", op.symbol()));
+            outline_expr(expr, depth + 1, text);
+        }
+        ExprKind::Assign { name, expr } => {
+            push_line(text, depth, &format!("Assign {}", name));
+            outline_expr(expr, depth + 1, text);
+        }
+        ExprKind::TypeOf { expr } => {
+            push_line(text, depth, "TypeOf");
+            outline_expr(expr, depth + 1, text);
+        }
+        ExprKind::Call { name, args } => {
+            push_line(text, depth, &format!("Call {}", name));
+            for arg in args {
+                outline_expr(arg, depth + 1, text);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{lexer::Lexer, parser::Parser};
+
+    fn parse(input: &str) -> Ast {
+        let mut lexer = Lexer::new(input);
+        lexer.tokenize().unwrap();
+
+        let mut parser = Parser::new(lexer.get_tokens().to_vec());
+        parser.parse().unwrap();
+        parser.into_tree()
+    }
+
+    #[test]
+    fn test_outline() {
+        let ast = parse("int a = 1 + 2;\nmut float b = -a;\nif (a > 2) {\n  print(sqrt(b));\n} else {\n  swap(a, b);\n}");
+        assert_eq!(
+            outline(&ast),
+            "\
+Declare a: int
+  BinOp +
+    Literal 1
+    Literal 2
+Declare mut b: float
+  UnaryOp -
+    Identifier a
+If
+  BinOp >
+    Identifier a
+    Literal 2
+  Then
+    Print
+      Call sqrt
+        Identifier b
+  Else
+    Swap a b
+"
+        );
+    }
+
+    #[test]
+    fn test_outline_for_loop() {
+        let ast = parse("for (mut int i = 0; i < 3; i += 1) { print(i); }");
+        assert_eq!(
+            outline(&ast),
+            "\
+For
+  Declare mut i: int
+    Literal 0
+  BinOp <
+    Identifier i
+    Literal 3
+  MutAssign i
+    BinOp +
+      Identifier i
+      Literal 1
+  Body
+    Print
+      Identifier i
+"
+        );
+    }
+}
@@ -1,64 +1,239 @@
-use crate::{errors::CompilerError, schemas::*};
-use std::f32::INFINITY;
-
-pub struct Parser {
-    tokens: Vec<Token>,
+use crate::{
+    errors::{CompilerError, CompilerWarning, FixIt},
+    lexer::Lexer,
+    schemas::*,
+};
+use std::{borrow::Cow, collections::VecDeque};
+
+/// Default limit of `Parser::max_nesting_depth`, low enough that the recursive descent cannot overflow the stack.
+pub const DEFAULT_MAX_NESTING_DEPTH: usize = 128;
+
+/// Binding power of the prefix operators '-', '+' and '!': tighter than '*' but looser than '^', so
+/// `-2 * 3` is `(-2) * 3` and `-2 ^ 2` is `-(2 ^ 2)`.
+pub(crate) const PREFIX_BINDING_POWER: f32 = 7.0;
+
+pub struct Parser<'a> {
+    tokens: Cow<'a, [Token]>,
+    pos: usize,
     tree: Ast,
+    warnings: Vec<CompilerWarning>,
+    suggest_parentheses: bool,
+    assignment_expressions: bool,
+    next_node_id: usize,
+    nesting_depth: usize,
+    max_nesting_depth: usize,
+    /// Declarations following the first one of a comma separated declaration like `int a = 1, b = 2;`,
+    /// handed out as statements of their own after it.
+    pending_declarations: VecDeque<Stmt>,
 }
 
-impl Parser {
-    pub fn new(mut tokens: Vec<Token>) -> Self {
-        tokens.reverse();
+impl<'a> Parser<'a> {
+    pub fn new(tokens: Vec<Token>) -> Self {
+        Self::with_tokens(Cow::Owned(tokens))
+    }
+
+    /// Creates a parser which borrows the tokens instead of taking ownership of (a clone of) them.
+    pub fn from_slice(tokens: &'a [Token]) -> Self {
+        Self::with_tokens(Cow::Borrowed(tokens))
+    }
 
+    fn with_tokens(tokens: Cow<'a, [Token]>) -> Self {
         Parser {
-            tokens: tokens,
+            tokens,
+            pos: 0,
             tree: vec![],
+            warnings: vec![],
+            suggest_parentheses: false,
+            assignment_expressions: false,
+            next_node_id: 0,
+            nesting_depth: 0,
+            max_nesting_depth: DEFAULT_MAX_NESTING_DEPTH,
+            pending_declarations: VecDeque::new(),
         }
     }
 
+    /// Enables the (opt-in) lint for expressions like `a && b || c` whose precedence is easily misread.
+    pub fn suggest_parentheses(&mut self, enabled: bool) {
+        self.suggest_parentheses = enabled;
+    }
+
+    /// Enables the C-like dialect in which `=` is a right-associative expression operator, so that
+    /// `(a = 5) > 0` is an expression. By default assignment is a statement, which may still chain
+    /// assignments like `a = b = 5;`.
+    pub fn assignment_expressions(&mut self, enabled: bool) {
+        self.assignment_expressions = enabled;
+    }
+
+    /// Limits how deeply operands may be nested, e.g. through parentheses or chained unary operators
+    /// like `!!!!b`. Deeper expressions are rejected with a syntax error instead of overflowing the stack.
+    pub fn max_nesting_depth(&mut self, limit: usize) {
+        self.max_nesting_depth = limit;
+    }
+
     fn peek_next(&self) -> Token {
-        self.tokens.last().cloned().unwrap_or(Token {
+        self.peek_nth(0)
+    }
+
+    /// Peeks `n` tokens past the next one, i.e. `peek_nth(0)` equals `peek_next()`.
+    fn peek_nth(&self, n: usize) -> Token {
+        self.tokens.get(self.pos + n).cloned().unwrap_or(Token {
             kind: TokenKind::EOF,
-            span: Span { line: 0, col: 0 },
+            span: Span { line: 0, col: 0, end_line: 0, end_col: 0, file_id: None },
         })
     }
 
     fn consume_next(&mut self) -> Token {
-        self.tokens.pop().unwrap_or(Token {
-            kind: TokenKind::EOF,
-            span: Span { line: 0, col: 0 },
-        })
+        let token = self.peek_next();
+        if self.pos < self.tokens.len() {
+            self.pos += 1;
+        }
+        token
+    }
+
+    /// Steps back to the previously consumed token, so that it is returned by `peek_next` again.
+    fn unconsume(&mut self) {
+        self.pos = self.pos.saturating_sub(1);
+    }
+
+    fn prev_token(&self) -> Option<&Token> {
+        self.pos.checked_sub(1).and_then(|index| self.tokens.get(index))
+    }
+
+    /// Hands out the id for the next AST node. Ids are reserved when the token a node is anchored at
+    /// (its span) is consumed, so they increase in source order.
+    fn next_node_id(&mut self) -> NodeId {
+        let node_id = NodeId(self.next_node_id);
+        self.next_node_id += 1;
+        node_id
     }
 
     fn parse_expression(&mut self, min_binding_pow: f32) -> Result<Expr, CompilerError> {
+        Ok(self.parse_grouped_expression(min_binding_pow)?.0)
+    }
+
+    /// Parses an expression and additionally returns whether it was entirely wrapped in parentheses.
+    fn parse_grouped_expression(&mut self, min_binding_pow: f32) -> Result<(Expr, bool), CompilerError> {
+        // Every nested operand recurses into this function, so its depth is bounded here.
+        if self.nesting_depth >= self.max_nesting_depth {
+            return Err(CompilerError::SyntaxError {
+                message: format!(
+                    "Expression is nested too deeply, the maximum nesting depth is {}.",
+                    self.max_nesting_depth
+                ),
+                span: self.peek_next().span,
+                fixit: None,
+            });
+        }
+        self.nesting_depth += 1;
+        let result = self.parse_nested_expression(min_binding_pow);
+        self.nesting_depth -= 1;
+        result
+    }
+
+    fn parse_nested_expression(&mut self, min_binding_pow: f32) -> Result<(Expr, bool), CompilerError> {
+        // The operator in front of the operand, to explain an operand missing at the end of the input.
+        let prev_operator = match self.prev_token() {
+            Some(Token {
+                kind: TokenKind::BinOp(op),
+                span,
+            }) => Some((op.symbol().to_string(), span.clone())),
+            Some(Token {
+                kind: TokenKind::CompoundAssign(op),
+                span,
+            }) => Some((format!("{}=", op.symbol()), span.clone())),
+            _ => None,
+        };
         let cur_token = self.consume_next();
+        let mut lhs_grouped = matches!(cur_token.kind, TokenKind::LParen);
         let mut lhs = match cur_token.kind {
-            TokenKind::Literal(literal) => Expr::Literal {
-                value: literal.value,
-                primitive: literal.primitive,
+            TokenKind::Literal(literal) => Expr {
+                node: ExprKind::Literal {
+                    value: literal.value,
+                    primitive: literal.primitive,
+                },
+                node_id: self.next_node_id(),
                 span: cur_token.span,
             },
-            TokenKind::Identifier(name) => Expr::Identifier {
-                name: name.clone(),
+            // An identifier followed by '(' calls a function, e.g. `pow(2, 10)`.
+            TokenKind::Identifier(name) if matches!(self.peek_next().kind, TokenKind::LParen) => {
+                let node_id = self.next_node_id();
+                Expr {
+                    node: ExprKind::Call {
+                        name,
+                        args: self.parse_call_arguments()?,
+                    },
+                    node_id,
+                    span: cur_token.span,
+                }
+            }
+            // The conversion builtins are named after the type they convert to, e.g. `int(c)` or `char(65)`.
+            TokenKind::Declare(primitive @ (Primitive::Int | Primitive::Char)) if matches!(self.peek_next().kind, TokenKind::LParen) => {
+                let node_id = self.next_node_id();
+                Expr {
+                    node: ExprKind::Call {
+                        name: primitive.name().to_string(),
+                        args: self.parse_call_arguments()?,
+                    },
+                    node_id,
+                    span: cur_token.span,
+                }
+            }
+            TokenKind::Identifier(name) => Expr {
+                node: ExprKind::Identifier { name: name.clone() },
+                node_id: self.next_node_id(),
                 span: cur_token.span,
             },
 
-            // Handles unary '-' sign.
-            TokenKind::BinOp(BinOpKind::Sub) => Expr::UnaryOp {
-                op: UnaryOpKind::Neg,
-                expr: Box::new(self.parse_expression(INFINITY)?),
-                span: cur_token.span,
-            },
+            // Handles unary '-' sign, '!' (boolean negation) and '~' (bitwise negation).
+            TokenKind::BinOp(BinOpKind::Sub | BinOpKind::Not | BinOpKind::BitNot) => {
+                let op = UnaryOpKind::try_from(&cur_token)?;
+                let node_id = self.next_node_id();
+                let operand = self.parse_expression(PREFIX_BINDING_POWER)?;
+                Expr {
+                    span: cur_token.span.to(&operand.span),
+                    node: ExprKind::UnaryOp {
+                        op,
+                        expr: Box::new(operand),
+                    },
+                    node_id,
+                }
+            }
 
-            // Handle unary '-' sign.
-            TokenKind::BinOp(BinOpKind::Add) => self.parse_expression(INFINITY)?,
+            // Handle unary '+' sign. It has no effect, so it is collapsed into its operand and
+            // `+x` produces the exact same tree as `x`.
+            TokenKind::BinOp(BinOpKind::Add) => {
+                let next_token = self.peek_next();
+                match next_token.kind {
+                    TokenKind::EOS | TokenKind::EOF | TokenKind::RParen => {
+                        return Err(CompilerError::SyntaxError {
+                            message: "Expected an operand after unary '+'.".to_string(),
+                            span: next_token.span,
+                            fixit: None,
+                        });
+                    }
+                    TokenKind::BinOp(ref op)
+                        if !matches!(op, BinOpKind::Add | BinOpKind::Sub | BinOpKind::Not | BinOpKind::BitNot) =>
+                    {
+                        return Err(CompilerError::SyntaxError {
+                            message: format!("Expected an operand after unary '+', found operator {:?}.", op),
+                            span: next_token.span,
+                            fixit: None,
+                        });
+                    }
+                    _ => self.parse_expression(PREFIX_BINDING_POWER)?,
+                }
+            }
 
-            // Handle unary '!' (boolean negation).
-            TokenKind::BinOp(BinOpKind::Not) => Expr::UnaryOp {
-                op: UnaryOpKind::Not,
-                expr: Box::new(self.parse_expression(INFINITY)?),
-                span: cur_token.span,
-            },
+            TokenKind::TypeOf => {
+                let node_id = self.next_node_id();
+                Expr {
+                    node: ExprKind::TypeOf {
+                        expr: Box::new(self.parse_keyword_argument("typeof")?),
+                    },
+                    node_id,
+                    span: cur_token.span,
+                }
+            }
 
             // Handle expression in parentheses.
             TokenKind::LParen => {
@@ -69,15 +244,33 @@ impl Parser {
                     return Err(CompilerError::SyntaxError {
                         message: "Expected closing ')'.".to_string(),
                         span: next_token.span,
+                        fixit: None,
                     });
                 }
                 self.consume_next();
                 expr
             }
+            // The input ends right after an operator, e.g. `int a = 1 +`.
+            TokenKind::EOF if let Some((symbol, span)) = prev_operator => {
+                return Err(CompilerError::SyntaxError {
+                    message: format!("Unexpected end of input; expected an operand after '{}'.", symbol),
+                    span: span.end_point(),
+                    fixit: None,
+                });
+            }
+            // Any other operator cannot start an operand, e.g. the '*' in `1 + * 2`.
+            TokenKind::BinOp(op) => {
+                return Err(CompilerError::SyntaxError {
+                    message: format!("Expected an operand, found operator '{}'.", op.symbol()),
+                    span: cur_token.span,
+                    fixit: None,
+                });
+            }
             t => {
                 return Err(CompilerError::SyntaxError {
                     message: format!("Unexpected token {:?}.", t),
                     span: cur_token.span,
+                    fixit: None,
                 });
             }
         };
@@ -86,44 +279,117 @@ impl Parser {
             let next_op_token = self.peek_next();
 
             match &next_op_token.kind {
-                TokenKind::BinOp(op) => {
+                // Assignment binds weakest and is right-associative.
+                TokenKind::BinOp(BinOpKind::Assign) if self.assignment_expressions => {
+                    if min_binding_pow > 0.2 {
+                        break;
+                    }
+                    let ExprKind::Identifier { name } = &lhs.node else {
+                        return Err(CompilerError::SyntaxError {
+                            message: "Invalid assignment target, expected a variable name.".to_string(),
+                            span: next_op_token.span,
+                            fixit: None,
+                        });
+                    };
+                    let name = name.clone();
+
+                    let _ = self.consume_next();
+                    let node_id = self.next_node_id();
+                    lhs = Expr {
+                        node: ExprKind::Assign {
+                            name,
+                            expr: Box::new(self.parse_expression(0.1)?),
+                        },
+                        node_id,
+                        span: next_op_token.span,
+                    };
+                    lhs_grouped = false;
+                }
+                TokenKind::BinOp(_) => {
+                    let op = BinOpKind::try_from(&next_op_token)?;
                     let (lbp, rbp) = Self::airthmetic_binding_power(&op, &next_op_token.span)?;
                     if lbp < min_binding_pow {
                         break;
                     }
 
-                    let op_clone = op.clone();
                     let _ = self.consume_next();
+                    let node_id = self.next_node_id();
+                    let (rhs, rhs_grouped) = self.parse_grouped_expression(rbp)?;
+
+                    if self.suggest_parentheses
+                        && ((!lhs_grouped && Self::mixes_precedence(&op, &lhs))
+                            || (!rhs_grouped && Self::mixes_precedence(&op, &rhs)))
+                    {
+                        self.warnings.push(CompilerWarning::SuggestParentheses {
+                            op: op.clone(),
+                            span: next_op_token.span.clone(),
+                        });
+                    }
 
-                    lhs = Expr::BinOp {
-                        op: op_clone,
-                        left: Box::new(lhs),
-                        right: Box::new(self.parse_expression(rbp.clone())?),
-                        span: next_op_token.span,
+                    // The operation spans from the start of its left to the end of its right operand.
+                    let span = lhs.span.to(&rhs.span);
+                    lhs = Expr {
+                        node: ExprKind::BinOp {
+                            op,
+                            left: Box::new(lhs),
+                            right: Box::new(rhs),
+                        },
+                        node_id,
+                        span,
                     };
+                    lhs_grouped = false;
                 }
-                TokenKind::RParen => break,
-                TokenKind::EOS => break,
-                TokenKind::EOF => break,
-                t => {
-                    return Err(CompilerError::SyntaxError {
-                        message: format!("Unexpected token {:?}.", t),
-                        span: next_op_token.span,
-                    });
-                }
+                // Any other token ends the expression, the caller decides whether it is valid there.
+                _ => break,
             };
         }
 
-        Ok(lhs)
+        Ok((lhs, lhs_grouped))
+    }
+
+    /// Whether an unparenthesized `operand` of `op` mixes `&&` with `||` or arithmetic or bitwise
+    /// operations with comparison, e.g. `a & 1 == 0`, which C would parse as `a & (1 == 0)`.
+    fn mixes_precedence(op: &BinOpKind, operand: &Expr) -> bool {
+        let ExprKind::BinOp { op: operand_op, .. } = &operand.node else {
+            return false;
+        };
+        match op {
+            BinOpKind::And | BinOpKind::Or => {
+                matches!(operand_op, BinOpKind::And | BinOpKind::Or) && operand_op != op
+            }
+            BinOpKind::Gt | BinOpKind::Lt | BinOpKind::Ge | BinOpKind::Le | BinOpKind::Eq | BinOpKind::Ne => {
+                matches!(
+                    operand_op,
+                    BinOpKind::Add
+                        | BinOpKind::Sub
+                        | BinOpKind::Mult
+                        | BinOpKind::Div
+                        | BinOpKind::Mod
+                        | BinOpKind::Pow
+                        | BinOpKind::BitAnd
+                        | BinOpKind::BitOr
+                        | BinOpKind::Shl
+                        | BinOpKind::Shr
+                )
+            }
+            _ => false,
+        }
     }
 
-    fn airthmetic_binding_power(
+    pub(crate) fn airthmetic_binding_power(
         binop_kind: &BinOpKind,
         span: &Span,
     ) -> Result<(f32, f32), CompilerError> {
         match binop_kind {
-            BinOpKind::Mult | BinOpKind::Div => Ok((6.1, 6.2)),
+            // The left binding power is the higher one, so that `2 ^ 3 ^ 2` is `2 ^ (3 ^ 2)`.
+            BinOpKind::Pow => Ok((7.2, 7.1)),
+            BinOpKind::Mult | BinOpKind::Div | BinOpKind::Mod => Ok((6.1, 6.2)),
             BinOpKind::Add | BinOpKind::Sub => Ok((5.1, 5.2)),
+            // Shifts and bitwise operations bind between arithmetic and comparison, so `a & 1 == 0`
+            // is `(a & 1) == 0` and `1 << n - 1` is `1 << (n - 1)`.
+            BinOpKind::Shl | BinOpKind::Shr => Ok((4.7, 4.8)),
+            BinOpKind::BitAnd => Ok((4.5, 4.6)),
+            BinOpKind::BitOr => Ok((4.3, 4.4)),
             BinOpKind::Gt | BinOpKind::Lt | BinOpKind::Ge | BinOpKind::Le => Ok((4.1, 4.2)),
             BinOpKind::Eq | BinOpKind::Ne => Ok((3.1, 3.2)),
             BinOpKind::And => Ok((2.1, 2.2)),
@@ -131,13 +397,38 @@ impl Parser {
             t => Err(CompilerError::SyntaxError {
                 message: format!("Unexpected token {:?}.", t),
                 span: span.clone(),
+                fixit: None,
             }),
         }
     }
 
+    /// Parses a declaration and queues the further declarations sharing its type, e.g. `b = a + 1` in
+    /// `int a = 1, b = a + 1;`, as `pending_declarations`.
     fn parse_declaration(
         &mut self,
         primitive: &Primitive,
+        node_id: NodeId,
+        span: Span,
+        mutable: bool,
+    ) -> Result<Stmt, CompilerError> {
+        let declaration = self.parse_declarator(primitive, node_id, span, mutable)?;
+
+        let mut declarations = vec![];
+        while matches!(self.peek_next().kind, TokenKind::Comma) {
+            self.consume_next();
+            let node_id = self.next_node_id();
+            let span = self.peek_next().span;
+            declarations.push(self.parse_declarator(primitive, node_id, span, mutable)?);
+        }
+        self.pending_declarations.extend(declarations);
+        Ok(declaration)
+    }
+
+    /// Parses the `name = expr` part of a declaration.
+    fn parse_declarator(
+        &mut self,
+        primitive: &Primitive,
+        node_id: NodeId,
         span: Span,
         mutable: bool,
     ) -> Result<Stmt, CompilerError> {
@@ -146,10 +437,32 @@ impl Parser {
         // Check for identifier (ie. variable name)
         let identifer_name = match next_token.kind {
             TokenKind::Identifier(name) => name,
+            t @ (TokenKind::Print
+            | TokenKind::EPrint
+            | TokenKind::Swap
+            | TokenKind::TypeOf
+            | TokenKind::If
+            | TokenKind::Else
+            | TokenKind::For
+            | TokenKind::Fn
+            | TokenKind::Return
+            | TokenKind::Mut
+            | TokenKind::Declare(_)
+            | TokenKind::Literal(Literal {
+                primitive: Primitive::Bool,
+                ..
+            })) => {
+                return Err(CompilerError::SyntaxError {
+                    message: format!("Expected a variable name, found reserved keyword {:?}.", t),
+                    span: next_token.span,
+                    fixit: None,
+                });
+            }
             t => {
                 return Err(CompilerError::SyntaxError {
                     message: format!("Unexpected token {:?}.", t),
                     span: next_token.span,
+                    fixit: None,
                 });
             }
         };
@@ -162,6 +475,7 @@ impl Parser {
             return Err(CompilerError::SyntaxError {
                 message: "Expected '=' after declaration.".to_string(),
                 span: next_token.span,
+                fixit: None,
             });
         }
         self.consume_next();
@@ -170,207 +484,697 @@ impl Parser {
             dtype: primitive.clone(),
             name: identifer_name.clone(),
             expr: self.parse_expression(0.0)?,
+            node_id,
             span: span.clone(),
             mutable,
         })
     }
 
-    fn parse_statement(&mut self) -> Result<Stmt, CompilerError> {
-        let cur_token = self.consume_next().clone();
-        match cur_token.kind {
-            TokenKind::Declare(ref primitive) => {
-                self.parse_declaration(primitive, cur_token.span, false)
-            }
-            TokenKind::Mut => {
-                let next_token = self.peek_next().clone();
+    /// Parses the parenthesized expression following a keyword like 'print' or 'typeof'.
+    fn parse_keyword_argument(&mut self, keyword: &str) -> Result<Expr, CompilerError> {
+        // Check for opening parenthese.
+        let next_token = self.peek_next();
+        if !matches!(next_token.kind, TokenKind::LParen) {
+            return Err(CompilerError::SyntaxError {
+                message: format!("Expected opening '(' after '{}' keyword.", keyword),
+                span: next_token.span,
+                fixit: None,
+            });
+        }
+        self.consume_next();
 
-                // Check for declaration
-                let declared_primitive = match next_token.kind {
-                    TokenKind::Declare(ref primitive) => primitive,
-                    t => {
-                        return Err(CompilerError::SyntaxError {
-                            message: format!(
-                                "UExpected variable declaration after 'mut'. Found token '{:?}' instead.",
-                                t
-                            ),
-                            span: next_token.span,
-                        });
-                    }
-                };
-                self.consume_next();
-                self.parse_declaration(declared_primitive, cur_token.span, true)
-            }
-            TokenKind::Identifier(name) => {
-                let next_token = self.peek_next();
+        // Processes expression inside the parentheses.
+        let expr = self.parse_expression(0.0)?;
 
-                // Check for assign token (ie. '=')
-                if !matches!(next_token.kind, TokenKind::BinOp(BinOpKind::Assign)) {
-                    return Err(CompilerError::SyntaxError {
-                        message: format!("Invalid syntax. Did you mean to put '=' after variable '{name}'?"),
-                        span: next_token.span,
-                    });
-                }
-                self.consume_next();
+        // Check for closing parenthese.
+        let next_token = self.peek_next();
+        if !matches!(next_token.kind, TokenKind::RParen) {
+            return Err(CompilerError::SyntaxError {
+                message: "Expected closing ')'.".to_string(),
+                span: next_token.span,
+                fixit: None,
+            });
+        }
+        self.consume_next();
 
-                Ok(Stmt::MutAssign {
-                    name: name,
-                    expr: self.parse_expression(0.0)?,
-                    span: cur_token.span,
-                })
-            }
-            TokenKind::Print => {
-                // Check for opening parenthese.
-                let next_token = self.peek_next();
-                if !matches!(next_token.kind, TokenKind::LParen) {
-                    return Err(CompilerError::SyntaxError {
-                        message: "Expected opening '(' after 'print' keyword.".to_string(),
-                        span: next_token.span,
-                    });
-                }
-                self.consume_next();
+        Ok(expr)
+    }
 
-                // Processes expression inside print().
-                let expr = self.parse_expression(0.0)?;
+    /// Parses the comma-separated arguments of a function call, including the enclosing parentheses.
+    fn parse_call_arguments(&mut self) -> Result<Vec<Expr>, CompilerError> {
+        self.expect_token(TokenKind::LParen, "Expected opening '(' of function call.")?;
+        let mut args = vec![];
+        if matches!(self.peek_next().kind, TokenKind::RParen) {
+            self.consume_next();
+            return Ok(args);
+        }
 
-                // Check for closing parenthese.
-                let next_token = self.peek_next();
-                if !matches!(next_token.kind, TokenKind::RParen) {
+        loop {
+            args.push(self.parse_expression(0.0)?);
+            let next_token = self.consume_next();
+            match next_token.kind {
+                TokenKind::Comma => (),
+                TokenKind::RParen => return Ok(args),
+                _ => {
                     return Err(CompilerError::SyntaxError {
-                        message: "Expected closing ')'.".to_string(),
+                        message: "Expected ',' or closing ')' after function argument.".to_string(),
                         span: next_token.span,
+                        fixit: None,
                     });
                 }
-                self.consume_next();
-
-                Ok(Stmt::Print {
-                    expr,
-                    span: Span {
-                        line: cur_token.span.line,
-                        col: cur_token.span.col,
-                    },
-                })
             }
-            k => Err(CompilerError::SyntaxError {
-                message: format!("Unexpected token of kind {:?}.", k),
-                span: cur_token.span,
-            }),
         }
     }
 
-    pub fn parse(&mut self) -> Result<(), CompilerError> {
-        while !matches!(self.peek_next().kind, TokenKind::EOF) {
-            let stmt = self.parse_statement()?;
+    /// Parses the statements of a brace-delimited block, e.g. the body of an if statement.
+    fn parse_block(&mut self, keyword: &str) -> Result<Ast, CompilerError> {
+        self.expect_token(TokenKind::LBrace, &format!("Expected opening '{{' after '{}'.", keyword))?;
 
+        let mut body = vec![];
+        loop {
+            self.skip_empty_statements();
             let next_token = self.peek_next();
             match next_token.kind {
-                TokenKind::EOS => {
+                TokenKind::RBrace => {
                     self.consume_next();
-                    self.tree.push(stmt);
+                    return Ok(body);
                 }
-                TokenKind::RParen => {
+                TokenKind::EOF => {
                     return Err(CompilerError::SyntaxError {
-                        message: "Unmatched ')'.".to_string(),
+                        message: "Missing closing '}' of block.".to_string(),
                         span: next_token.span,
+                        fixit: None,
                     });
                 }
                 _ => {
-                    return Err(CompilerError::SyntaxError {
-                        message: "Expected ';' at end of expression.".to_string(),
-                        span: next_token.span,
-                    });
+                    let stmt = self.parse_terminated_statement()?;
+                    if let Stmt::FnDecl { span, .. } = stmt {
+                        return Err(CompilerError::SyntaxError {
+                            message: "Functions can only be declared at the top level.".to_string(),
+                            span,
+                            fixit: None,
+                        });
+                    }
+                    body.push(stmt);
+                    body.extend(self.pending_declarations.drain(..));
                 }
             }
         }
-
-        Ok(())
-    }
-
-    pub fn get_tree(&self) -> &Ast {
-        &self.tree
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use crate::lexer::Lexer;
+    /// Parses the rest of an if statement after its 'if' keyword, including any else branches.
+    fn parse_if(&mut self, node_id: NodeId, span: Span) -> Result<Stmt, CompilerError> {
+        let cond = self.parse_keyword_argument("if")?;
+        let then_body = self.parse_block("if (...)")?;
 
-    fn parse(input: &str) -> Result<Ast, CompilerError> {
-        let mut lexer = Lexer::new(&(input.to_owned() + "\0"));
-        lexer.tokenize()?;
+        let else_body = if matches!(self.peek_next().kind, TokenKind::Else) {
+            self.consume_next();
+            let next_token = self.peek_next();
+            if matches!(next_token.kind, TokenKind::If) {
+                self.consume_next();
+                let else_if_id = self.next_node_id();
+                Some(vec![self.parse_if(else_if_id, next_token.span)?])
+            } else {
+                Some(self.parse_block("else")?)
+            }
+        } else {
+            None
+        };
 
-        let mut parser = Parser::new(lexer.get_tokens().to_vec());
-        parser.parse()?;
+        Ok(Stmt::If {
+            cond,
+            then_body,
+            else_body,
+            node_id,
+            span,
+        })
+    }
 
-        Ok(parser.get_tree().to_vec())
+    /// Parses the rest of a for loop after its 'for' keyword.
+    fn parse_for(&mut self, node_id: NodeId, span: Span) -> Result<Stmt, CompilerError> {
+        self.expect_token(TokenKind::LParen, "Expected opening '(' after 'for' keyword.")?;
+        let init = self.parse_for_clause("initializer")?;
+        self.expect_token(TokenKind::EOS, "Expected ';' after the initializer of the 'for' loop.")?;
+        let cond = self.parse_expression(0.0)?;
+        self.expect_token(TokenKind::EOS, "Expected ';' after the condition of the 'for' loop.")?;
+        let step = self.parse_for_clause("step")?;
+        self.expect_token(TokenKind::RParen, "Expected closing ')'.")?;
+
+        Ok(Stmt::For {
+            init: Box::new(init),
+            cond,
+            step: Box::new(step),
+            body: self.parse_block("for (...)")?,
+            node_id,
+            span,
+        })
     }
 
-    fn ignore_spans_expr(expr: Expr) -> Expr {
-        match expr {
-            Expr::Literal {
-                value, primitive, ..
-            } => Expr::Literal {
-                value,
-                primitive,
-                span: Span::default(),
-            },
-            Expr::Identifier { name, .. } => Expr::Identifier {
-                name,
-                span: Span::default(),
-            },
-            Expr::UnaryOp { op, expr, span: _ } => Expr::UnaryOp {
-                op,
-                expr: Box::new(ignore_spans_expr(*expr)),
-                span: Span::default(),
-            },
-            Expr::BinOp {
-                op,
-                left,
-                right,
-                span: _,
-            } => Expr::BinOp {
-                op,
-                left: Box::new(ignore_spans_expr(*left)),
-                right: Box::new(ignore_spans_expr(*right)),
-                span: Span::default(),
-            },
+    /// Parses the rest of a function declaration after its 'fn' keyword, e.g. `add(int a, int b) -> int { ... }`.
+    /// The `-> type` is left out for a function that returns no value.
+    fn parse_function(&mut self, node_id: NodeId, span: Span) -> Result<Stmt, CompilerError> {
+        let name = self.expect_identifier()?;
+        self.expect_token(TokenKind::LParen, "Expected opening '(' of the parameter list.")?;
+        let mut params = vec![];
+        if matches!(self.peek_next().kind, TokenKind::RParen) {
+            self.consume_next();
+        } else {
+            loop {
+                let primitive = self.parse_type()?;
+                params.push((self.expect_identifier()?, primitive));
+                let next_token = self.consume_next();
+                match next_token.kind {
+                    TokenKind::Comma => (),
+                    TokenKind::RParen => break,
+                    _ => {
+                        return Err(CompilerError::SyntaxError {
+                            message: "Expected ',' or closing ')' after function parameter.".to_string(),
+                            span: next_token.span,
+                            fixit: None,
+                        });
+                    }
+                }
+            }
         }
+        let ret = match self.peek_next().kind {
+            TokenKind::Arrow => {
+                self.consume_next();
+                Some(self.parse_type()?)
+            }
+            _ => None,
+        };
+
+        Ok(Stmt::FnDecl {
+            name,
+            params,
+            ret,
+            body: self.parse_block("function signature")?,
+            node_id,
+            span,
+        })
     }
 
-    fn ignore_spans_stmt(stmt: Stmt) -> Stmt {
-        match stmt {
-            Stmt::Declare {
-                dtype,
-                name,
-                mutable,
-                expr,
-                span: _,
-            } => Stmt::Declare {
-                dtype,
-                name,
-                mutable,
-                expr: ignore_spans_expr(expr),
-                span: Span::default(),
-            },
-            Stmt::Print { expr, span: _ } => Stmt::Print {
-                expr: ignore_spans_expr(expr),
-                span: Span::default(),
-            },
-            Stmt::MutAssign {
-                name,
-                expr,
-                span: _,
-            } => Stmt::MutAssign {
-                name,
-                expr: ignore_spans_expr(expr),
-                span: Span::default(),
-            },
+    /// Parses a type name, either a built-in one like `int` or a named one like `Color`.
+    fn parse_type(&mut self) -> Result<Primitive, CompilerError> {
+        let next_token = self.consume_next();
+        match next_token.kind {
+            TokenKind::Declare(primitive) => Ok(primitive),
+            TokenKind::Identifier(name) => Ok(Primitive::Named(name)),
+            t => Err(CompilerError::SyntaxError {
+                message: format!("Expected a type, found token {:?}.", t),
+                span: next_token.span,
+                fixit: None,
+            }),
         }
     }
 
-    fn ignore_spans_ast(ast: Ast) -> Ast {
-        ast.into_iter().map(ignore_spans_stmt).collect()
-    }
+    /// Parses the value of an assignment statement. A chain like `a = b = 5;` assigns to all of its
+    /// variables, the inner assignments become assignment expressions even without the dialect.
+    fn parse_assigned_value(&mut self) -> Result<Expr, CompilerError> {
+        let next_token = self.peek_next();
+        let assign_token = self.peek_nth(1);
+        match (&next_token.kind, &assign_token.kind) {
+            (TokenKind::Identifier(name), TokenKind::BinOp(BinOpKind::Assign)) if !self.assignment_expressions => {
+                self.consume_next();
+                self.consume_next();
+                let node_id = self.next_node_id();
+                Ok(Expr {
+                    node: ExprKind::Assign {
+                        name: name.clone(),
+                        expr: Box::new(self.parse_assigned_value()?),
+                    },
+                    node_id,
+                    span: assign_token.span,
+                })
+            }
+            _ => self.parse_expression(0.0),
+        }
+    }
+
+    /// Parses the initializer or step of a for loop, which have to be simple statements.
+    fn parse_for_clause(&mut self, clause: &str) -> Result<Stmt, CompilerError> {
+        let stmt = self.parse_statement()?;
+        if let Some(declaration) = self.pending_declarations.drain(..).next() {
+            return Err(CompilerError::SyntaxError {
+                message: format!("Expected a single declaration as {} of the 'for' loop.", clause),
+                span: declaration.span().clone(),
+                fixit: None,
+            });
+        }
+        match stmt {
+            Stmt::Declare { .. } | Stmt::MutAssign { .. } | Stmt::Expr { .. } => Ok(stmt),
+            stmt => Err(CompilerError::SyntaxError {
+                message: format!("Expected a declaration, assignment or expression as {} of the 'for' loop.", clause),
+                span: stmt.span().clone(),
+                fixit: None,
+            }),
+        }
+    }
+
+    fn expect_token(&mut self, kind: TokenKind, message: &str) -> Result<(), CompilerError> {
+        let next_token = self.peek_next();
+        if next_token.kind != kind {
+            return Err(CompilerError::SyntaxError {
+                message: message.to_string(),
+                span: next_token.span,
+                fixit: None,
+            });
+        }
+        self.consume_next();
+        Ok(())
+    }
+
+    fn expect_identifier(&mut self) -> Result<String, CompilerError> {
+        let next_token = self.consume_next();
+        match next_token.kind {
+            TokenKind::Identifier(name) => Ok(name),
+            t => Err(CompilerError::SyntaxError {
+                message: format!("Expected a variable name, found token {:?}.", t),
+                span: next_token.span,
+                fixit: None,
+            }),
+        }
+    }
+
+    fn parse_statement(&mut self) -> Result<Stmt, CompilerError> {
+        let cur_token = self.consume_next().clone();
+        let node_id = self.next_node_id();
+        match cur_token.kind {
+            TokenKind::Declare(ref primitive) => {
+                self.parse_declaration(primitive, node_id, cur_token.span, false)
+            }
+            TokenKind::Mut => {
+                let next_token = self.peek_next().clone();
+
+                // Check for declaration, either of a built-in or of a named type
+                let declared_primitive = match next_token.kind {
+                    TokenKind::Declare(ref primitive) => primitive.clone(),
+                    TokenKind::Identifier(ref type_name)
+                        if matches!(self.peek_nth(1).kind, TokenKind::Identifier(_)) =>
+                    {
+                        Primitive::Named(type_name.clone())
+                    }
+                    t => {
+                        return Err(CompilerError::SyntaxError {
+                            message: format!(
+                                "UExpected variable declaration after 'mut'. Found token '{:?}' instead.",
+                                t
+                            ),
+                            span: next_token.span,
+                            fixit: None,
+                        });
+                    }
+                };
+                self.consume_next();
+                self.parse_declaration(&declared_primitive, node_id, cur_token.span, true)
+            }
+            TokenKind::Identifier(ref name) => {
+                let next_token = self.peek_next();
+
+                // Two consecutive identifiers declare a variable of a named type, e.g. `Color c = Red;`.
+                if matches!(next_token.kind, TokenKind::Identifier(_)) {
+                    return self.parse_declaration(&Primitive::Named(name.clone()), node_id, cur_token.span, false);
+                }
+
+                // A compound assignment like `a += 1;` is desugared into `a = a + 1;`.
+                if let TokenKind::CompoundAssign(op) = next_token.kind {
+                    let variable = Expr {
+                        node: ExprKind::Identifier { name: name.clone() },
+                        node_id: self.next_node_id(),
+                        span: cur_token.span.clone(),
+                    };
+                    self.consume_next();
+                    let binop_id = self.next_node_id();
+                    let value = self.parse_expression(0.0)?;
+                    return Ok(Stmt::MutAssign {
+                        name: name.clone(),
+                        expr: Expr {
+                            span: variable.span.to(&value.span),
+                            node: ExprKind::BinOp {
+                                op,
+                                left: Box::new(variable),
+                                right: Box::new(value),
+                            },
+                            node_id: binop_id,
+                        },
+                        node_id,
+                        span: cur_token.span,
+                    });
+                }
+
+                // Without an assign token (ie. '=') the statement is a bare expression.
+                if !matches!(next_token.kind, TokenKind::BinOp(BinOpKind::Assign)) {
+                    self.unconsume();
+                    return Ok(Stmt::Expr {
+                        expr: self.parse_expression(0.0)?,
+                        node_id,
+                        span: cur_token.span,
+                    });
+                }
+                self.consume_next();
+
+                Ok(Stmt::MutAssign {
+                    name: name.clone(),
+                    expr: self.parse_assigned_value()?,
+                    node_id,
+                    span: cur_token.span,
+                })
+            }
+            TokenKind::Print => Ok(Stmt::Print {
+                expr: self.parse_keyword_argument("print")?,
+                node_id,
+                span: cur_token.span,
+            }),
+            TokenKind::EPrint => Ok(Stmt::EPrint {
+                expr: self.parse_keyword_argument("eprint")?,
+                node_id,
+                span: cur_token.span,
+            }),
+            TokenKind::Swap => {
+                self.expect_token(TokenKind::LParen, "Expected opening '(' after 'swap' keyword.")?;
+                let left = self.expect_identifier()?;
+                self.expect_token(TokenKind::Comma, "Expected ',' between the arguments of 'swap'.")?;
+                let right = self.expect_identifier()?;
+                self.expect_token(TokenKind::RParen, "Expected closing ')'.")?;
+
+                Ok(Stmt::Swap {
+                    left,
+                    right,
+                    node_id,
+                    span: cur_token.span,
+                })
+            }
+            TokenKind::If => self.parse_if(node_id, cur_token.span),
+            TokenKind::For => self.parse_for(node_id, cur_token.span),
+            TokenKind::Fn => self.parse_function(node_id, cur_token.span),
+            TokenKind::Return => Ok(Stmt::Return {
+                expr: match self.peek_next().kind {
+                    TokenKind::EOS => None,
+                    _ => Some(self.parse_expression(0.0)?),
+                },
+                node_id,
+                span: cur_token.span,
+            }),
+            TokenKind::LBrace => {
+                self.unconsume();
+                Ok(Stmt::Block {
+                    body: self.parse_block("block")?,
+                    node_id,
+                    span: cur_token.span,
+                })
+            }
+            // Bare expression statement, e.g. `a == 2;`.
+            TokenKind::Literal(_)
+            | TokenKind::LParen
+            | TokenKind::TypeOf
+            | TokenKind::BinOp(BinOpKind::Sub | BinOpKind::Add | BinOpKind::Not | BinOpKind::BitNot) => {
+                self.unconsume();
+                Ok(Stmt::Expr {
+                    expr: self.parse_expression(0.0)?,
+                    node_id,
+                    span: cur_token.span,
+                })
+            }
+            k => Err(CompilerError::SyntaxError {
+                message: format!("Unexpected token of kind {:?}.", k),
+                span: cur_token.span,
+                fixit: None,
+            }),
+        }
+    }
+
+    fn expect_eos(&mut self) -> Result<(), CompilerError> {
+        let next_token = self.peek_next();
+        match next_token.kind {
+            TokenKind::EOS => {
+                self.consume_next();
+                Ok(())
+            }
+            TokenKind::RParen => Err(CompilerError::SyntaxError {
+                message: "Unmatched ')'.".to_string(),
+                span: next_token.span,
+                fixit: None,
+            }),
+
+            // Point right behind the last token of the statement, not at the (possibly much later) EOF
+            // or the end of the enclosing block.
+            TokenKind::EOF | TokenKind::RBrace => {
+                let span = match self.prev_token() {
+                    Some(prev_token) => prev_token.span.end_point(),
+                    None => next_token.span,
+                };
+                Err(CompilerError::SyntaxError {
+                    message: "Missing ';' after statement.".to_string(),
+                    span: span.clone(),
+                    fixit: Some(Box::new(FixIt {
+                        span,
+                        len: 0,
+                        replacement: ";".to_string(),
+                    })),
+                })
+            }
+            t => Err(CompilerError::SyntaxError {
+                message: format!("Unexpected token {:?}, expected ';'.", t),
+                span: next_token.span,
+                fixit: None,
+            }),
+        }
+    }
+
+    /// Parses the next statement including its ';', returns `None` once all tokens are consumed.
+    /// Empty statements, i.e. stray ';', are skipped.
+    pub fn parse_next(&mut self) -> Result<Option<Stmt>, CompilerError> {
+        if let Some(declaration) = self.pending_declarations.pop_front() {
+            return Ok(Some(declaration));
+        }
+        self.skip_empty_statements();
+        if matches!(self.peek_next().kind, TokenKind::EOF) {
+            return Ok(None);
+        }
+        Ok(Some(self.parse_terminated_statement()?))
+    }
+
+    fn skip_empty_statements(&mut self) {
+        while matches!(self.peek_next().kind, TokenKind::EOS) {
+            self.consume_next();
+        }
+    }
+
+    /// Parses a statement and its terminating ';', which statements ending in a block don't have.
+    fn parse_terminated_statement(&mut self) -> Result<Stmt, CompilerError> {
+        let stmt = self.parse_statement()?;
+        if !matches!(stmt, Stmt::If { .. } | Stmt::Block { .. } | Stmt::For { .. } | Stmt::FnDecl { .. }) {
+            self.expect_eos()?;
+        }
+        Ok(stmt)
+    }
+
+    /// Skips the remaining tokens of a statement which failed to parse, up to and including its ';'.
+    pub fn synchronize(&mut self) {
+        self.pending_declarations.clear();
+        if matches!(self.prev_token(), Some(Token { kind: TokenKind::EOS, .. })) {
+            return;
+        }
+        loop {
+            match self.consume_next().kind {
+                TokenKind::EOS | TokenKind::EOF => break,
+                _ => (),
+            }
+        }
+    }
+
+    pub fn parse(&mut self) -> Result<(), CompilerError> {
+        while let Some(stmt) = self.parse_next()? {
+            self.tree.push(stmt);
+        }
+
+        Ok(())
+    }
+
+    /// Parses all statements like `parse`, but skips to the next statement after a syntax error
+    /// and returns all errors at once. The statements which parsed successfully are kept in the tree.
+    pub fn parse_all(&mut self) -> Result<(), Vec<CompilerError>> {
+        let mut errors = vec![];
+        loop {
+            match self.parse_next() {
+                Ok(Some(stmt)) => self.tree.push(stmt),
+                Ok(None) => break,
+                Err(err) => {
+                    errors.push(err);
+                    self.synchronize();
+                }
+            }
+        }
+
+        if errors.is_empty() { Ok(()) } else { Err(errors) }
+    }
+
+    pub fn get_tree(&self) -> &Ast {
+        &self.tree
+    }
+
+    pub fn get_warnings(&self) -> &Vec<CompilerWarning> {
+        &self.warnings
+    }
+
+    /// Consumes the parser and hands over the parsed AST without cloning it.
+    pub fn into_tree(self) -> Ast {
+        self.tree
+    }
+}
+
+/// Lexes and parses a source consisting of exactly one statement (including its ';'), e.g. to test
+/// a single grammar rule. Fails if any tokens remain after the statement.
+pub fn parse_single_statement(source: &str) -> Result<Stmt, CompilerError> {
+    let mut lexer = Lexer::new(source);
+    lexer.tokenize()?;
+
+    let mut parser = Parser::from_slice(lexer.get_tokens());
+    let stmt = parser.parse_terminated_statement()?;
+    let next_token = parser.peek_next();
+    if !matches!(next_token.kind, TokenKind::EOF) || !parser.pending_declarations.is_empty() {
+        return Err(CompilerError::SyntaxError {
+            message: "Expected a single statement, found trailing tokens.".to_string(),
+            span: next_token.span,
+            fixit: None,
+        });
+    }
+    Ok(stmt)
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+
+    fn parse(input: &str) -> Result<Ast, CompilerError> {
+        let mut lexer = Lexer::new(input);
+        lexer.tokenize()?;
+
+        let mut parser = Parser::new(lexer.get_tokens().to_vec());
+        parser.parse()?;
+
+        Ok(parser.into_tree())
+    }
+
+    // Resets spans and node ids, so that trees can be compared by their structure alone.
+    fn ignore_spans_expr(expr: Expr) -> Expr {
+        let node = match expr.node {
+            ExprKind::Literal { .. } | ExprKind::Identifier { .. } => expr.node,
+            ExprKind::Assign { name, expr } => ExprKind::Assign {
+                name,
+                expr: Box::new(ignore_spans_expr(*expr)),
+            },
+            ExprKind::UnaryOp { op, expr } => ExprKind::UnaryOp {
+                op,
+                expr: Box::new(ignore_spans_expr(*expr)),
+            },
+            ExprKind::TypeOf { expr } => ExprKind::TypeOf {
+                expr: Box::new(ignore_spans_expr(*expr)),
+            },
+            ExprKind::Call { name, args } => ExprKind::Call {
+                name,
+                args: args.into_iter().map(ignore_spans_expr).collect(),
+            },
+            ExprKind::BinOp { op, left, right } => ExprKind::BinOp {
+                op,
+                left: Box::new(ignore_spans_expr(*left)),
+                right: Box::new(ignore_spans_expr(*right)),
+            },
+        };
+        Expr::new(node, Span::default())
+    }
+
+    fn ignore_spans_stmt(stmt: Stmt) -> Stmt {
+        match stmt {
+            Stmt::Declare {
+                dtype,
+                name,
+                mutable,
+                expr,
+                ..
+            } => Stmt::Declare {
+                dtype,
+                name,
+                mutable,
+                expr: ignore_spans_expr(expr),
+                node_id: NodeId::default(),
+                span: Span::default(),
+            },
+            Stmt::Print { expr, .. } => Stmt::Print {
+                expr: ignore_spans_expr(expr),
+                node_id: NodeId::default(),
+                span: Span::default(),
+            },
+            Stmt::EPrint { expr, .. } => Stmt::EPrint {
+                expr: ignore_spans_expr(expr),
+                node_id: NodeId::default(),
+                span: Span::default(),
+            },
+            Stmt::MutAssign { name, expr, .. } => Stmt::MutAssign {
+                name,
+                expr: ignore_spans_expr(expr),
+                node_id: NodeId::default(),
+                span: Span::default(),
+            },
+            Stmt::Expr { expr, .. } => Stmt::Expr {
+                expr: ignore_spans_expr(expr),
+                node_id: NodeId::default(),
+                span: Span::default(),
+            },
+            Stmt::Swap { left, right, .. } => Stmt::Swap {
+                left,
+                right,
+                node_id: NodeId::default(),
+                span: Span::default(),
+            },
+            Stmt::Block { body, .. } => Stmt::Block {
+                body: ignore_spans_ast(body),
+                node_id: NodeId::default(),
+                span: Span::default(),
+            },
+            Stmt::For {
+                init, cond, step, body, ..
+            } => Stmt::For {
+                init: Box::new(ignore_spans_stmt(*init)),
+                cond: ignore_spans_expr(cond),
+                step: Box::new(ignore_spans_stmt(*step)),
+                body: ignore_spans_ast(body),
+                node_id: NodeId::default(),
+                span: Span::default(),
+            },
+            Stmt::If {
+                cond,
+                then_body,
+                else_body,
+                ..
+            } => Stmt::If {
+                cond: ignore_spans_expr(cond),
+                then_body: ignore_spans_ast(then_body),
+                else_body: else_body.map(ignore_spans_ast),
+                node_id: NodeId::default(),
+                span: Span::default(),
+            },
+            Stmt::FnDecl {
+                name, params, ret, body, ..
+            } => Stmt::FnDecl {
+                name,
+                params,
+                ret,
+                body: ignore_spans_ast(body),
+                node_id: NodeId::default(),
+                span: Span::default(),
+            },
+            Stmt::Return { expr, .. } => Stmt::Return {
+                expr: expr.map(ignore_spans_expr),
+                node_id: NodeId::default(),
+                span: Span::default(),
+            },
+        }
+    }
+
+    pub(crate) fn ignore_spans_ast(ast: Ast) -> Ast {
+        ast.into_iter().map(ignore_spans_stmt).collect()
+    }
 
     #[test]
     fn test_simple_statement() {
@@ -378,22 +1182,1001 @@ mod tests {
         assert_eq!(
             ignore_spans_ast(ast),
             [Stmt::Declare {
-                dtype: Primitive::Int,
+                dtype: Primitive::Int,
+                name: "a".to_string(),
+                expr: Expr {
+                    node: ExprKind::BinOp {
+                        op: BinOpKind::Add,
+                        left: Box::new(Expr {
+                            node: ExprKind::Literal {
+                                value: "1".to_string(),
+                                primitive: Primitive::Int,
+                            },
+                            node_id: NodeId::default(),
+                            span: Span::default(),
+                        }),
+                        right: Box::new(Expr {
+                            node: ExprKind::Literal {
+                                value: "2".to_string(),
+                                primitive: Primitive::Int,
+                            },
+                            node_id: NodeId::default(),
+                            span: Span::default(),
+                        }),
+                    },
+                    node_id: NodeId::default(),
+                    span: Span::default(),
+                },
+                node_id: NodeId::default(),
+                span: Span::default(),
+                mutable: false
+            }]
+        );
+    }
+
+    #[test]
+    fn test_left_side_precedence() {
+        let ast = parse("float a = 1 * 2 + 3.5;").unwrap();
+        assert_eq!(
+            ignore_spans_ast(ast),
+            [Stmt::Declare {
+                dtype: Primitive::Float,
+                name: "a".to_string(),
+                expr: Expr {
+                    node: ExprKind::BinOp {
+                        op: BinOpKind::Add,
+                        left: Box::new(Expr {
+                            node: ExprKind::BinOp {
+                                op: BinOpKind::Mult,
+                                left: Box::new(Expr {
+                                    node: ExprKind::Literal {
+                                        value: "1".to_string(),
+                                        primitive: Primitive::Int,
+                                    },
+                                    node_id: NodeId::default(),
+                                    span: Span::default(),
+                                }),
+                                right: Box::new(Expr {
+                                    node: ExprKind::Literal {
+                                        value: "2".to_string(),
+                                        primitive: Primitive::Int,
+                                    },
+                                    node_id: NodeId::default(),
+                                    span: Span::default(),
+                                }),
+                            },
+                            node_id: NodeId::default(),
+                            span: Span::default(),
+                        }),
+                        right: Box::new(Expr {
+                            node: ExprKind::Literal {
+                                value: "3.5".to_string(),
+                                primitive: Primitive::Float,
+                            },
+                            node_id: NodeId::default(),
+                            span: Span::default(),
+                        }),
+                    },
+                    node_id: NodeId::default(),
+                    span: Span::default(),
+                },
+                node_id: NodeId::default(),
+                span: Span::default(),
+                mutable: false
+            }]
+        );
+    }
+
+    #[test]
+    fn test_modulo_precedence() {
+        let ast = parse("int r = 1 + 10 % 3;").unwrap();
+        assert_eq!(
+            ignore_spans_ast(ast),
+            [Stmt::Declare {
+                dtype: Primitive::Int,
+                name: "r".to_string(),
+                expr: Expr {
+                    node: ExprKind::BinOp {
+                        op: BinOpKind::Add,
+                        left: Box::new(Expr {
+                            node: ExprKind::Literal {
+                                value: "1".to_string(),
+                                primitive: Primitive::Int,
+                            },
+                            node_id: NodeId::default(),
+                            span: Span::default(),
+                        }),
+                        right: Box::new(Expr {
+                            node: ExprKind::BinOp {
+                                op: BinOpKind::Mod,
+                                left: Box::new(Expr {
+                                    node: ExprKind::Literal {
+                                        value: "10".to_string(),
+                                        primitive: Primitive::Int,
+                                    },
+                                    node_id: NodeId::default(),
+                                    span: Span::default(),
+                                }),
+                                right: Box::new(Expr {
+                                    node: ExprKind::Literal {
+                                        value: "3".to_string(),
+                                        primitive: Primitive::Int,
+                                    },
+                                    node_id: NodeId::default(),
+                                    span: Span::default(),
+                                }),
+                            },
+                            node_id: NodeId::default(),
+                            span: Span::default(),
+                        }),
+                    },
+                    node_id: NodeId::default(),
+                    span: Span::default(),
+                },
+                node_id: NodeId::default(),
+                span: Span::default(),
+                mutable: false
+            }]
+        );
+
+        // '%' is left-associative with '*' and '/'.
+        let ast = parse("int r = 10 % 3 * 2;").unwrap();
+        assert!(matches!(
+            &ast[0],
+            Stmt::Declare { expr: Expr { node: ExprKind::BinOp { op: BinOpKind::Mult, left, .. }, .. }, .. }
+                if matches!(left.node, ExprKind::BinOp { op: BinOpKind::Mod, .. })
+        ));
+    }
+
+    #[test]
+    fn test_prefix_binding_power() {
+        let int_literal = |value: &str| Expr {
+            node: ExprKind::Literal {
+                value: value.to_string(),
+                primitive: Primitive::Int,
+            },
+            node_id: NodeId::default(),
+            span: Span::default(),
+        };
+        let negated = |expr: Expr| Expr {
+            node: ExprKind::UnaryOp {
+                op: UnaryOpKind::Neg,
+                expr: Box::new(expr),
+            },
+            node_id: NodeId::default(),
+            span: Span::default(),
+        };
+        let binop = |op: BinOpKind, left: Expr, right: Expr| Expr {
+            node: ExprKind::BinOp {
+                op,
+                left: Box::new(left),
+                right: Box::new(right),
+            },
+            node_id: NodeId::default(),
+            span: Span::default(),
+        };
+        let expressions = |input: &str| -> Vec<Expr> {
+            ignore_spans_ast(parse(input).unwrap())
+                .into_iter()
+                .map(|stmt| match stmt {
+                    Stmt::Declare { expr, .. } => expr,
+                    stmt => panic!("expected a declaration, found {:?}", stmt),
+                })
+                .collect()
+        };
+
+        // `-2 * 3` is `(-2) * 3`.
+        assert_eq!(
+            expressions("int a = -2 * 3;"),
+            [binop(BinOpKind::Mult, negated(int_literal("2")), int_literal("3"))]
+        );
+
+        // `-2 ^ 2` is `-(2 ^ 2)`, and so is `2 ^ -2 ^ 2` on the right of '^'.
+        assert_eq!(
+            expressions("int b = -2 ^ 2;\nfloat c = 2 ^ -2 ^ 2;"),
+            [
+                negated(binop(BinOpKind::Pow, int_literal("2"), int_literal("2"))),
+                binop(
+                    BinOpKind::Pow,
+                    int_literal("2"),
+                    negated(binop(BinOpKind::Pow, int_literal("2"), int_literal("2")))
+                ),
+            ]
+        );
+
+        // `-2 ^ 2 * 3` is `(-(2 ^ 2)) * 3`.
+        assert_eq!(
+            expressions("int d = -2 ^ 2 * 3;"),
+            [binop(
+                BinOpKind::Mult,
+                negated(binop(BinOpKind::Pow, int_literal("2"), int_literal("2"))),
+                int_literal("3")
+            )]
+        );
+    }
+
+    #[test]
+    fn test_power_right_associativity() {
+        let ast = parse("int a = 2 ^ 3 ^ 2;").unwrap();
+        assert_eq!(
+            ignore_spans_ast(ast),
+            [Stmt::Declare {
+                dtype: Primitive::Int,
+                name: "a".to_string(),
+                expr: Expr {
+                    node: ExprKind::BinOp {
+                        op: BinOpKind::Pow,
+                        left: Box::new(Expr {
+                            node: ExprKind::Literal {
+                                value: "2".to_string(),
+                                primitive: Primitive::Int,
+                            },
+                            node_id: NodeId::default(),
+                            span: Span::default(),
+                        }),
+                        right: Box::new(Expr {
+                            node: ExprKind::BinOp {
+                                op: BinOpKind::Pow,
+                                left: Box::new(Expr {
+                                    node: ExprKind::Literal {
+                                        value: "3".to_string(),
+                                        primitive: Primitive::Int,
+                                    },
+                                    node_id: NodeId::default(),
+                                    span: Span::default(),
+                                }),
+                                right: Box::new(Expr {
+                                    node: ExprKind::Literal {
+                                        value: "2".to_string(),
+                                        primitive: Primitive::Int,
+                                    },
+                                    node_id: NodeId::default(),
+                                    span: Span::default(),
+                                }),
+                            },
+                            node_id: NodeId::default(),
+                            span: Span::default(),
+                        }),
+                    },
+                    node_id: NodeId::default(),
+                    span: Span::default(),
+                },
+                node_id: NodeId::default(),
+                span: Span::default(),
+                mutable: false
+            }]
+        );
+
+        // '^' binds tighter than '*', `2 * 3 ^ 2` is `2 * (3 ^ 2)`.
+        let ast = parse("int a = 2 * 3 ^ 2;").unwrap();
+        assert!(matches!(
+            &ast[0],
+            Stmt::Declare { expr: Expr { node: ExprKind::BinOp { op: BinOpKind::Mult, right, .. }, .. }, .. }
+                if matches!(right.node, ExprKind::BinOp { op: BinOpKind::Pow, .. })
+        ));
+    }
+
+    #[test]
+    fn test_right_side_precedence() {
+        let ast = parse("float a = 0.3333 - 2 / 3;").unwrap();
+        assert_eq!(
+            ignore_spans_ast(ast),
+            [Stmt::Declare {
+                dtype: Primitive::Float,
+                name: "a".to_string(),
+                expr: Expr {
+                    node: ExprKind::BinOp {
+                        op: BinOpKind::Sub,
+                        left: Box::new(Expr {
+                            node: ExprKind::Literal {
+                                value: "0.3333".to_string(),
+                                primitive: Primitive::Float,
+                            },
+                            node_id: NodeId::default(),
+                            span: Span::default(),
+                        }),
+                        right: Box::new(Expr {
+                            node: ExprKind::BinOp {
+                                op: BinOpKind::Div,
+                                left: Box::new(Expr {
+                                    node: ExprKind::Literal {
+                                        value: "2".to_string(),
+                                        primitive: Primitive::Int,
+                                    },
+                                    node_id: NodeId::default(),
+                                    span: Span::default(),
+                                }),
+                                right: Box::new(Expr {
+                                    node: ExprKind::Literal {
+                                        value: "3".to_string(),
+                                        primitive: Primitive::Int,
+                                    },
+                                    node_id: NodeId::default(),
+                                    span: Span::default(),
+                                }),
+                            },
+                            node_id: NodeId::default(),
+                            span: Span::default(),
+                        }),
+                    },
+                    node_id: NodeId::default(),
+                    span: Span::default(),
+                },
+                node_id: NodeId::default(),
+                span: Span::default(),
+                mutable: false
+            }]
+        );
+    }
+
+    #[test]
+    fn test_unary_sign_operator() {
+        let ast = parse("int res = -b * +3;").unwrap();
+        assert_eq!(
+            ignore_spans_ast(ast),
+            [Stmt::Declare {
+                dtype: Primitive::Int,
+                name: "res".to_string(),
+                expr: Expr {
+                    node: ExprKind::BinOp {
+                        op: BinOpKind::Mult,
+                        left: Box::new(Expr {
+                            node: ExprKind::UnaryOp {
+                                op: UnaryOpKind::Neg,
+                                expr: Box::new(Expr {
+                                    node: ExprKind::Identifier { name: "b".to_string() },
+                                    node_id: NodeId::default(),
+                                    span: Span::default(),
+                                }),
+                            },
+                            node_id: NodeId::default(),
+                            span: Span::default(),
+                        }),
+                        right: Box::new(Expr {
+                            node: ExprKind::Literal {
+                                value: "3".to_string(),
+                                primitive: Primitive::Int,
+                            },
+                            node_id: NodeId::default(),
+                            span: Span::default(),
+                        }),
+                    },
+                    node_id: NodeId::default(),
+                    span: Span::default(),
+                },
+                node_id: NodeId::default(),
+                span: Span::default(),
+                mutable: false
+            }]
+        );
+    }
+
+    #[test]
+    fn test_unary_plus_is_collapsed() {
+        let expected = ignore_spans_ast(parse("int a = x;").unwrap());
+        assert_eq!(ignore_spans_ast(parse("int a = +x;").unwrap()), expected);
+        assert_eq!(ignore_spans_ast(parse("int a = + + x;").unwrap()), expected);
+    }
+
+    #[test]
+    fn test_unary_plus_without_operand() {
+        let result = parse("int a = +;");
+        assert!(matches!(
+            result,
+            Err(CompilerError::SyntaxError { message, span, .. }) if message == "Expected an operand after unary '+'." && span.col == 10
+        ));
+
+        let result = parse("int a = + * x;");
+        assert!(matches!(
+            result,
+            Err(CompilerError::SyntaxError { span, .. }) if span.col == 11
+        ));
+    }
+
+    #[test]
+    fn test_operator_after_operator() {
+        let result = parse("int a = 1 + * 2;");
+        assert!(matches!(
+            result,
+            Err(CompilerError::SyntaxError { message, span, .. }) if message == "Expected an operand, found operator '*'." && span.col == 13
+        ));
+
+        let result = parse("int a = 1 * / 2;");
+        assert!(matches!(
+            result,
+            Err(CompilerError::SyntaxError { message, span, .. }) if message == "Expected an operand, found operator '/'." && span.col == 13
+        ));
+
+        let result = parse("print(a == >= b);");
+        assert!(matches!(
+            result,
+            Err(CompilerError::SyntaxError { message, .. }) if message == "Expected an operand, found operator '>='."
+        ));
+    }
+
+    #[test]
+    fn test_word_operators() {
+        let mut lexer = Lexer::new("bool b = a and not c;");
+        lexer.word_operators(true);
+        lexer.tokenize().unwrap();
+        let mut parser = Parser::new(lexer.get_tokens().to_vec());
+        parser.parse().unwrap();
+        let words: Ast = parser.into_tree().into_iter().map(ignore_spans_stmt).collect();
+
+        let symbols: Ast = parse("bool b = a && !c;").unwrap().into_iter().map(ignore_spans_stmt).collect();
+        assert_eq!(words, symbols);
+    }
+
+    #[test]
+    fn test_bitwise_precedence() {
+        let cases = [
+            ("int a = 1 | 2 & 3 << 4 + 5;", "int a = 1 | (2 & (3 << (4 + 5)));"),
+            ("int a = 1 << 2 >> 3;", "int a = (1 << 2) >> 3;"),
+            ("int a = ~b & c;", "int a = (~b) & c;"),
+            ("bool b = a & 1 == 0 || a | 2 > 3;", "bool b = ((a & 1) == 0) || ((a | 2) > 3);"),
+        ];
+        for (input, parenthesized) in cases {
+            let ast: Ast = parse(input).unwrap().into_iter().map(ignore_spans_stmt).collect();
+            let expected: Ast = parse(parenthesized).unwrap().into_iter().map(ignore_spans_stmt).collect();
+            assert_eq!(ast, expected, "{}", input);
+        }
+
+        assert!(matches!(
+            &parse("~a;").unwrap()[0],
+            Stmt::Expr { expr: Expr { node: ExprKind::UnaryOp { op: UnaryOpKind::BitNot, .. }, .. }, .. }
+        ));
+        assert_eq!(parentheses_warnings("bool b = a & 1 == 0;").len(), 1);
+    }
+
+    fn parse_assignment_expressions(input: &str) -> Result<Ast, CompilerError> {
+        let mut lexer = Lexer::new(input);
+        lexer.tokenize()?;
+
+        let mut parser = Parser::new(lexer.get_tokens().to_vec());
+        parser.assignment_expressions(true);
+        parser.parse()?;
+        Ok(parser.into_tree())
+    }
+
+    #[test]
+    fn test_assignment_expression() {
+        let ast = parse_assignment_expressions("a = b = 5;\n(c = 1) > 0;").unwrap();
+        assert_eq!(
+            ignore_spans_ast(ast),
+            [
+                Stmt::MutAssign {
+                    name: "a".to_string(),
+                    expr: Expr {
+                        node: ExprKind::Assign {
+                            name: "b".to_string(),
+                            expr: Box::new(Expr {
+                                node: ExprKind::Literal {
+                                    value: "5".to_string(),
+                                    primitive: Primitive::Int,
+                                },
+                                node_id: NodeId::default(),
+                                span: Span::default(),
+                            }),
+                        },
+                        node_id: NodeId::default(),
+                        span: Span::default(),
+                    },
+                    node_id: NodeId::default(),
+                    span: Span::default(),
+                },
+                Stmt::Expr {
+                    expr: Expr {
+                        node: ExprKind::BinOp {
+                            op: BinOpKind::Gt,
+                            left: Box::new(Expr {
+                                node: ExprKind::Assign {
+                                    name: "c".to_string(),
+                                    expr: Box::new(Expr {
+                                        node: ExprKind::Literal {
+                                            value: "1".to_string(),
+                                            primitive: Primitive::Int,
+                                        },
+                                        node_id: NodeId::default(),
+                                        span: Span::default(),
+                                    }),
+                                },
+                                node_id: NodeId::default(),
+                                span: Span::default(),
+                            }),
+                            right: Box::new(Expr {
+                                node: ExprKind::Literal {
+                                    value: "0".to_string(),
+                                    primitive: Primitive::Int,
+                                },
+                                node_id: NodeId::default(),
+                                span: Span::default(),
+                            }),
+                        },
+                        node_id: NodeId::default(),
+                        span: Span::default(),
+                    },
+                    node_id: NodeId::default(),
+                    span: Span::default(),
+                }
+            ]
+        );
+
+        // The right-hand side of the assignment extends as far as possible.
+        let ast = parse_assignment_expressions("a = b = c + 1;").unwrap();
+        assert!(matches!(
+            &ast[0],
+            Stmt::MutAssign { expr: Expr { node: ExprKind::Assign { expr, .. }, .. }, .. } if matches!(expr.node, ExprKind::BinOp { op: BinOpKind::Add, .. })
+        ));
+
+        let result = parse_assignment_expressions("a = 1 + b = 2;");
+        assert!(matches!(
+            result,
+            Err(CompilerError::SyntaxError { message, span, .. }) if message == "Invalid assignment target, expected a variable name." && span.col == 11
+        ));
+
+        // A chained assignment can be used as the step of a for loop.
+        let ast = parse_assignment_expressions("for (mut int i = 0; i < 9; i = j = i + 1) {}").unwrap();
+        let Stmt::For { step, .. } = &ast[0] else {
+            panic!("expected a for loop, found {:?}", ast[0]);
+        };
+        assert!(matches!(
+            step.as_ref(),
+            Stmt::MutAssign { name, expr: Expr { node: ExprKind::Assign { name: inner, .. }, .. }, .. } if name == "i" && inner == "j"
+        ));
+
+        // Without the flag assignment remains a statement, which may only chain further assignments.
+        assert!(parse("(c = 1) > 0;").is_err());
+        assert!(parse("print(a = 2);").is_err());
+        assert!(parse("a = 1 + b = 2;").is_err());
+        assert_eq!(
+            ignore_spans_ast(parse("a = b = c + 1;").unwrap()),
+            ignore_spans_ast(parse_assignment_expressions("a = b = c + 1;").unwrap())
+        );
+        assert_eq!(
+            ignore_spans_ast(parse("for (mut int i = 0; i < 9; i = j = i + 1) {}").unwrap()),
+            ignore_spans_ast(parse_assignment_expressions("for (mut int i = 0; i < 9; i = j = i + 1) {}").unwrap())
+        );
+    }
+
+    fn parentheses_warnings(input: &str) -> Vec<CompilerWarning> {
+        let mut lexer = Lexer::new(input);
+        lexer.tokenize().unwrap();
+
+        let mut parser = Parser::new(lexer.get_tokens().to_vec());
+        parser.suggest_parentheses(true);
+        parser.parse().unwrap();
+        parser.warnings
+    }
+
+    #[test]
+    fn test_suggest_parentheses() {
+        assert_eq!(
+            parentheses_warnings("bool d = a && b || c;"),
+            vec![CompilerWarning::SuggestParentheses {
+                op: BinOpKind::Or,
+                span: Span { line: 1, col: 17, end_line: 1, end_col: 19, file_id: None },
+            }]
+        );
+        assert_eq!(
+            parentheses_warnings("bool d = 1 + 2 < 3;"),
+            vec![CompilerWarning::SuggestParentheses {
+                op: BinOpKind::Lt,
+                span: Span { line: 1, col: 16, end_line: 1, end_col: 17, file_id: None },
+            }]
+        );
+        assert!(parentheses_warnings("bool d = (a && b) || c;").is_empty());
+        assert!(parentheses_warnings("bool d = a || (b && c);").is_empty());
+        assert!(parentheses_warnings("bool d = (1 + 2) < 3 && a && b;").is_empty());
+
+        let mut lexer = Lexer::new("bool d = a && b || c;");
+        lexer.tokenize().unwrap();
+        let mut parser = Parser::new(lexer.get_tokens().to_vec());
+        parser.parse().unwrap();
+        assert!(parser.get_warnings().is_empty());
+    }
+
+    #[test]
+    fn test_simple_parentheses() {
+        let ast = parse("int c = (1 + 2) * 3;").unwrap();
+        assert_eq!(
+            ignore_spans_ast(ast),
+            [Stmt::Declare {
+                dtype: Primitive::Int,
+                name: "c".to_string(),
+                expr: Expr {
+                    node: ExprKind::BinOp {
+                        op: BinOpKind::Mult,
+                        left: Box::new(Expr {
+                            node: ExprKind::BinOp {
+                                op: BinOpKind::Add,
+                                left: Box::new(Expr {
+                                    node: ExprKind::Literal {
+                                        value: "1".to_string(),
+                                        primitive: Primitive::Int,
+                                    },
+                                    node_id: NodeId::default(),
+                                    span: Span::default(),
+                                }),
+                                right: Box::new(Expr {
+                                    node: ExprKind::Literal {
+                                        value: "2".to_string(),
+                                        primitive: Primitive::Int,
+                                    },
+                                    node_id: NodeId::default(),
+                                    span: Span::default(),
+                                }),
+                            },
+                            node_id: NodeId::default(),
+                            span: Span::default(),
+                        }),
+                        right: Box::new(Expr {
+                            node: ExprKind::Literal {
+                                value: "3".to_string(),
+                                primitive: Primitive::Int,
+                            },
+                            node_id: NodeId::default(),
+                            span: Span::default(),
+                        }),
+                    },
+                    node_id: NodeId::default(),
+                    span: Span::default(),
+                },
+                node_id: NodeId::default(),
+                span: Span::default(),
+                mutable: false
+            }]
+        );
+    }
+
+    #[test]
+    fn test_nested_parentheses() {
+        let ast = parse("float c = ((1 + a) * b) / (a - b);").unwrap();
+        assert_eq!(
+            ignore_spans_ast(ast),
+            [Stmt::Declare {
+                dtype: Primitive::Float,
+                name: "c".to_string(),
+                expr: Expr {
+                    node: ExprKind::BinOp {
+                        op: BinOpKind::Div,
+                        left: Box::new(Expr {
+                            node: ExprKind::BinOp {
+                                op: BinOpKind::Mult,
+                                left: Box::new(Expr {
+                                    node: ExprKind::BinOp {
+                                        op: BinOpKind::Add,
+                                        left: Box::new(Expr {
+                                            node: ExprKind::Literal {
+                                                value: "1".to_string(),
+                                                primitive: Primitive::Int,
+                                            },
+                                            node_id: NodeId::default(),
+                                            span: Span::default(),
+                                        }),
+                                        right: Box::new(Expr {
+                                            node: ExprKind::Identifier { name: "a".to_string() },
+                                            node_id: NodeId::default(),
+                                            span: Span::default(),
+                                        }),
+                                    },
+                                    node_id: NodeId::default(),
+                                    span: Span::default(),
+                                }),
+                                right: Box::new(Expr {
+                                    node: ExprKind::Identifier { name: "b".to_string() },
+                                    node_id: NodeId::default(),
+                                    span: Span::default(),
+                                }),
+                            },
+                            node_id: NodeId::default(),
+                            span: Span::default(),
+                        }),
+                        right: Box::new(Expr {
+                            node: ExprKind::BinOp {
+                                op: BinOpKind::Sub,
+                                left: Box::new(Expr {
+                                    node: ExprKind::Identifier { name: "a".to_string() },
+                                    node_id: NodeId::default(),
+                                    span: Span::default(),
+                                }),
+                                right: Box::new(Expr {
+                                    node: ExprKind::Identifier { name: "b".to_string() },
+                                    node_id: NodeId::default(),
+                                    span: Span::default(),
+                                }),
+                            },
+                            node_id: NodeId::default(),
+                            span: Span::default(),
+                        }),
+                    },
+                    node_id: NodeId::default(),
+                    span: Span::default(),
+                },
+                node_id: NodeId::default(),
+                span: Span::default(),
+                mutable: false
+            }]
+        );
+    }
+
+    #[test]
+    fn test_print_statement() {
+        let ast = parse("print(1 * b);").unwrap();
+        assert_eq!(
+            ignore_spans_ast(ast),
+            [Stmt::Print {
+                expr: Expr {
+                    node: ExprKind::BinOp {
+                        op: BinOpKind::Mult,
+                        left: Box::new(Expr {
+                            node: ExprKind::Literal {
+                                value: "1".to_string(),
+                                primitive: Primitive::Int,
+                            },
+                            node_id: NodeId::default(),
+                            span: Span::default(),
+                        }),
+                        right: Box::new(Expr {
+                            node: ExprKind::Identifier { name: "b".to_string() },
+                            node_id: NodeId::default(),
+                            span: Span::default(),
+                        }),
+                    },
+                    node_id: NodeId::default(),
+                    span: Span::default(),
+                },
+                node_id: NodeId::default(),
+                span: Span::default()
+            }]
+        );
+    }
+
+    #[test]
+    fn test_eprint_statement() {
+        let ast = parse("eprint(b);").unwrap();
+        assert_eq!(
+            ast,
+            [Stmt::EPrint {
+                expr: Expr {
+                    node: ExprKind::Identifier { name: "b".to_string() },
+                    node_id: NodeId(1),
+                    span: Span { line: 1, col: 8, end_line: 1, end_col: 9, file_id: None },
+                },
+                node_id: NodeId(0),
+                span: Span { line: 1, col: 1, end_line: 1, end_col: 7, file_id: None }
+            }]
+        );
+
+        let result = parse("eprint b;");
+        assert!(matches!(
+            result,
+            Err(CompilerError::SyntaxError { message, span, .. }) if message == "Expected opening '(' after 'eprint' keyword." && span.col == 8
+        ));
+    }
+
+    #[test]
+    fn test_print_statement_with_parentheses() {
+        let ast = parse("print((1 - b) * c);").unwrap();
+        assert_eq!(
+            ignore_spans_ast(ast),
+            [Stmt::Print {
+                expr: Expr {
+                    node: ExprKind::BinOp {
+                        op: BinOpKind::Mult,
+                        left: Box::new(Expr {
+                            node: ExprKind::BinOp {
+                                op: BinOpKind::Sub,
+                                left: Box::new(Expr {
+                                    node: ExprKind::Literal {
+                                        value: "1".to_string(),
+                                        primitive: Primitive::Int,
+                                    },
+                                    node_id: NodeId::default(),
+                                    span: Span::default(),
+                                }),
+                                right: Box::new(Expr {
+                                    node: ExprKind::Identifier { name: "b".to_string() },
+                                    node_id: NodeId::default(),
+                                    span: Span::default(),
+                                }),
+                            },
+                            node_id: NodeId::default(),
+                            span: Span::default(),
+                        }),
+                        right: Box::new(Expr {
+                            node: ExprKind::Identifier { name: "c".to_string() },
+                            node_id: NodeId::default(),
+                            span: Span::default(),
+                        }),
+                    },
+                    node_id: NodeId::default(),
+                    span: Span::default(),
+                },
+                node_id: NodeId::default(),
+                span: Span::default()
+            }]
+        );
+    }
+
+    #[test]
+    fn test_boolean_statement() {
+        let ast = parse("bool a = true || (b >= 4);").unwrap();
+        assert_eq!(
+            ignore_spans_ast(ast),
+            [Stmt::Declare {
+                dtype: Primitive::Bool,
+                name: "a".to_string(),
+                expr: Expr {
+                    node: ExprKind::BinOp {
+                        op: BinOpKind::Or,
+                        left: Box::new(Expr {
+                            node: ExprKind::Literal {
+                                value: "true".to_string(),
+                                primitive: Primitive::Bool,
+                            },
+                            node_id: NodeId::default(),
+                            span: Span::default(),
+                        }),
+                        right: Box::new(Expr {
+                            node: ExprKind::BinOp {
+                                op: BinOpKind::Ge,
+                                left: Box::new(Expr {
+                                    node: ExprKind::Identifier { name: "b".to_string() },
+                                    node_id: NodeId::default(),
+                                    span: Span::default(),
+                                }),
+                                right: Box::new(Expr {
+                                    node: ExprKind::Literal {
+                                        value: "4".to_string(),
+                                        primitive: Primitive::Int,
+                                    },
+                                    node_id: NodeId::default(),
+                                    span: Span::default(),
+                                }),
+                            },
+                            node_id: NodeId::default(),
+                            span: Span::default(),
+                        }),
+                    },
+                    node_id: NodeId::default(),
+                    span: Span::default(),
+                },
+                node_id: NodeId::default(),
+                span: Span::default(),
+                mutable: false
+            }]
+        );
+    }
+
+    #[test]
+    fn test_logical_not_unary_operation() {
+        let ast = parse("bool a = !(true && !b);").unwrap();
+        assert_eq!(
+            ignore_spans_ast(ast),
+            [Stmt::Declare {
+                dtype: Primitive::Bool,
+                name: "a".to_string(),
+                expr: Expr {
+                    node: ExprKind::UnaryOp {
+                        op: UnaryOpKind::Not,
+                        expr: Box::new(Expr {
+                            node: ExprKind::BinOp {
+                                op: BinOpKind::And,
+                                left: Box::new(Expr {
+                                    node: ExprKind::Literal {
+                                        value: "true".to_string(),
+                                        primitive: Primitive::Bool,
+                                    },
+                                    node_id: NodeId::default(),
+                                    span: Span::default(),
+                                }),
+                                right: Box::new(Expr {
+                                    node: ExprKind::UnaryOp {
+                                        op: UnaryOpKind::Not,
+                                        expr: Box::new(Expr {
+                                            node: ExprKind::Identifier { name: "b".to_string() },
+                                            node_id: NodeId::default(),
+                                            span: Span::default(),
+                                        }),
+                                    },
+                                    node_id: NodeId::default(),
+                                    span: Span::default(),
+                                }),
+                            },
+                            node_id: NodeId::default(),
+                            span: Span::default(),
+                        }),
+                    },
+                    node_id: NodeId::default(),
+                    span: Span::default(),
+                },
+                node_id: NodeId::default(),
+                span: Span::default(),
+                mutable: false
+            }]
+        );
+    }
+
+    #[test]
+    fn test_boolean_precedence() {
+        let ast = parse("bool a = true || b >= 4 && c == d != e;").unwrap();
+        assert_eq!(
+            ignore_spans_ast(ast),
+            [Stmt::Declare {
+                dtype: Primitive::Bool,
                 name: "a".to_string(),
-                expr: Expr::BinOp {
-                    op: BinOpKind::Add,
-                    left: Box::new(Expr::Literal {
-                        value: "1".to_string(),
-                        primitive: Primitive::Int,
-                        span: Span::default()
-                    }),
-                    right: Box::new(Expr::Literal {
-                        value: "2".to_string(),
-                        primitive: Primitive::Int,
-                        span: Span::default()
-                    }),
-                    span: Span::default()
+                expr: Expr {
+                    node: ExprKind::BinOp {
+                        op: BinOpKind::Or,
+                        left: Box::new(Expr {
+                            node: ExprKind::Literal {
+                                value: "true".to_string(),
+                                primitive: Primitive::Bool,
+                            },
+                            node_id: NodeId::default(),
+                            span: Span::default(),
+                        }),
+                        right: Box::new(Expr {
+                            node: ExprKind::BinOp {
+                                op: BinOpKind::And,
+                                left: Box::new(Expr {
+                                    node: ExprKind::BinOp {
+                                        op: BinOpKind::Ge,
+                                        left: Box::new(Expr {
+                                            node: ExprKind::Identifier { name: "b".to_string() },
+                                            node_id: NodeId::default(),
+                                            span: Span::default(),
+                                        }),
+                                        right: Box::new(Expr {
+                                            node: ExprKind::Literal {
+                                                value: "4".to_string(),
+                                                primitive: Primitive::Int,
+                                            },
+                                            node_id: NodeId::default(),
+                                            span: Span::default(),
+                                        }),
+                                    },
+                                    node_id: NodeId::default(),
+                                    span: Span::default(),
+                                }),
+                                right: Box::new(Expr {
+                                    node: ExprKind::BinOp {
+                                        op: BinOpKind::Ne,
+                                        left: Box::new(Expr {
+                                            node: ExprKind::BinOp {
+                                                op: BinOpKind::Eq,
+                                                left: Box::new(Expr {
+                                                    node: ExprKind::Identifier { name: "c".to_string() },
+                                                    node_id: NodeId::default(),
+                                                    span: Span::default(),
+                                                }),
+                                                right: Box::new(Expr {
+                                                    node: ExprKind::Identifier { name: "d".to_string() },
+                                                    node_id: NodeId::default(),
+                                                    span: Span::default(),
+                                                }),
+                                            },
+                                            node_id: NodeId::default(),
+                                            span: Span::default(),
+                                        }),
+                                        right: Box::new(Expr {
+                                            node: ExprKind::Identifier { name: "e".to_string() },
+                                            node_id: NodeId::default(),
+                                            span: Span::default(),
+                                        }),
+                                    },
+                                    node_id: NodeId::default(),
+                                    span: Span::default(),
+                                }),
+                            },
+                            node_id: NodeId::default(),
+                            span: Span::default(),
+                        }),
+                    },
+                    node_id: NodeId::default(),
+                    span: Span::default(),
                 },
+                node_id: NodeId::default(),
                 span: Span::default(),
                 mutable: false
             }]
@@ -401,36 +2184,85 @@ mod tests {
     }
 
     #[test]
-    fn test_left_side_precedence() {
-        let ast = parse("float a = 1 * 2 + 3.5;").unwrap();
+    fn test_bool_expr_without_whitespaces() {
+        let ast = parse("bool a=true||b>=4&&c==d!=e;").unwrap();
         assert_eq!(
             ignore_spans_ast(ast),
             [Stmt::Declare {
-                dtype: Primitive::Float,
+                dtype: Primitive::Bool,
                 name: "a".to_string(),
-                expr: Expr::BinOp {
-                    op: BinOpKind::Add,
-                    left: Box::new(Expr::BinOp {
-                        op: BinOpKind::Mult,
-                        left: Box::new(Expr::Literal {
-                            value: "1".to_string(),
-                            primitive: Primitive::Int,
-                            span: Span::default()
-                        }),
-                        right: Box::new(Expr::Literal {
-                            value: "2".to_string(),
-                            primitive: Primitive::Int,
-                            span: Span::default()
-                        }),
-                        span: Span::default()
-                    }),
-                    right: Box::new(Expr::Literal {
-                        value: "3.5".to_string(),
-                        primitive: Primitive::Float,
-                        span: Span::default()
-                    }),
-                    span: Span::default()
+                expr: Expr {
+                    node: ExprKind::BinOp {
+                        op: BinOpKind::Or,
+                        left: Box::new(Expr {
+                            node: ExprKind::Literal {
+                                value: "true".to_string(),
+                                primitive: Primitive::Bool,
+                            },
+                            node_id: NodeId::default(),
+                            span: Span::default(),
+                        }),
+                        right: Box::new(Expr {
+                            node: ExprKind::BinOp {
+                                op: BinOpKind::And,
+                                left: Box::new(Expr {
+                                    node: ExprKind::BinOp {
+                                        op: BinOpKind::Ge,
+                                        left: Box::new(Expr {
+                                            node: ExprKind::Identifier { name: "b".to_string() },
+                                            node_id: NodeId::default(),
+                                            span: Span::default(),
+                                        }),
+                                        right: Box::new(Expr {
+                                            node: ExprKind::Literal {
+                                                value: "4".to_string(),
+                                                primitive: Primitive::Int,
+                                            },
+                                            node_id: NodeId::default(),
+                                            span: Span::default(),
+                                        }),
+                                    },
+                                    node_id: NodeId::default(),
+                                    span: Span::default(),
+                                }),
+                                right: Box::new(Expr {
+                                    node: ExprKind::BinOp {
+                                        op: BinOpKind::Ne,
+                                        left: Box::new(Expr {
+                                            node: ExprKind::BinOp {
+                                                op: BinOpKind::Eq,
+                                                left: Box::new(Expr {
+                                                    node: ExprKind::Identifier { name: "c".to_string() },
+                                                    node_id: NodeId::default(),
+                                                    span: Span::default(),
+                                                }),
+                                                right: Box::new(Expr {
+                                                    node: ExprKind::Identifier { name: "d".to_string() },
+                                                    node_id: NodeId::default(),
+                                                    span: Span::default(),
+                                                }),
+                                            },
+                                            node_id: NodeId::default(),
+                                            span: Span::default(),
+                                        }),
+                                        right: Box::new(Expr {
+                                            node: ExprKind::Identifier { name: "e".to_string() },
+                                            node_id: NodeId::default(),
+                                            span: Span::default(),
+                                        }),
+                                    },
+                                    node_id: NodeId::default(),
+                                    span: Span::default(),
+                                }),
+                            },
+                            node_id: NodeId::default(),
+                            span: Span::default(),
+                        }),
+                    },
+                    node_id: NodeId::default(),
+                    span: Span::default(),
                 },
+                node_id: NodeId::default(),
                 span: Span::default(),
                 mutable: false
             }]
@@ -438,36 +2270,70 @@ mod tests {
     }
 
     #[test]
-    fn test_right_side_precedence() {
-        let ast = parse("float a = 0.3333 - 2 / 3;").unwrap();
+    fn test_arithm_expr_without_whitespaces() {
+        let ast = parse("float c=((1+a)*b)/(a-b);").unwrap();
         assert_eq!(
             ignore_spans_ast(ast),
             [Stmt::Declare {
                 dtype: Primitive::Float,
-                name: "a".to_string(),
-                expr: Expr::BinOp {
-                    op: BinOpKind::Sub,
-                    left: Box::new(Expr::Literal {
-                        value: "0.3333".to_string(),
-                        primitive: Primitive::Float,
-                        span: Span::default()
-                    }),
-                    right: Box::new(Expr::BinOp {
+                name: "c".to_string(),
+                expr: Expr {
+                    node: ExprKind::BinOp {
                         op: BinOpKind::Div,
-                        left: Box::new(Expr::Literal {
-                            value: "2".to_string(),
-                            primitive: Primitive::Int,
-                            span: Span::default()
-                        }),
-                        right: Box::new(Expr::Literal {
-                            value: "3".to_string(),
-                            primitive: Primitive::Int,
-                            span: Span::default()
-                        }),
-                        span: Span::default()
-                    }),
-                    span: Span::default()
+                        left: Box::new(Expr {
+                            node: ExprKind::BinOp {
+                                op: BinOpKind::Mult,
+                                left: Box::new(Expr {
+                                    node: ExprKind::BinOp {
+                                        op: BinOpKind::Add,
+                                        left: Box::new(Expr {
+                                            node: ExprKind::Literal {
+                                                value: "1".to_string(),
+                                                primitive: Primitive::Int,
+                                            },
+                                            node_id: NodeId::default(),
+                                            span: Span::default(),
+                                        }),
+                                        right: Box::new(Expr {
+                                            node: ExprKind::Identifier { name: "a".to_string() },
+                                            node_id: NodeId::default(),
+                                            span: Span::default(),
+                                        }),
+                                    },
+                                    node_id: NodeId::default(),
+                                    span: Span::default(),
+                                }),
+                                right: Box::new(Expr {
+                                    node: ExprKind::Identifier { name: "b".to_string() },
+                                    node_id: NodeId::default(),
+                                    span: Span::default(),
+                                }),
+                            },
+                            node_id: NodeId::default(),
+                            span: Span::default(),
+                        }),
+                        right: Box::new(Expr {
+                            node: ExprKind::BinOp {
+                                op: BinOpKind::Sub,
+                                left: Box::new(Expr {
+                                    node: ExprKind::Identifier { name: "a".to_string() },
+                                    node_id: NodeId::default(),
+                                    span: Span::default(),
+                                }),
+                                right: Box::new(Expr {
+                                    node: ExprKind::Identifier { name: "b".to_string() },
+                                    node_id: NodeId::default(),
+                                    span: Span::default(),
+                                }),
+                            },
+                            node_id: NodeId::default(),
+                            span: Span::default(),
+                        }),
+                    },
+                    node_id: NodeId::default(),
+                    span: Span::default(),
                 },
+                node_id: NodeId::default(),
                 span: Span::default(),
                 mutable: false
             }]
@@ -475,456 +2341,780 @@ mod tests {
     }
 
     #[test]
-    fn test_unary_sign_operator() {
-        let ast = parse("int res = -b * +3;").unwrap();
+    fn test_mut_declare_statement() {
+        let ast = parse("mut int a = 1;").unwrap();
         assert_eq!(
             ignore_spans_ast(ast),
             [Stmt::Declare {
                 dtype: Primitive::Int,
-                name: "res".to_string(),
-                expr: Expr::BinOp {
-                    op: BinOpKind::Mult,
-                    left: Box::new(Expr::UnaryOp {
-                        op: UnaryOpKind::Neg,
-                        expr: Box::new(Expr::Identifier {
-                            name: "b".to_string(),
-                            span: Span::default()
-                        }),
-                        span: Span::default()
-                    }),
-                    right: Box::new(Expr::Literal {
-                        value: "3".to_string(),
+                name: "a".to_string(),
+                mutable: true,
+                expr: Expr {
+                    node: ExprKind::Literal {
+                        value: "1".to_string(),
                         primitive: Primitive::Int,
-                        span: Span::default()
-                    }),
-                    span: Span::default()
+                    },
+                    node_id: NodeId::default(),
+                    span: Span::default(),
                 },
+                node_id: NodeId::default(),
                 span: Span::default(),
-                mutable: false
             }]
         );
     }
 
     #[test]
-    fn test_simple_parentheses() {
-        let ast = parse("int c = (1 + 2) * 3;").unwrap();
+    fn test_mut_assign_statement() {
+        let ast = parse("a = 1;").unwrap();
         assert_eq!(
             ignore_spans_ast(ast),
-            [Stmt::Declare {
-                dtype: Primitive::Int,
-                name: "c".to_string(),
-                expr: Expr::BinOp {
-                    op: BinOpKind::Mult,
-                    left: Box::new(Expr::BinOp {
-                        op: BinOpKind::Add,
-                        left: Box::new(Expr::Literal {
-                            value: "1".to_string(),
-                            primitive: Primitive::Int,
-                            span: Span::default()
-                        }),
-                        right: Box::new(Expr::Literal {
-                            value: "2".to_string(),
-                            primitive: Primitive::Int,
-                            span: Span::default()
-                        }),
-                        span: Span::default()
-                    }),
-                    right: Box::new(Expr::Literal {
-                        value: "3".to_string(),
+            [Stmt::MutAssign {
+                name: "a".to_string(),
+                expr: Expr {
+                    node: ExprKind::Literal {
+                        value: "1".to_string(),
                         primitive: Primitive::Int,
-                        span: Span::default()
-                    }),
-                    span: Span::default()
+                    },
+                    node_id: NodeId::default(),
+                    span: Span::default(),
                 },
-                span: Span::default(),
-                mutable: false
+                node_id: NodeId::default(),
+                span: Span::default()
             }]
         );
     }
 
     #[test]
-    fn test_nested_parentheses() {
-        let ast = parse("float c = ((1 + a) * b) / (a - b);").unwrap();
+    fn test_expression_statement() {
+        let ast = parse("a == 2;\n-1;").unwrap();
         assert_eq!(
             ignore_spans_ast(ast),
-            [Stmt::Declare {
-                dtype: Primitive::Float,
-                name: "c".to_string(),
-                expr: Expr::BinOp {
-                    op: BinOpKind::Div,
-                    left: Box::new(Expr::BinOp {
-                        op: BinOpKind::Mult,
-                        left: Box::new(Expr::BinOp {
-                            op: BinOpKind::Add,
-                            left: Box::new(Expr::Literal {
-                                value: "1".to_string(),
-                                primitive: Primitive::Int,
-                                span: Span::default()
+            [
+                Stmt::Expr {
+                    expr: Expr {
+                        node: ExprKind::BinOp {
+                            op: BinOpKind::Eq,
+                            left: Box::new(Expr {
+                                node: ExprKind::Identifier { name: "a".to_string() },
+                                node_id: NodeId::default(),
+                                span: Span::default(),
                             }),
-                            right: Box::new(Expr::Identifier {
-                                name: "a".to_string(),
-                                span: Span::default()
+                            right: Box::new(Expr {
+                                node: ExprKind::Literal {
+                                    value: "2".to_string(),
+                                    primitive: Primitive::Int,
+                                },
+                                node_id: NodeId::default(),
+                                span: Span::default(),
                             }),
-                            span: Span::default()
-                        }),
-                        right: Box::new(Expr::Identifier {
-                            name: "b".to_string(),
-                            span: Span::default()
+                        },
+                        node_id: NodeId::default(),
+                        span: Span::default(),
+                    },
+                    node_id: NodeId::default(),
+                    span: Span::default()
+                },
+                Stmt::Expr {
+                    expr: Expr {
+                        node: ExprKind::UnaryOp {
+                            op: UnaryOpKind::Neg,
+                            expr: Box::new(Expr {
+                                node: ExprKind::Literal {
+                                    value: "1".to_string(),
+                                    primitive: Primitive::Int,
+                                },
+                                node_id: NodeId::default(),
+                                span: Span::default(),
+                            }),
+                        },
+                        node_id: NodeId::default(),
+                        span: Span::default(),
+                    },
+                    node_id: NodeId::default(),
+                    span: Span::default()
+                }
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_from_slice() {
+        let mut lexer = Lexer::new("int a = (1 + 2) * 3;\nprint(-a);");
+        lexer.tokenize().unwrap();
+
+        // Borrows the lexer's tokens, only the owning parser below needs a copy of them.
+        let mut parser = Parser::from_slice(lexer.get_tokens());
+        parser.parse().unwrap();
+
+        let mut owning_parser = Parser::new(lexer.get_tokens().to_vec());
+        owning_parser.parse().unwrap();
+        assert_eq!(parser.into_tree(), owning_parser.into_tree());
+    }
+
+    #[test]
+    fn test_into_tree_matches_get_tree() {
+        let mut lexer = Lexer::new("int a = 1;\nprint(a * 2);");
+        lexer.tokenize().unwrap();
+
+        let mut parser = Parser::new(lexer.get_tokens().to_vec());
+        parser.parse().unwrap();
+        let borrowed = parser.get_tree().clone();
+
+        // `into_tree` moves the tree out of the parser, so only the reference copy above is a clone.
+        assert_eq!(parser.into_tree(), borrowed);
+    }
+
+    #[test]
+    fn test_multiple_statements_on_one_line() {
+        let ast = parse("int a = 1; int b = 2; print(a);").unwrap();
+        let spans: Vec<Span> = ast
+            .iter()
+            .map(|stmt| match stmt {
+                Stmt::Declare { span, .. } | Stmt::Print { span, .. } => span.clone(),
+                s => panic!("Unexpected statement {:?}.", s),
+        })
+            .collect();
+        assert_eq!(
+            spans,
+            vec![
+                Span { line: 1, col: 1, end_line: 1, end_col: 4, file_id: None },
+                Span { line: 1, col: 12, end_line: 1, end_col: 15, file_id: None },
+                Span { line: 1, col: 23, end_line: 1, end_col: 28, file_id: None },
+            ]
+        );
+        assert!(matches!(
+            &ast[1],
+            Stmt::Declare { expr: Expr { node: ExprKind::Literal { .. }, span, .. }, .. } if span.col == 20
+        ));
+    }
+
+    #[test]
+    fn test_keyword_as_variable_name() {
+        let result = parse("int print = 1;");
+        assert!(matches!(
+            result,
+            Err(CompilerError::SyntaxError { message, span, .. }) if message == "Expected a variable name, found reserved keyword Print." && span.col == 5
+        ));
+
+        let result = parse("mut bool true = false;");
+        assert!(matches!(
+            result,
+            Err(CompilerError::SyntaxError { span, .. }) if span.col == 10
+        ));
+    }
+
+    #[test]
+    fn test_call_expression() {
+        let ast = parse("float a = pow(x, 2) + sqrt(2.0);\nsqrt(4);").unwrap();
+        assert_eq!(
+            ast[0],
+            Stmt::Declare {
+                dtype: Primitive::Float,
+                name: "a".to_string(),
+                expr: Expr {
+                    node: ExprKind::BinOp {
+                        op: BinOpKind::Add,
+                        left: Box::new(Expr {
+                            node: ExprKind::Call {
+                                name: "pow".to_string(),
+                                args: vec![
+                                    Expr {
+                                        node: ExprKind::Identifier { name: "x".to_string() },
+                                        node_id: NodeId(2),
+                                        span: Span { line: 1, col: 15, end_line: 1, end_col: 16, file_id: None },
+                                    },
+                                    Expr {
+                                        node: ExprKind::Literal {
+                                            value: "2".to_string(),
+                                            primitive: Primitive::Int,
+                                        },
+                                        node_id: NodeId(3),
+                                        span: Span { line: 1, col: 18, end_line: 1, end_col: 19, file_id: None },
+                                    },
+                                ],
+                            },
+                            node_id: NodeId(1),
+                            span: Span { line: 1, col: 11, end_line: 1, end_col: 14, file_id: None },
                         }),
-                        span: Span::default()
-                    }),
-                    right: Box::new(Expr::BinOp {
-                        op: BinOpKind::Sub,
-                        left: Box::new(Expr::Identifier {
-                            name: "a".to_string(),
-                            span: Span::default()
+                        right: Box::new(Expr {
+                            node: ExprKind::Call {
+                                name: "sqrt".to_string(),
+                                args: vec![Expr {
+                                    node: ExprKind::Literal {
+                                        value: "2.0".to_string(),
+                                        primitive: Primitive::Float,
+                                    },
+                                    node_id: NodeId(6),
+                                    span: Span { line: 1, col: 28, end_line: 1, end_col: 31, file_id: None },
+                                }],
+                            },
+                            node_id: NodeId(5),
+                            span: Span { line: 1, col: 23, end_line: 1, end_col: 27, file_id: None },
                         }),
-                        right: Box::new(Expr::Identifier {
-                            name: "b".to_string(),
-                            span: Span::default()
+                    },
+                    node_id: NodeId(4),
+                    span: Span { line: 1, col: 11, end_line: 1, end_col: 27, file_id: None },
+                },
+                node_id: NodeId(0),
+                span: Span { line: 1, col: 1, end_line: 1, end_col: 6, file_id: None },
+                mutable: false
+            }
+        );
+        assert!(matches!(
+            &ast[1],
+            Stmt::Expr { expr: Expr { node: ExprKind::Call { name, args }, .. }, .. } if name == "sqrt" && args.len() == 1
+        ));
+
+        let ast = parse("int a = int(c) + 1;\nchar b = char(a);").unwrap();
+        assert!(matches!(
+            &ast[0],
+            Stmt::Declare { expr: Expr { node: ExprKind::BinOp { left, .. }, .. }, .. }
+                if matches!(&left.node, ExprKind::Call { name, args } if name == "int" && args.len() == 1)
+        ));
+        assert!(matches!(
+            &ast[1],
+            Stmt::Declare { expr: Expr { node: ExprKind::Call { name, .. }, .. }, .. } if name == "char"
+        ));
+
+        let result = parse("float a = pow(2 3);");
+        assert!(matches!(
+            result,
+            Err(CompilerError::SyntaxError { message, span, .. }) if message == "Expected ',' or closing ')' after function argument." && span.col == 17
+        ));
+    }
+
+    #[test]
+    fn test_typeof_expression() {
+        let ast = parse("string t = typeof(a);\ntypeof(1);").unwrap();
+        assert_eq!(
+            ast[0],
+            Stmt::Declare {
+                dtype: Primitive::Str,
+                name: "t".to_string(),
+                expr: Expr {
+                    node: ExprKind::TypeOf {
+                        expr: Box::new(Expr {
+                            node: ExprKind::Identifier { name: "a".to_string() },
+                            node_id: NodeId(2),
+                            span: Span { line: 1, col: 19, end_line: 1, end_col: 20, file_id: None },
                         }),
-                        span: Span::default()
-                    }),
-                    span: Span::default()
+                    },
+                    node_id: NodeId(1),
+                    span: Span { line: 1, col: 12, end_line: 1, end_col: 18, file_id: None },
                 },
-                span: Span::default(),
+                node_id: NodeId(0),
+                span: Span { line: 1, col: 1, end_line: 1, end_col: 7, file_id: None },
                 mutable: false
+            }
+        );
+        assert!(matches!(
+            &ast[1],
+            Stmt::Expr { expr: Expr { node: ExprKind::TypeOf { .. }, .. }, .. }
+        ));
+
+        let result = parse("string t = typeof 1;");
+        assert!(matches!(
+            result,
+            Err(CompilerError::SyntaxError { message, span, .. }) if message == "Expected opening '(' after 'typeof' keyword." && span.col == 19
+        ));
+
+        let result = parse("int typeof = 1;");
+        assert!(matches!(
+            result,
+            Err(CompilerError::SyntaxError { message, .. }) if message == "Expected a variable name, found reserved keyword TypeOf."
+        ));
+    }
+
+    #[test]
+    fn test_swap_statement() {
+        let ast = parse("swap(a, b);").unwrap();
+        assert_eq!(
+            ast,
+            [Stmt::Swap {
+                left: "a".to_string(),
+                right: "b".to_string(),
+                node_id: NodeId(0),
+                span: Span { line: 1, col: 1, end_line: 1, end_col: 5, file_id: None }
             }]
         );
+
+        let result = parse("swap(a, 1);");
+        assert!(matches!(
+            result,
+            Err(CompilerError::SyntaxError { span, .. }) if span.col == 9
+        ));
+
+        let result = parse("swap(a b);");
+        assert!(matches!(
+            result,
+            Err(CompilerError::SyntaxError { span, .. }) if span.col == 8
+        ));
     }
 
     #[test]
-    fn test_print_statement() {
-        let ast = parse("print(1 * b);").unwrap();
+    fn test_if_statement() {
+        let identifier = |name: &str| Expr::new(ExprKind::Identifier { name: name.to_string() }, Span::default());
+        let print = |name: &str| Stmt::Print {
+            expr: identifier(name),
+            node_id: NodeId::default(),
+            span: Span::default(),
+        };
+
+        let ast = parse("if (a) {\n  if (b) { print(b); }\n} else if (c) {\n  print(c);\n} else {\n  print(d);\n  print(e);\n}").unwrap();
         assert_eq!(
             ignore_spans_ast(ast),
-            [Stmt::Print {
-                expr: Expr::BinOp {
-                    op: BinOpKind::Mult,
-                    left: Box::new(Expr::Literal {
-                        value: "1".to_string(),
-                        primitive: Primitive::Int,
-                        span: Span::default()
-                    }),
-                    right: Box::new(Expr::Identifier {
-                        name: "b".to_string(),
-                        span: Span::default()
-                    }),
-                    span: Span::default()
-                },
-                span: Span::default()
+            [Stmt::If {
+                cond: identifier("a"),
+                then_body: vec![Stmt::If {
+                    cond: identifier("b"),
+                    then_body: vec![print("b")],
+                    else_body: None,
+                    node_id: NodeId::default(),
+                    span: Span::default(),
+                }],
+                else_body: Some(vec![Stmt::If {
+                    cond: identifier("c"),
+                    then_body: vec![print("c")],
+                    else_body: Some(vec![print("d"), print("e")]),
+                    node_id: NodeId::default(),
+                    span: Span::default(),
+                }]),
+                node_id: NodeId::default(),
+                span: Span::default(),
             }]
         );
+
+        // The chained if is anchored at its own keyword.
+        let ast = parse("if (a) {} else if (b) {}\nprint(a);").unwrap();
+        assert!(matches!(
+            &ast[0],
+            Stmt::If { else_body: Some(else_body), .. } if else_body[0].span() == &Span { line: 1, col: 16, end_line: 1, end_col: 18, file_id: None }
+        ));
+        assert_eq!(ast.len(), 2);
+    }
+
+    #[test]
+    fn test_block_statement() {
+        let ast = parse("{\n  int a = 1;\n  { print(a); }\n}\nprint(2);").unwrap();
+        assert_eq!(ast.len(), 2);
+        assert_eq!(ast[0].span(), &Span { line: 1, col: 1, end_line: 1, end_col: 2, file_id: None });
+        assert_eq!(
+            ignore_spans_stmt(ast[0].clone()),
+            Stmt::Block {
+                body: vec![
+                    Stmt::Declare {
+                        dtype: Primitive::Int,
+                        mutable: false,
+                        name: "a".to_string(),
+                        expr: Expr::new(
+                            ExprKind::Literal {
+                                value: "1".to_string(),
+                                primitive: Primitive::Int,
+                            },
+                            Span::default(),
+                        ),
+                        node_id: NodeId::default(),
+                        span: Span::default(),
+                    },
+                    Stmt::Block {
+                        body: vec![Stmt::Print {
+                            expr: Expr::new(ExprKind::Identifier { name: "a".to_string() }, Span::default()),
+                            node_id: NodeId::default(),
+                            span: Span::default(),
+                        }],
+                        node_id: NodeId::default(),
+                        span: Span::default(),
+                    },
+                ],
+                node_id: NodeId::default(),
+                span: Span::default(),
+            }
+        );
+
+        assert_eq!(parse("{}").unwrap().len(), 1);
+        let result = parse("{ int a = 1;\nprint(a);");
+        assert!(matches!(
+            result,
+            Err(CompilerError::SyntaxError { message, .. }) if message == "Missing closing '}' of block."
+        ));
+    }
+
+    #[test]
+    fn test_empty_statements() {
+        let ast = parse(";;int a=1;;").unwrap();
+        assert!(matches!(ast.as_slice(), [Stmt::Declare { name, .. }] if name == "a"));
+
+        let ast = parse("{ ; print(1);; }").unwrap();
+        assert!(matches!(&ast[0], Stmt::Block { body, .. } if matches!(body.as_slice(), [Stmt::Print { .. }])));
+
+        assert_eq!(parse("   ").unwrap(), vec![]);
+        assert_eq!(parse("").unwrap(), vec![]);
+        assert_eq!(parse(" ;\n; ").unwrap(), vec![]);
+    }
+
+    #[test]
+    fn test_comma_separated_declaration() {
+        let ast = parse("mut int a = 1, b = a + 1;\nprint(b);").unwrap();
+        assert_eq!(ast.len(), 3);
+        assert!(matches!(
+            &ast[0],
+            Stmt::Declare { dtype: Primitive::Int, name, mutable: true, .. } if name == "a"
+        ));
+        let Stmt::Declare {
+            dtype,
+            name,
+            mutable,
+            expr,
+            span,
+            ..
+        } = &ast[1]
+        else {
+            panic!("Expected a declaration, found {:?}.", ast[1]);
+        };
+        assert_eq!((dtype, name.as_str(), mutable), (&Primitive::Int, "b", &true));
+        assert!(matches!(&expr.node, ExprKind::BinOp { op: BinOpKind::Add, .. }));
+        assert_eq!(span, &Span { line: 1, col: 16, end_line: 1, end_col: 17, file_id: None });
+        assert!(matches!(&ast[2], Stmt::Print { .. }));
+        assert!(ast[0].node_id() < ast[1].node_id() && ast[1].node_id() < ast[2].node_id());
+
+        let ast = parse("{ Color c = Red, d = Blue; }").unwrap();
+        assert!(matches!(&ast[0], Stmt::Block { body, .. } if body.len() == 2));
+
+        let result = parse("int a = 1, = 2;");
+        assert!(matches!(
+            result,
+            Err(CompilerError::SyntaxError { message, span, .. }) if message == "Unexpected token BinOp(Assign)." && span.col == 12
+        ));
+
+        let result = parse("for (int i = 0, j = 0; i < 10; i += 1) {}");
+        assert!(matches!(
+            result,
+            Err(CompilerError::SyntaxError { message, span, .. }) if message == "Expected a single declaration as initializer of the 'for' loop." && span.col == 17
+        ));
+    }
+
+    #[test]
+    fn test_for_loop() {
+        let ast = parse("for (mut int i = 0; i < 10; i = i + 1) {\n  print(i);\n}\nprint(0);").unwrap();
+        assert_eq!(ast.len(), 2);
+        let Stmt::For {
+            init, cond, step, body, ..
+        } = &ast[0]
+        else {
+            panic!("Expected a for loop, found {:?}.", ast[0]);
+        };
+        assert!(matches!(init.as_ref(), Stmt::Declare { name, mutable: true, .. } if name == "i"));
+        assert!(matches!(&cond.node, ExprKind::BinOp { op: BinOpKind::Lt, .. }));
+        assert_eq!(cond.span, Span { line: 1, col: 21, end_line: 1, end_col: 27, file_id: None });
+        assert!(matches!(step.as_ref(), Stmt::MutAssign { name, .. } if name == "i"));
+        assert!(matches!(body.as_slice(), [Stmt::Print { .. }]));
+
+        let result = parse("for (int i = 0; i < 10) {}");
+        assert!(matches!(
+            result,
+            Err(CompilerError::SyntaxError { message, span, .. }) if message == "Expected ';' after the condition of the 'for' loop." && span.col == 23
+        ));
+
+        let result = parse("for (print(1); true; i = 1) {}");
+        assert!(matches!(
+            result,
+            Err(CompilerError::SyntaxError { message, span, .. }) if message == "Expected a declaration, assignment or expression as initializer of the 'for' loop." && span.col == 6
+        ));
+    }
+
+    #[test]
+    fn test_function_declaration() {
+        let ast = parse("fn add(int x, float y) -> float {\n  return x + y;\n}\nint z = add(1, 2);").unwrap();
+        let identifier = |name: &str| Expr::new(ExprKind::Identifier { name: name.to_string() }, Span::default());
+        assert_eq!(
+            ignore_spans_stmt(ast[0].clone()),
+            Stmt::FnDecl {
+                name: "add".to_string(),
+                params: vec![("x".to_string(), Primitive::Int), ("y".to_string(), Primitive::Float)],
+                ret: Some(Primitive::Float),
+                body: vec![Stmt::Return {
+                    expr: Some(Expr::new(
+                        ExprKind::BinOp {
+                            op: BinOpKind::Add,
+                            left: Box::new(identifier("x")),
+                            right: Box::new(identifier("y")),
+                        },
+                        Span::default(),
+                    )),
+                    node_id: NodeId::default(),
+                    span: Span::default(),
+                }],
+                node_id: NodeId::default(),
+                span: Span::default(),
+            }
+        );
+        assert_eq!(ast[0].span(), &Span { line: 1, col: 1, end_line: 1, end_col: 3, file_id: None });
+        assert!(matches!(&ast[1], Stmt::Declare { expr: Expr { node: ExprKind::Call { name, args }, .. }, .. } if name == "add" && args.len() == 2));
+
+        let ast = parse("fn zero() -> Color { return Red; }").unwrap();
+        assert!(matches!(&ast[0], Stmt::FnDecl { params, ret: Some(Primitive::Named(ret)), .. } if params.is_empty() && ret == "Color"));
+    }
+
+    #[test]
+    fn test_return_statement() {
+        let ast = parse("fn log(int a) {\n  print(a);\n  return;\n}\nfn next(int a) -> int {\n  return a + 1;\n}").unwrap();
+        let Stmt::FnDecl { ret: None, body, .. } = &ast[0] else {
+            panic!("Expected a function without return type, found {:?}.", ast[0]);
+        };
+        assert!(matches!(
+            &body[1],
+            Stmt::Return { expr: None, span, .. } if *span == Span { line: 3, col: 3, end_line: 3, end_col: 9, file_id: None }
+        ));
+
+        let Stmt::FnDecl { ret: Some(Primitive::Int), body, .. } = &ast[1] else {
+            panic!("Expected a function returning int, found {:?}.", ast[1]);
+        };
+        let Stmt::Return { expr: Some(expr), .. } = &body[0] else {
+            panic!("Expected a return statement, found {:?}.", body[0]);
+        };
+        assert!(matches!(&expr.node, ExprKind::BinOp { op: BinOpKind::Add, .. }));
+        assert_eq!(expr.span, Span { line: 6, col: 10, end_line: 6, end_col: 15, file_id: None });
+
+        // A return outside of a function is only rejected by the semantic analyser.
+        assert!(matches!(parse("return;").unwrap()[..], [Stmt::Return { expr: None, .. }]));
+
+        let result = parse("fn f() -> int {\n  return 1\n}");
+        assert!(matches!(
+            result,
+            Err(CompilerError::SyntaxError { message, span, .. }) if message == "Missing ';' after statement." && span.line == 2 && span.col == 11
+        ));
     }
 
     #[test]
-    fn test_print_statement_with_parentheses() {
-        let ast = parse("print((1 - b) * c);").unwrap();
-        assert_eq!(
-            ignore_spans_ast(ast),
-            [Stmt::Print {
-                expr: Expr::BinOp {
-                    op: BinOpKind::Mult,
-                    left: Box::new(Expr::BinOp {
-                        op: BinOpKind::Sub,
-                        left: Box::new(Expr::Literal {
-                            value: "1".to_string(),
-                            primitive: Primitive::Int,
-                            span: Span::default()
-                        }),
-                        right: Box::new(Expr::Identifier {
-                            name: "b".to_string(),
-                            span: Span::default()
-                        }),
-                        span: Span::default()
-                    }),
-                    right: Box::new(Expr::Identifier {
-                        name: "c".to_string(),
-                        span: Span::default()
-                    }),
-                    span: Span::default()
-                },
-                span: Span::default()
-            }]
-        );
+    fn test_invalid_function_declaration() {
+        let result = parse("fn add(int x int y) -> int { return x; }");
+        assert!(matches!(
+            result,
+            Err(CompilerError::SyntaxError { message, span, .. }) if message == "Expected ',' or closing ')' after function parameter." && span.col == 14
+        ));
+
+        let result = parse("fn add(x) -> int { return x; }");
+        assert!(matches!(
+            result,
+            Err(CompilerError::SyntaxError { message, span, .. }) if message == "Expected a variable name, found token RParen." && span.col == 9
+        ));
+
+        let result = parse("fn one() -> { return 1; }");
+        assert!(matches!(
+            result,
+            Err(CompilerError::SyntaxError { message, span, .. }) if message == "Expected a type, found token LBrace." && span.col == 13
+        ));
+
+        let result = parse("{\n  fn one() -> int { return 1; }\n}");
+        assert!(matches!(
+            result,
+            Err(CompilerError::SyntaxError { message, span, .. }) if message == "Functions can only be declared at the top level." && span.line == 2
+        ));
+
+        let result = parse("int fn = 1;");
+        assert!(matches!(
+            result,
+            Err(CompilerError::SyntaxError { message, .. }) if message == "Expected a variable name, found reserved keyword Fn."
+        ));
     }
 
     #[test]
-    fn test_boolean_statement() {
-        let ast = parse("bool a = true || (b >= 4);").unwrap();
-        assert_eq!(
-            ignore_spans_ast(ast),
-            [Stmt::Declare {
-                dtype: Primitive::Bool,
-                name: "a".to_string(),
-                expr: Expr::BinOp {
-                    op: BinOpKind::Or,
-                    left: Box::new(Expr::Literal {
-                        value: "true".to_string(),
-                        primitive: Primitive::Bool,
-                        span: Span::default()
-                    }),
-                    right: Box::new(Expr::BinOp {
-                        op: BinOpKind::Ge,
-                        left: Box::new(Expr::Identifier {
-                            name: "b".to_string(),
-                            span: Span::default()
-                        }),
-                        right: Box::new(Expr::Literal {
-                            value: "4".to_string(),
-                            primitive: Primitive::Int,
-                            span: Span::default()
-                        }),
-                        span: Span::default()
-                    }),
-                    span: Span::default()
-                },
-                span: Span::default(),
-                mutable: false
-            }]
-        );
+    fn test_invalid_if_statement() {
+        let result = parse("if a {}");
+        assert!(matches!(
+            result,
+            Err(CompilerError::SyntaxError { message, span, .. }) if message == "Expected opening '(' after 'if' keyword." && span.col == 4
+        ));
+
+        let result = parse("if (a) print(a);");
+        assert!(matches!(
+            result,
+            Err(CompilerError::SyntaxError { message, span, .. }) if message == "Expected opening '{' after 'if (...)'." && span.col == 8
+        ));
+
+        let result = parse("if (a) {} else print(a);");
+        assert!(matches!(
+            result,
+            Err(CompilerError::SyntaxError { message, .. }) if message == "Expected opening '{' after 'else'."
+        ));
+
+        let result = parse("if (a) {\n  print(a);");
+        assert!(matches!(
+            result,
+            Err(CompilerError::SyntaxError { message, .. }) if message == "Missing closing '}' of block."
+        ));
+
+        let result = parse("if (a) { print(a) }");
+        assert!(matches!(
+            result,
+            Err(CompilerError::SyntaxError { message, span, .. }) if message == "Missing ';' after statement." && span.col == 18
+        ));
     }
 
     #[test]
-    fn test_logical_not_unary_operation() {
-        let ast = parse("bool a = !(true && !b);").unwrap();
+    fn test_missing_semicolon_fixit() {
+        let err = parse("int a = 0;\nprint(a)").unwrap_err();
         assert_eq!(
-            ignore_spans_ast(ast),
-            [Stmt::Declare {
-                dtype: Primitive::Bool,
-                name: "a".to_string(),
-                expr: Expr::UnaryOp {
-                    op: UnaryOpKind::Not,
-                    expr: Box::new(Expr::BinOp {
-                        op: BinOpKind::And,
-                        left: Box::new(Expr::Literal {
-                            value: "true".to_string(),
-                            primitive: Primitive::Bool,
-                            span: Span::default()
-                        }),
-                        right: Box::new(Expr::UnaryOp {
-                            op: UnaryOpKind::Not,
-                            expr: Box::new(Expr::Identifier {
-                                name: "b".to_string(),
-                                span: Span::default()
-                            }),
-                            span: Span::default()
-                        }),
-                        span: Span::default()
-                    }),
-                    span: Span::default()
-                },
-                span: Span::default(),
-                mutable: false
-            }]
+            err.fixit(),
+            Some(&FixIt {
+                span: Span { line: 2, col: 9, end_line: 2, end_col: 9, file_id: None },
+                len: 0,
+                replacement: ";".to_string(),
+            })
         );
+
+        let err = parse("int a = 0 print(a);").unwrap_err();
+        assert_eq!(err.fixit(), None);
     }
 
     #[test]
-    fn test_boolean_precedence() {
-        let ast = parse("bool a = true || b >= 4 && c == d != e;").unwrap();
+    fn test_compound_assign() {
+        let stmt = parse_single_statement("a *= b + 1;").unwrap();
         assert_eq!(
-            ignore_spans_ast(ast),
-            [Stmt::Declare {
-                dtype: Primitive::Bool,
+            stmt,
+            Stmt::MutAssign {
                 name: "a".to_string(),
-                expr: Expr::BinOp {
-                    op: BinOpKind::Or,
-                    left: Box::new(Expr::Literal {
-                        value: "true".to_string(),
-                        primitive: Primitive::Bool,
-                        span: Span::default()
-                    }),
-                    right: Box::new(Expr::BinOp {
-                        op: BinOpKind::And,
-                        left: Box::new(Expr::BinOp {
-                            op: BinOpKind::Ge,
-                            left: Box::new(Expr::Identifier {
-                                name: "b".to_string(),
-                                span: Span::default()
-                            }),
-                            right: Box::new(Expr::Literal {
-                                value: "4".to_string(),
-                                primitive: Primitive::Int,
-                                span: Span::default()
-                            }),
-                            span: Span::default()
+                expr: Expr {
+                    node: ExprKind::BinOp {
+                        op: BinOpKind::Mult,
+                        left: Box::new(Expr {
+                            node: ExprKind::Identifier { name: "a".to_string() },
+                            node_id: NodeId(1),
+                            span: Span { line: 1, col: 1, end_line: 1, end_col: 2, file_id: None },
                         }),
-                        right: Box::new(Expr::BinOp {
-                            op: BinOpKind::Ne,
-                            left: Box::new(Expr::BinOp {
-                                op: BinOpKind::Eq,
-                                left: Box::new(Expr::Identifier {
-                                    name: "c".to_string(),
-                                    span: Span::default()
+                        right: Box::new(Expr {
+                            node: ExprKind::BinOp {
+                                op: BinOpKind::Add,
+                                left: Box::new(Expr {
+                                    node: ExprKind::Identifier { name: "b".to_string() },
+                                    node_id: NodeId(3),
+                                    span: Span { line: 1, col: 6, end_line: 1, end_col: 7, file_id: None },
                                 }),
-                                right: Box::new(Expr::Identifier {
-                                    name: "d".to_string(),
-                                    span: Span::default()
+                                right: Box::new(Expr {
+                                    node: ExprKind::Literal {
+                                        value: "1".to_string(),
+                                        primitive: Primitive::Int,
+                                    },
+                                    node_id: NodeId(5),
+                                    span: Span { line: 1, col: 10, end_line: 1, end_col: 11, file_id: None },
                                 }),
-                                span: Span::default()
-                            }),
-                            right: Box::new(Expr::Identifier {
-                                name: "e".to_string(),
-                                span: Span::default()
-                            }),
-                            span: Span::default()
+                            },
+                            node_id: NodeId(4),
+                            span: Span { line: 1, col: 6, end_line: 1, end_col: 11, file_id: None },
                         }),
-                        span: Span::default()
-                    }),
-                    span: Span::default()
+                    },
+                    node_id: NodeId(2),
+                    span: Span { line: 1, col: 1, end_line: 1, end_col: 11, file_id: None },
                 },
-                span: Span::default(),
-                mutable: false
-            }]
+                node_id: NodeId(0),
+                span: Span { line: 1, col: 1, end_line: 1, end_col: 2, file_id: None },
+            }
         );
+
+        // The desugared statement is identical to the written out assignment.
+        for (compound, assignment) in [("a += 3;", "a = a + 3;"), ("a -= 3;", "a = a - 3;"), ("a /= 3;", "a = a / 3;")] {
+            assert_eq!(
+                ignore_spans_ast(parse(compound).unwrap()),
+                ignore_spans_ast(parse(assignment).unwrap())
+            );
+        }
     }
 
     #[test]
-    fn test_bool_expr_without_whitespaces() {
-        let ast = parse("bool a=true||b>=4&&c==d!=e;").unwrap();
-        assert_eq!(
-            ignore_spans_ast(ast),
-            [Stmt::Declare {
-                dtype: Primitive::Bool,
-                name: "a".to_string(),
-                expr: Expr::BinOp {
-                    op: BinOpKind::Or,
-                    left: Box::new(Expr::Literal {
-                        value: "true".to_string(),
-                        primitive: Primitive::Bool,
-                        span: Span::default()
-                    }),
-                    right: Box::new(Expr::BinOp {
-                        op: BinOpKind::And,
-                        left: Box::new(Expr::BinOp {
-                            op: BinOpKind::Ge,
-                            left: Box::new(Expr::Identifier {
-                                name: "b".to_string(),
-                                span: Span::default()
-                            }),
-                            right: Box::new(Expr::Literal {
-                                value: "4".to_string(),
-                                primitive: Primitive::Int,
-                                span: Span::default()
-                            }),
-                            span: Span::default()
-                        }),
-                        right: Box::new(Expr::BinOp {
-                            op: BinOpKind::Ne,
-                            left: Box::new(Expr::BinOp {
-                                op: BinOpKind::Eq,
-                                left: Box::new(Expr::Identifier {
-                                    name: "c".to_string(),
-                                    span: Span::default()
-                                }),
-                                right: Box::new(Expr::Identifier {
-                                    name: "d".to_string(),
-                                    span: Span::default()
-                                }),
-                                span: Span::default()
-                            }),
-                            right: Box::new(Expr::Identifier {
-                                name: "e".to_string(),
-                                span: Span::default()
-                            }),
-                            span: Span::default()
-                        }),
-                        span: Span::default()
-                    }),
-                    span: Span::default()
-                },
-                span: Span::default(),
-                mutable: false
-            }]
-        );
+    fn test_trailing_operator_at_eof() {
+        let result = parse("int a = 1 +");
+        assert!(matches!(
+            result,
+            Err(CompilerError::SyntaxError { message, span, .. }) if message == "Unexpected end of input; expected an operand after '+'." && span == Span { line: 1, col: 12, end_line: 1, end_col: 12, file_id: None }
+        ));
+
+        let result = parse("mut int a = 1;\na *=\n");
+        assert!(matches!(
+            result,
+            Err(CompilerError::SyntaxError { message, span, .. }) if message == "Unexpected end of input; expected an operand after '*='." && span == Span { line: 2, col: 5, end_line: 2, end_col: 5, file_id: None }
+        ));
+
+        let result = parse("bool b = a >=");
+        assert!(matches!(
+            result,
+            Err(CompilerError::SyntaxError { message, .. }) if message == "Unexpected end of input; expected an operand after '>='."
+        ));
     }
 
     #[test]
-    fn test_arithm_expr_without_whitespaces() {
-        let ast = parse("float c=((1+a)*b)/(a-b);").unwrap();
-        assert_eq!(
-            ignore_spans_ast(ast),
-            [Stmt::Declare {
-                dtype: Primitive::Float,
-                name: "c".to_string(),
-                expr: Expr::BinOp {
-                    op: BinOpKind::Div,
-                    left: Box::new(Expr::BinOp {
-                        op: BinOpKind::Mult,
-                        left: Box::new(Expr::BinOp {
-                            op: BinOpKind::Add,
-                            left: Box::new(Expr::Literal {
-                                value: "1".to_string(),
-                                primitive: Primitive::Int,
-                                span: Span::default()
-                            }),
-                            right: Box::new(Expr::Identifier {
-                                name: "a".to_string(),
-                                span: Span::default()
-                            }),
-                            span: Span::default()
-                        }),
-                        right: Box::new(Expr::Identifier {
-                            name: "b".to_string(),
-                            span: Span::default()
-                        }),
-                        span: Span::default()
-                    }),
-                    right: Box::new(Expr::BinOp {
-                        op: BinOpKind::Sub,
-                        left: Box::new(Expr::Identifier {
-                            name: "a".to_string(),
-                            span: Span::default()
-                        }),
-                        right: Box::new(Expr::Identifier {
-                            name: "b".to_string(),
-                            span: Span::default()
-                        }),
-                        span: Span::default()
-                    }),
-                    span: Span::default()
-                },
-                span: Span::default(),
-                mutable: false
-            }]
-        );
+    fn test_nesting_depth_limit() {
+        let result = parse(&format!("bool a = {}b;", "!".repeat(100_000)));
+        assert!(matches!(
+            result,
+            Err(CompilerError::SyntaxError { message, span, .. }) if message == "Expression is nested too deeply, the maximum nesting depth is 128." && span.col == 10 + 128
+        ));
+
+        let result = parse(&format!("int a = {}1{};", "(".repeat(10_000), ")".repeat(10_000)));
+        assert!(matches!(result, Err(CompilerError::SyntaxError { .. })));
+
+        parse(&format!("int a = {}x;", "-".repeat(DEFAULT_MAX_NESTING_DEPTH - 1))).unwrap();
+
+        let mut lexer = Lexer::new("int a = --x;\nint b = -x;");
+        lexer.tokenize().unwrap();
+        let mut parser = Parser::new(lexer.get_tokens().to_vec());
+        parser.max_nesting_depth(2);
+        let errors = parser.parse_all().unwrap_err();
+        assert!(matches!(
+            &errors[..],
+            [CompilerError::SyntaxError { message, span, .. }] if message == "Expression is nested too deeply, the maximum nesting depth is 2." && span.col == 11
+        ));
+        assert_eq!(parser.get_tree().len(), 1);
     }
 
     #[test]
-    fn test_mut_declare_statement() {
-        let ast = parse("mut int a = 1;").unwrap();
-        assert_eq!(
-            ignore_spans_ast(ast),
-            [Stmt::Declare {
-                dtype: Primitive::Int,
-                name: "a".to_string(),
-                mutable: true,
-                expr: Expr::Literal {
-                    value: "1".to_string(),
-                    primitive: Primitive::Int,
-                    span: Span::default()
-                },
-                span: Span::default(),
-            }]
-        );
+    fn test_parse_all_collects_errors() {
+        let mut lexer = Lexer::new("int a = ;\nint b = 1;\nprint(b +);\nprint(b);");
+        lexer.tokenize().unwrap();
+        let mut parser = Parser::new(lexer.get_tokens().to_vec());
+
+        let errors = parser.parse_all().unwrap_err();
+        let lines: Vec<usize> = errors
+            .iter()
+            .map(|err| match err {
+                CompilerError::SyntaxError { span, .. } => span.line,
+                err => panic!("unexpected error {:?}", err),
+            })
+            .collect();
+        assert_eq!(lines, vec![1, 3]);
+        assert_eq!(parser.get_tree().len(), 2);
     }
 
     #[test]
-    fn test_mut_assign_statement() {
-        let ast = parse("a = 1;").unwrap();
+    fn test_parse_single_statement() {
+        let stmt = parse_single_statement("mut float a = 1.5;").unwrap();
         assert_eq!(
-            ignore_spans_ast(ast),
-            [Stmt::MutAssign {
+            stmt,
+            Stmt::Declare {
+                dtype: Primitive::Float,
+                mutable: true,
                 name: "a".to_string(),
-                expr: Expr::Literal {
-                    value: "1".to_string(),
-                    primitive: Primitive::Int,
-                    span: Span::default()
+                expr: Expr {
+                    node: ExprKind::Literal {
+                        value: "1.5".to_string(),
+                        primitive: Primitive::Float,
+                    },
+                    node_id: NodeId(1),
+                    span: Span { line: 1, col: 15, end_line: 1, end_col: 18, file_id: None },
                 },
-                span: Span::default()
-            }]
+                node_id: NodeId(0),
+                span: Span { line: 1, col: 1, end_line: 1, end_col: 4, file_id: None },
+            }
         );
+
+        let stmt = parse_single_statement("print(a);").unwrap();
+        assert!(matches!(stmt, Stmt::Print { expr: Expr { node: ExprKind::Identifier { name }, .. }, .. } if name == "a"));
+
+        let result = parse_single_statement("int a = 1; int b = 2;");
+        assert!(matches!(
+            result,
+            Err(CompilerError::SyntaxError { message, span, .. }) if message == "Expected a single statement, found trailing tokens." && span.col == 12
+        ));
+        assert!(parse_single_statement("int a = 1").is_err());
     }
 
     #[test]
@@ -945,6 +3135,24 @@ mod tests {
         ));
     }
 
+    #[test]
+    fn test_missing_eos_semicolon_at_eof() {
+        let result = parse("int a = 0;\nprint(a + 12)\n\n");
+        assert!(matches!(
+            result,
+            Err(CompilerError::SyntaxError { message, span, .. }) if message == "Missing ';' after statement." && span.line == 2 && span.col == 14
+        ));
+    }
+
+    #[test]
+    fn test_unexpected_token_instead_of_eos_semicolon() {
+        let result = parse("int a = 0 print(a);");
+        assert!(matches!(
+            result,
+            Err(CompilerError::SyntaxError { message, .. }) if message == "Unexpected token Print, expected ';'."
+        ));
+    }
+
     #[test]
     fn test_missing_closing_parenthese() {
         let result = parse("int a = ((5 + 4) / 4;");
@@ -983,13 +3191,54 @@ mod tests {
 
     #[test]
     fn test_unknown_statement_start_token() {
-        let result = parse("let a = 2;"); // keyword 'let' doesn't exist, parser will think it's an identifier
+        // keyword 'let' doesn't exist, parser will think it's the name of a type
+        let ast = parse("let a = 2;").unwrap();
+        assert!(matches!(
+            &ast[0],
+            Stmt::Declare { dtype: Primitive::Named(type_name), .. } if type_name == "let"
+        ));
+
+        let result = parse("let a b = 2;");
         assert!(matches!(
             result,
-            Err(CompilerError::SyntaxError { span, .. }) if span.line == 1 && span.col == 5
+            Err(CompilerError::SyntaxError { span, .. }) if span.line == 1 && span.col == 7
         ));
     }
 
+    #[test]
+    fn test_named_type_declaration() {
+        let ast = parse("Color c = Red;\nmut Color d = c;").unwrap();
+        assert_eq!(
+            ast,
+            [
+                Stmt::Declare {
+                    dtype: Primitive::Named("Color".to_string()),
+                    name: "c".to_string(),
+                    expr: Expr {
+                        node: ExprKind::Identifier { name: "Red".to_string() },
+                        node_id: NodeId(1),
+                        span: Span { line: 1, col: 11, end_line: 1, end_col: 14, file_id: None },
+                    },
+                    node_id: NodeId(0),
+                    span: Span { line: 1, col: 1, end_line: 1, end_col: 6, file_id: None },
+                    mutable: false
+                },
+                Stmt::Declare {
+                    dtype: Primitive::Named("Color".to_string()),
+                    name: "d".to_string(),
+                    expr: Expr {
+                        node: ExprKind::Identifier { name: "c".to_string() },
+                        node_id: NodeId(3),
+                        span: Span { line: 2, col: 15, end_line: 2, end_col: 16, file_id: None },
+                    },
+                    node_id: NodeId(2),
+                    span: Span { line: 2, col: 1, end_line: 2, end_col: 4, file_id: None },
+                    mutable: true
+                }
+            ]
+        );
+    }
+
     #[test]
     fn test_missing_identifier_initialisation() {
         let result = parse("int = 2;"); // keyword 'let' doesn't exist
@@ -1007,4 +3256,66 @@ mod tests {
             Err(CompilerError::SyntaxError { span, .. }) if span.line == 2 && span.col == 13
         ));
     }
+
+    #[test]
+    fn test_expression_node_and_span() {
+        let ast = parse("print(1 + a);").unwrap();
+        let Stmt::Print { expr, .. } = &ast[0] else {
+            panic!("Expected a print statement, found {:?}.", ast[0]);
+        };
+        assert_eq!(expr.span, Span { line: 1, col: 7, end_line: 1, end_col: 12, file_id: None });
+        let ExprKind::BinOp { op, left, right } = &expr.node else {
+            panic!("Expected a binary operation, found {:?}.", expr.node);
+        };
+        assert_eq!(op, &BinOpKind::Add);
+        assert_eq!(left.span, Span { line: 1, col: 7, end_line: 1, end_col: 8, file_id: None });
+        assert_eq!(right.node, ExprKind::Identifier { name: "a".to_string() });
+        assert_eq!(right.span, Span { line: 1, col: 11, end_line: 1, end_col: 12, file_id: None });
+    }
+
+    fn collect_node_ids(expr: &Expr, nodes: &mut Vec<(NodeId, Span)>) {
+        // A binary operation gets its id at the operator, right behind its left operand, but its span
+        // already starts with the left operand.
+        let position = match &expr.node {
+            ExprKind::BinOp { left, .. } => left.span.end_point(),
+            _ => expr.span.clone(),
+        };
+        nodes.push((expr.node_id(), position));
+        match &expr.node {
+            ExprKind::Literal { .. } | ExprKind::Identifier { .. } => (),
+            ExprKind::UnaryOp { expr, .. } | ExprKind::Assign { expr, .. } | ExprKind::TypeOf { expr } => {
+                collect_node_ids(expr, nodes)
+            }
+            ExprKind::BinOp { left, right, .. } => {
+                collect_node_ids(left, nodes);
+                collect_node_ids(right, nodes);
+            }
+            ExprKind::Call { args, .. } => args.iter().for_each(|arg| collect_node_ids(arg, nodes)),
+        }
+    }
+
+    #[test]
+    fn test_node_ids_in_source_order() {
+        let ast = parse("int a = -(1 + 2) * b;\nprint(a == 3);\nswap(a, b);\n-a;").unwrap();
+
+        let mut nodes = vec![];
+        for stmt in &ast {
+            nodes.push((stmt.node_id(), stmt.span().clone()));
+            match stmt {
+                Stmt::Declare { expr, .. } | Stmt::Print { expr, .. } | Stmt::Expr { expr, .. } => {
+                    collect_node_ids(expr, &mut nodes)
+                }
+                _ => (),
+            }
+        }
+        nodes.sort_by_key(|(node_id, _)| *node_id);
+
+        // Every node got its own id, handed out without gaps.
+        let ids: Vec<usize> = nodes.iter().map(|(node_id, _)| node_id.0).collect();
+        assert_eq!(ids, (0..15).collect::<Vec<usize>>());
+
+        // Ordered by id, the nodes appear in the order of the source.
+        let positions: Vec<(usize, usize)> = nodes.iter().map(|(_, span)| (span.line, span.col)).collect();
+        assert!(positions.is_sorted());
+    }
 }
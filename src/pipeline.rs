@@ -0,0 +1,197 @@
+use crate::{
+    diagnostic::Diagnostic,
+    errors::CompilerError,
+    lexer::Lexer,
+    parser::Parser,
+    schemas::{Ast, IntWidth, Span, Stmt},
+    semantic::SemanticAnalyser,
+};
+use std::collections::HashSet;
+
+/// Runs the whole pipeline statement by statement and calls `on_stmt` for every statement right
+/// after it has been parsed and checked. Stops at the first error.
+pub fn compile_streaming(source: &str, on_stmt: impl FnMut(&Stmt)) -> Result<Ast, CompilerError> {
+    compile_streaming_with_int_width(source, IntWidth::I64, on_stmt)
+}
+
+/// Like `compile_streaming`, but checks the int literals against the given width.
+pub fn compile_streaming_with_int_width(
+    source: &str,
+    int_width: IntWidth,
+    mut on_stmt: impl FnMut(&Stmt),
+) -> Result<Ast, CompilerError> {
+    let mut lexer = Lexer::new(source);
+    lexer.tokenize()?;
+
+    let mut parser = Parser::from_slice(lexer.get_tokens());
+    let mut analyser = SemanticAnalyser::new(vec![]);
+    analyser.int_width(int_width);
+
+    let mut ast = vec![];
+    while let Some(stmt) = parser.parse_next()? {
+        analyser.check_statement(&stmt)?;
+        on_stmt(&stmt);
+        ast.push(stmt);
+    }
+
+    Ok(ast)
+}
+
+/// Lexes, parses and checks the source while recovering from errors where possible. Returns the
+/// (possibly partial) AST together with all errors and warnings, sorted by their position.
+pub fn analyze(source: &str) -> (Option<Ast>, Vec<Diagnostic>) {
+    analyze_with_int_width(source, IntWidth::I64)
+}
+
+/// Like `analyze`, but checks the int literals against the given width.
+pub fn analyze_with_int_width(source: &str, int_width: IntWidth) -> (Option<Ast>, Vec<Diagnostic>) {
+    let mut lexer = Lexer::new(source);
+    if let Err(err) = lexer.tokenize() {
+        return (None, vec![Diagnostic::from(&err)]);
+    }
+
+    let mut diagnostics = vec![];
+    let mut parser = Parser::from_slice(lexer.get_tokens());
+    if let Err(errors) = parser.parse_all() {
+        diagnostics.extend(errors.iter().map(Diagnostic::from));
+    }
+    diagnostics.extend(parser.get_warnings().iter().map(Diagnostic::from));
+    let ast = parser.into_tree();
+
+    let mut analyser = SemanticAnalyser::new(vec![]);
+    analyser.int_width(int_width);
+    let mut unknown_names = HashSet::new();
+    for stmt in &ast {
+        if let Err(err) = analyser.check_statement(stmt) {
+            // An unknown name is reported at its first use only, the later uses would repeat the error.
+            if let CompilerError::NameError { name, .. } = &err
+                && !unknown_names.insert(name.clone())
+            {
+                continue;
+            }
+            diagnostics.push(Diagnostic::from(&err));
+        }
+    }
+    diagnostics.extend(analyser.get_warnings().iter().map(Diagnostic::from));
+
+    diagnostics.sort_by_key(|diagnostic| (diagnostic.span.line, diagnostic.span.col));
+    dedup_diagnostics(&mut diagnostics);
+    (Some(ast), diagnostics)
+}
+
+/// Removes diagnostics with the same code, span and message as an earlier one, keeping the first.
+fn dedup_diagnostics(diagnostics: &mut Vec<Diagnostic>) {
+    let mut seen: Vec<(String, Span, String)> = vec![];
+    diagnostics.retain(|diagnostic| {
+        let key = (diagnostic.code.clone(), diagnostic.span.clone(), diagnostic.message.clone());
+        let duplicate = seen.contains(&key);
+        if !duplicate {
+            seen.push(key);
+        }
+        !duplicate
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_compile_streaming() {
+        let mut lines = vec![];
+        let ast = compile_streaming("int a = 1;\nfloat b = a / 2;\nprint(b);", |stmt| {
+            lines.push(match stmt {
+                Stmt::Declare { span, .. } | Stmt::Print { span, .. } => span.line,
+                s => panic!("Unexpected statement {:?}.", s),
+            })
+        })
+        .unwrap();
+
+        assert_eq!(lines, vec![1, 2, 3]);
+        assert_eq!(ast.len(), 3);
+    }
+
+    #[test]
+    fn test_compile_streaming_stops_at_first_error() {
+        let mut count = 0;
+        let result = compile_streaming("int a = 1;\nbool b = a;\nprint(a);", |_| count += 1);
+
+        assert!(matches!(result, Err(CompilerError::TypeDeclarationError { .. })));
+        assert_eq!(count, 1);
+    }
+
+    #[test]
+    fn test_analyze_reports_all_diagnostics() {
+        let (ast, diagnostics) = analyze("int a = ;\nint b = 1;\nb == 2;\nbool c = b;\nprint(b +);\nprint(b);");
+
+        let codes: Vec<(&str, usize)> = diagnostics
+            .iter()
+            .map(|diagnostic| (diagnostic.code.as_str(), diagnostic.span.line))
+            .collect();
+        assert_eq!(
+            codes,
+            vec![
+                ("E0001", 1),
+                ("discarded-comparison", 3),
+                ("E0002", 4),
+                ("E0001", 5)
+            ]
+        );
+        assert_eq!(ast.unwrap().len(), 4);
+    }
+
+    #[test]
+    fn test_dedup_diagnostics() {
+        let name_error = |col| CompilerError::NameError {
+            name: "x".to_string(),
+            self_reference: false,
+            suggestion: None,
+            span: Span { line: 1, col, end_line: 1, end_col: col + 1, file_id: None },
+        };
+        let mut diagnostics: Vec<Diagnostic> = [name_error(7), name_error(7), name_error(11), name_error(7)]
+            .iter()
+            .map(Diagnostic::from)
+            .collect();
+        dedup_diagnostics(&mut diagnostics);
+
+        let cols: Vec<usize> = diagnostics.iter().map(|diagnostic| diagnostic.span.col).collect();
+        assert_eq!(cols, vec![7, 11]);
+
+    }
+
+    #[test]
+    fn test_analyze_repeated_name_error() {
+        let (_, diagnostics) = analyze("print(x);\nprint(x + 1);\nprint(y);\nint a = x;");
+        let errors: Vec<(&str, usize)> = diagnostics
+            .iter()
+            .map(|diagnostic| (diagnostic.message.as_str(), diagnostic.span.line))
+            .collect();
+        assert_eq!(
+            errors,
+            vec![
+                ("Cannot find identifier 'x'.", 1),
+                ("Cannot find identifier 'y'.", 3)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_int_width() {
+        let source = "int a = 1;\nprint(a + 3000000000);";
+        assert!(compile_streaming(source, |_| ()).is_ok());
+        let result = compile_streaming_with_int_width(source, IntWidth::I32, |_| ());
+        assert!(matches!(result, Err(CompilerError::LiteralOutOfRange { .. })));
+
+        assert!(analyze(source).1.is_empty());
+        let (_, diagnostics) = analyze_with_int_width(source, IntWidth::I32);
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].code, "E0009");
+    }
+
+    #[test]
+    fn test_analyze_lexer_error() {
+        let (ast, diagnostics) = analyze("int a = 1 $ 2;");
+        assert!(ast.is_none());
+        assert_eq!(diagnostics.len(), 1);
+    }
+}
@@ -0,0 +1,311 @@
+//! Renders an AST back into source code, e.g. to format a program or to look at the tree produced by
+//! a transformation. The output is canonical: four spaces of indentation per block, one statement per
+//! line and only the parentheses needed to keep the structure of the expressions.
+
+use crate::{
+    parser::{Parser, PREFIX_BINDING_POWER},
+    schemas::{Ast, BinOpKind, Expr, ExprKind, Primitive, Span, Stmt},
+};
+
+/// Binding power of an assignment expression, the weakest of all operators.
+const ASSIGN_BINDING_POWER: (f32, f32) = (0.2, 0.1);
+
+/// Renders the AST as source code which parses back into the same AST, apart from spans and node ids.
+pub fn pretty_print(ast: &Ast) -> String {
+    let mut text = String::new();
+    print_body(ast, 0, &mut text);
+    text
+}
+
+fn push_line(text: &mut String, depth: usize, line: &str) {
+    text.push_str(&"    ".repeat(depth));
+    text.push_str(line);
+    text.push('\n');
+}
+
+fn print_body(body: &[Stmt], depth: usize, text: &mut String) {
+    for stmt in body {
+        print_stmt(stmt, depth, text);
+    }
+}
+
+/// Prints the header of a statement with a body, e.g. `if (a) {`, followed by the body and its closing brace.
+fn print_block(header: &str, body: &Ast, depth: usize, text: &mut String) {
+    push_line(text, depth, &format!("{}{{", header));
+    print_body(body, depth + 1, text);
+    push_line(text, depth, "}");
+}
+
+fn print_stmt(stmt: &Stmt, depth: usize, text: &mut String) {
+    match stmt {
+        Stmt::Declare {
+            dtype,
+            mutable,
+            name,
+            expr,
+            ..
+        } => {
+            let mutability = if *mutable { "mut " } else { "" };
+            push_line(text, depth, &format!("{}{} {} = {};", mutability, dtype.name(), name, print_expr(expr)));
+        }
+        Stmt::MutAssign { name, expr, .. } => push_line(text, depth, &format!("{} = {};", name, print_expr(expr))),
+        Stmt::Expr { expr, .. } => push_line(text, depth, &format!("{};", print_expr(expr))),
+        Stmt::Print { expr, .. } => push_line(text, depth, &format!("print({});", print_expr(expr))),
+        Stmt::EPrint { expr, .. } => push_line(text, depth, &format!("eprint({});", print_expr(expr))),
+        Stmt::Swap { left, right, .. } => push_line(text, depth, &format!("swap({}, {});", left, right)),
+        Stmt::Block { body, .. } => print_block("", body, depth, text),
+        Stmt::If {
+            cond,
+            then_body,
+            else_body,
+            ..
+        } => print_if(cond, then_body, else_body, "", depth, text),
+        Stmt::For {
+            init, cond, step, body, ..
+        } => {
+            let header = format!("for ({}; {}; {}) ", print_clause(init), print_expr(cond), print_clause(step));
+            print_block(&header, body, depth, text);
+        }
+        Stmt::FnDecl {
+            name, params, ret, body, ..
+        } => {
+            let params: Vec<String> = params
+                .iter()
+                .map(|(name, primitive)| format!("{} {}", primitive.name(), name))
+                .collect();
+            let ret = ret.as_ref().map_or(String::new(), |ret| format!(" -> {}", ret.name()));
+            print_block(&format!("fn {}({}){} ", name, params.join(", "), ret), body, depth, text);
+        }
+        Stmt::Return { expr: Some(expr), .. } => push_line(text, depth, &format!("return {};", print_expr(expr))),
+        Stmt::Return { expr: None, .. } => push_line(text, depth, "return;"),
+    }
+}
+
+/// Prints an if statement, `prefix` is `} else ` for an else branch holding a single if statement,
+/// which is printed as `else if` on the line closing the previous branch.
+fn print_if(cond: &Expr, then_body: &Ast, else_body: &Option<Ast>, prefix: &str, depth: usize, text: &mut String) {
+    push_line(text, depth, &format!("{}if ({}) {{", prefix, print_expr(cond)));
+    print_body(then_body, depth + 1, text);
+    match else_body.as_deref() {
+        Some([Stmt::If {
+            cond,
+            then_body,
+            else_body,
+            ..
+        }]) => print_if(cond, then_body, else_body, "} else ", depth, text),
+        Some(else_body) => {
+            push_line(text, depth, "} else {");
+            print_body(else_body, depth + 1, text);
+            push_line(text, depth, "}");
+        }
+        None => push_line(text, depth, "}"),
+    }
+}
+
+/// Prints the initializer or step of a for loop, a single line statement without its ';'.
+fn print_clause(stmt: &Stmt) -> String {
+    let mut text = String::new();
+    print_stmt(stmt, 0, &mut text);
+    text.trim_end().trim_end_matches(';').to_string()
+}
+
+fn print_expr(expr: &Expr) -> String {
+    print_operand(expr, 0.0, f32::NEG_INFINITY)
+}
+
+/// Prints an expression parsed with the minimum binding power `min_binding_pow`, followed by an
+/// operator with the left binding power `next_binding_pow`. It is parenthesized if it would bind
+/// weaker than its surrounding, i.e. if it would not be parsed back as a whole.
+fn print_operand(expr: &Expr, min_binding_pow: f32, next_binding_pow: f32) -> String {
+    let (left_binding_pow, right_binding_pow) = match &expr.node {
+        ExprKind::BinOp { op, .. } => binop_binding_power(op),
+        ExprKind::Assign { .. } => ASSIGN_BINDING_POWER,
+        // A prefix operator always starts an operand, but its operand ends at any operator binding weaker.
+        ExprKind::UnaryOp { .. } => (f32::INFINITY, PREFIX_BINDING_POWER),
+        _ => (f32::INFINITY, f32::INFINITY),
+    };
+    if left_binding_pow < min_binding_pow || right_binding_pow <= next_binding_pow {
+        return format!("({})", print_expr(expr));
+    }
+
+    match &expr.node {
+        ExprKind::Literal { value, primitive } => print_literal(value, primitive),
+        ExprKind::Identifier { name } => name.clone(),
+        ExprKind::BinOp { op, left, right } => format!(
+            "{} {} {}",
+            print_operand(left, min_binding_pow, left_binding_pow),
+            op.symbol(),
+            print_operand(right, right_binding_pow, next_binding_pow)
+        ),
+        ExprKind::UnaryOp { op, expr } => {
+            format!("{}{}", op.symbol(), print_operand(expr, PREFIX_BINDING_POWER, next_binding_pow))
+        }
+        ExprKind::Assign { name, expr } => {
+            format!("{} = {}", name, print_operand(expr, right_binding_pow, next_binding_pow))
+        }
+        ExprKind::TypeOf { expr } => format!("typeof({})", print_expr(expr)),
+        ExprKind::Call { name, args } => {
+            let args: Vec<String> = args.iter().map(print_expr).collect();
+            format!("{}({})", name, args.join(", "))
+        }
+    }
+}
+
+/// The binding powers the parser uses for the operator. Operators it never builds a binary operation
+/// of get no binding power at all, so that they are always parenthesized.
+fn binop_binding_power(op: &BinOpKind) -> (f32, f32) {
+    Parser::airthmetic_binding_power(op, &Span::default()).unwrap_or((0.0, 0.0))
+}
+
+fn print_literal(value: &str, primitive: &Primitive) -> String {
+    match primitive {
+        Primitive::Str => format!("\"{}\"", escape(value, '"')),
+        Primitive::Char => format!("'{}'", escape(value, '\'')),
+        _ => value.to_string(),
+    }
+}
+
+/// Escapes the characters which cannot appear literally between the given quotes.
+fn escape(value: &str, quote: char) -> String {
+    let mut escaped = String::new();
+    for c in value.chars() {
+        match c {
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            '\t' => escaped.push_str("\\t"),
+            '\0' => escaped.push_str("\\0"),
+            c if c == quote => {
+                escaped.push('\\');
+                escaped.push(c);
+            }
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{lexer::Lexer, parser::tests::ignore_spans_ast};
+
+    /// The programs of the parser tests which parse successfully.
+    const PARSER_TEST_PROGRAMS: &[&str] = &[
+        "int a = 1 + 2;",
+        "float a = 1 * 2 + 3.5;",
+        "int r = 1 + 10 % 3;",
+        "int r = 10 % 3 * 2;",
+        "int a = 2 ^ 3 ^ 2;",
+        "int a = 2 * 3 ^ 2;",
+        "float a = 0.3333 - 2 / 3;",
+        "int res = -b * +3;",
+        "int a = x;",
+        "int a = +x;",
+        "int a = + + x;",
+        "bool b = a && !c;",
+        "~a;",
+        "int c = (1 + 2) * 3;",
+        "float c = ((1 + a) * b) / (a - b);",
+        "print(1 * b);",
+        "eprint(b);",
+        "print((1 - b) * c);",
+        "bool a = true || (b >= 4);",
+        "bool a = !(true && !b);",
+        "bool a = true || b >= 4 && c == d != e;",
+        "bool a=true||b>=4&&c==d!=e;",
+        "float c=((1+a)*b)/(a-b);",
+        "mut int a = 1;",
+        "a = 1;",
+        "a == 2;\n-1;",
+        "int a = 1; int b = 2; print(a);",
+        "float a = pow(x, 2) + sqrt(2.0);\nsqrt(4);",
+        "string t = typeof(a);\ntypeof(1);",
+        "swap(a, b);",
+        "if (a) {\n  if (b) { print(b); }\n} else if (c) {\n  print(c);\n} else {\n  print(d);\n  print(e);\n}",
+        "if (a) {} else if (b) {}\nprint(a);",
+        "{\n  int a = 1;\n  { print(a); }\n}\nprint(2);",
+        "{}",
+        ";;int a=1;;",
+        "{ ; print(1);; }",
+        "   ",
+        "",
+        " ;\n; ",
+        "mut int a = 1, b = a + 1;\nprint(b);",
+        "{ Color c = Red, d = Blue; }",
+        "for (mut int i = 0; i < 10; i = i + 1) {\n  print(i);\n}\nprint(0);",
+        "fn add(int x, float y) -> float {\n  return x + y;\n}\nint z = add(1, 2);",
+        "fn zero() -> Color { return Red; }",
+        "fn log(int a) {\n  print(a);\n  return;\n}\nfn next(int a) -> int {\n  return a + 1;\n}",
+        "return;",
+        "let a = 2;",
+        "Color c = Red;\nmut Color d = c;",
+        "print(1 + a);",
+        "int a = -(1 + 2) * b;\nprint(a == 3);\nswap(a, b);\n-a;",
+    ];
+
+    fn parse(input: &str) -> Ast {
+        let mut lexer = Lexer::new(input);
+        lexer.tokenize().unwrap();
+
+        let mut parser = Parser::new(lexer.get_tokens().to_vec());
+        parser.parse().unwrap();
+        parser.into_tree()
+    }
+
+    fn assert_round_trip(program: &str) {
+        let ast = parse(program);
+        let printed = pretty_print(&ast);
+        assert_eq!(ignore_spans_ast(parse(&printed)), ignore_spans_ast(ast), "printed as:\n{}", printed);
+    }
+
+    #[test]
+    fn test_round_trip_parser_test_programs() {
+        for program in PARSER_TEST_PROGRAMS {
+            assert_round_trip(program);
+        }
+    }
+
+    #[test]
+    fn test_round_trip_literals_and_operators() {
+        for program in [
+            "string s = \"say \\\"hi\\\"\\n\\tand \\\\ bye\";\nchar c = '\\'';\nchar d = '\\0';",
+            "int a = 0xFF & ~(1 << 3) | 4 >> 1;\nbool b = (a & 1) == 0;",
+            "int a = -(-x);\nint b = - -x;\nint c = (-2) ^ 2;\nint d = -2 ^ 2;\nbool e = !!f;",
+            "int a = (2 ^ 3) ^ 2;\nint b = 2 - (3 - 4);\nint c = (2 - 3) - 4;\nfloat d = 1 / (2 * 3);\nint e = 1 % (2 % 3);",
+            "mut float x = 1.5e3;\nx += 2;\nx *= x - 1;",
+            "if (a) { print(1); } else { if (b) { print(2); } }\nif (a) { } else { if (b) { } print(3); }",
+            "for (int i = 0; i < 3; i = i + 1) { for (mut int j = i; j > 0; j -= 1) { print(typeof(j)); } }",
+        ] {
+            assert_round_trip(program);
+        }
+    }
+
+    #[test]
+    fn test_pretty_print() {
+        let ast = parse(
+            "mut int a=(1+2)*3;if(a>=2&&!(a==3)){a-=1;}else if(a<0){swap(a,b);}else{eprint(\"no\");}\n\
+             fn sq(float x)->float{return x^2;}for(mut int i=0;i<sq(a);i+=1){{print(-(i^2));}}",
+        );
+        assert_eq!(
+            pretty_print(&ast),
+            "\
+mut int a = (1 + 2) * 3;
+if (a >= 2 && !(a == 3)) {
+    a = a - 1;
+} else if (a < 0) {
+    swap(a, b);
+} else {
+    eprint(\"no\");
+}
+fn sq(float x) -> float {
+    return x ^ 2;
+}
+for (mut int i = 0; i < sq(a); i = i + 1) {
+    {
+        print(-i ^ 2);
+    }
+}
+"
+        );
+    }
+}
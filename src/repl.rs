@@ -0,0 +1,128 @@
+//! Interactive session that evaluates the source line by line. The declarations of earlier lines stay
+//! visible to later ones, and an error only discards the statement it occurred in and the rest of its
+//! line.
+
+use crate::{
+    errors::CompilerError, interpreter::Interpreter, lexer::Lexer, parser::Parser, schemas::IntWidth, semantic::SemanticAnalyser,
+};
+use std::io::{BufRead, Write};
+
+pub struct Repl<'a> {
+    analyser: SemanticAnalyser,
+    interpreter: Interpreter<'a>,
+}
+
+impl<'a> Repl<'a> {
+    pub fn new(stdout: &'a mut dyn Write, stderr: &'a mut dyn Write) -> Self {
+        Repl {
+            analyser: SemanticAnalyser::new(vec![]),
+            interpreter: Interpreter::new(stdout, stderr),
+        }
+    }
+
+    /// Limits how deeply function calls may be nested, see `Interpreter::max_call_depth`.
+    pub fn max_call_depth(&mut self, limit: usize) {
+        self.interpreter.max_call_depth(limit);
+    }
+
+    /// Sets the width of the `int` primitive for both the checks and the execution.
+    pub fn int_width(&mut self, width: IntWidth) {
+        self.analyser.int_width(width);
+        self.interpreter.int_width(width);
+    }
+
+    /// Checks and runs the statements of one line, each right after it is parsed. Statements before
+    /// the failing one keep their effects, the declarations of the failing one are rolled back so that
+    /// the analyser and the interpreter agree on which variables exist.
+    pub fn eval(&mut self, line: &str) -> Result<(), CompilerError> {
+        let mut lexer = Lexer::new(line);
+        lexer.tokenize()?;
+
+        let mut parser = Parser::from_slice(lexer.get_tokens());
+        while let Some(stmt) = parser.parse_next()? {
+            let snapshot = self.analyser.snapshot();
+            if let Err(err) = self
+                .analyser
+                .check_statement(&stmt)
+                .and_then(|_| self.interpreter.execute(&stmt))
+            {
+                self.analyser.restore(snapshot);
+                return Err(err);
+            }
+        }
+        Ok(())
+    }
+
+    /// Evaluates the lines of `input` until it ends. `prompt` is written to `console` before every
+    /// line, errors are rendered to it after the failing line.
+    pub fn run(&mut self, mut input: impl BufRead, console: &mut dyn Write, prompt: &str, color: bool) -> std::io::Result<()> {
+        loop {
+            write!(console, "{}", prompt)?;
+            console.flush()?;
+
+            let mut line = String::new();
+            if input.read_line(&mut line)? == 0 {
+                return Ok(());
+            }
+            if let Err(err) = self.eval(&line) {
+                writeln!(console, "{}", err.render_colored(&line, color))?;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_repl() {
+        let script = "mut int a = 2;\nprint(a * 21);\nprint(b);\na += 1; print(a);\n\
+                      fn square(int x) -> int { return x * x; }\nprint(square(a)); int a = 1;\nprint(a);\n";
+        let (mut stdout, mut stderr, mut console) = (vec![], vec![], vec![]);
+        Repl::new(&mut stdout, &mut stderr)
+            .run(script.as_bytes(), &mut console, "", false)
+            .unwrap();
+
+        assert_eq!(String::from_utf8(stdout).unwrap(), "42\n3\n9\n3\n");
+        assert!(stderr.is_empty());
+        let console = String::from_utf8(console).unwrap();
+        assert!(console.contains("Cannot find identifier 'b'"), "{}", console);
+        assert!(console.contains("'a' is already declared in this scope"), "{}", console);
+    }
+
+    #[test]
+    fn test_repl_prompt() {
+        let (mut stdout, mut stderr, mut console) = (vec![], vec![], vec![]);
+        Repl::new(&mut stdout, &mut stderr)
+            .run("print(1);\n\n".as_bytes(), &mut console, "> ", false)
+            .unwrap();
+
+        assert_eq!(String::from_utf8(stdout).unwrap(), "1\n");
+        assert_eq!(String::from_utf8(console).unwrap(), "> > > ");
+    }
+
+    #[test]
+    fn test_repl_runtime_error() {
+        let mut stdout = vec![];
+        let mut stderr = vec![];
+        let mut repl = Repl::new(&mut stdout, &mut stderr);
+        repl.eval("int a = 0;").unwrap();
+        assert!(repl.eval("print(1); print(1 / a); print(2);").is_err());
+        repl.eval("print(a);").unwrap();
+
+        // The failed declaration is rolled back, so `x` is unknown rather than declared but missing.
+        assert!(repl.eval("int x = 1 / a;").is_err());
+        assert!(matches!(repl.eval("print(x);"), Err(CompilerError::NameError { name, .. }) if name == "x"));
+        repl.eval("int x = 2;").unwrap();
+        repl.eval("print(x);").unwrap();
+
+        // A function whose body fails the checks isn't declared either.
+        assert!(repl.eval("fn f() -> int { return y; }").is_err());
+        repl.eval("fn f() -> int { return x; }").unwrap();
+        repl.eval("print(f());").unwrap();
+        drop(repl);
+
+        assert_eq!(String::from_utf8(stdout).unwrap(), "1\n0\n2\n2\n");
+    }
+}
@@ -1,52 +1,210 @@
-#[derive(Debug, PartialEq, Clone)]
+use crate::errors::CompilerError;
 
+#[derive(Debug, PartialEq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Primitive {
     Int,
     Float,
     Bool,
+    Str,
+    Char,
+    /// A type referenced by an identifier, e.g. `Color c = ...;`, resolved by the semantic analyser.
+    Named(String),
+}
+
+impl Primitive {
+    /// The types built into the language, i.e. all but the named ones.
+    pub const BUILTINS: &'static [Primitive] = &[
+        Primitive::Int,
+        Primitive::Float,
+        Primitive::Bool,
+        Primitive::Str,
+        Primitive::Char,
+    ];
+
+    /// The type as it is written in a declaration.
+    pub fn name(&self) -> &str {
+        match self {
+            Primitive::Int => "int",
+            Primitive::Float => "float",
+            Primitive::Bool => "bool",
+            Primitive::Str => "string",
+            Primitive::Char => "char",
+            Primitive::Named(name) => name,
+        }
+    }
+
+    /// Int or float, the operand types of arithmetic and ordering comparisons.
+    pub fn is_numeric(&self) -> bool {
+        self.is_integer() || self.is_float()
+    }
+
+    pub fn is_integer(&self) -> bool {
+        *self == Primitive::Int
+    }
+
+    pub fn is_float(&self) -> bool {
+        *self == Primitive::Float
+    }
+
+    pub fn is_boolean(&self) -> bool {
+        *self == Primitive::Bool
+    }
+}
+
+/// Bit width of the `int` primitive, bounding int literals, the results of int arithmetic in the
+/// interpreter and selecting the integer type in codegen.
+#[derive(Clone, Copy, Debug, PartialEq, Default)]
+pub enum IntWidth {
+    I32,
+    #[default]
+    I64,
+}
+
+impl IntWidth {
+    pub fn min(&self) -> i128 {
+        match self {
+            IntWidth::I32 => i32::MIN as i128,
+            IntWidth::I64 => i64::MIN as i128,
+        }
+    }
+
+    pub fn max(&self) -> i128 {
+        match self {
+            IntWidth::I32 => i32::MAX as i128,
+            IntWidth::I64 => i64::MAX as i128,
+        }
+    }
+
+    pub fn bits(&self) -> u32 {
+        match self {
+            IntWidth::I32 => i32::BITS,
+            IntWidth::I64 => i64::BITS,
+        }
+    }
+
+    pub fn contains(&self, value: i64) -> bool {
+        (self.min()..=self.max()).contains(&(value as i128))
+    }
+
+    /// Drops the bits of `value` beyond the width, like a cast to the narrower integer type.
+    pub fn wrap(&self, value: i64) -> i64 {
+        match self {
+            IntWidth::I32 => value as i32 as i64,
+            IntWidth::I64 => value,
+        }
+    }
+}
+
+/// Converts a value between int and float, the only implicit conversions of the language. The
+/// interpreter and the backends apply it when a value is stored into a variable of another type.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Conversion {
+    Identity,
+    IntToFloat,
+    FloatToInt,
+}
+
+impl Conversion {
+    pub fn between(from: &Primitive, to: &Primitive, span: &Span) -> Result<Conversion, CompilerError> {
+        match (from, to) {
+            (Primitive::Int, Primitive::Float) => Ok(Conversion::IntToFloat),
+            (Primitive::Float, Primitive::Int) => Ok(Conversion::FloatToInt),
+            (from, to) if from == to => Ok(Conversion::Identity),
+            (from, to) => Err(CompilerError::TypeDeclarationError {
+                expected: to.clone(),
+                found: from.clone(),
+                span: span.clone(),
+            }),
+        }
+    }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct Identifier {
     pub primitive: Primitive,
     pub span: Span,
     pub mutable: bool,
 }
 
-#[derive(PartialEq, Clone, Debug)]
+/// Unique id of an AST node, assigned by the parser in source order.
+#[derive(PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Copy, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct NodeId(pub usize);
+
+#[derive(PartialEq, Clone, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Span {
     pub line: usize,
     pub col: usize,
+    /// Position right behind the last character of the spanned source, e.g. `1:7` for the `>=` at `1:5`.
+    /// Spans that only mark a position, like most error locations, end where they start.
+    pub end_line: usize,
+    pub end_col: usize,
+    /// Index of the source file in a `SourceMap`, `None` when compiling a single source.
+    pub file_id: Option<u32>,
 }
 
-impl Default for Span {
-    fn default() -> Self {
-        Span { line: 0, col: 0 }
+impl Span {
+    /// The span from the start of this span to the end of `other`, e.g. of a binary operation from
+    /// its left to its right operand.
+    pub fn to(&self, other: &Span) -> Span {
+        Span {
+            end_line: other.end_line,
+            end_col: other.end_col,
+            ..self.clone()
+        }
+    }
+
+    /// The empty span right behind the end of this span.
+    pub fn end_point(&self) -> Span {
+        Span {
+            line: self.end_line,
+            col: self.end_col,
+            ..self.clone()
+        }
     }
 }
 
 #[derive(Debug, PartialEq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Literal {
     pub value: String,
     pub primitive: Primitive,
 }
 
 // lexer schemas
+#[allow(clippy::upper_case_acronyms)]
 #[derive(Debug, PartialEq, Clone)]
 pub enum TokenKind {
     Declare(Primitive),
     Identifier(String),
     Literal(Literal),
     BinOp(BinOpKind),
+    /// An assignment operator combined with an arithmetic one, e.g. `+=`.
+    CompoundAssign(BinOpKind),
     Mut,
     LParen,
     RParen,
+    LBrace,
+    RBrace,
+    Comma,
     Print,
+    EPrint,
+    Swap,
+    TypeOf,
+    If,
+    Else,
+    For,
+    Fn,
+    Return,
+    /// The `->` in front of the return type of a function.
+    Arrow,
     EOS,
     EOF,
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq)]
 pub struct Token {
     pub kind: TokenKind,
     pub span: Span,
@@ -54,12 +212,15 @@ pub struct Token {
 
 // ast schemas
 #[derive(PartialEq, Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum BinOpKind {
     Assign,
     Add,
     Sub,
     Mult,
     Div,
+    Mod,
+    Pow,
     Gt,
     Lt,
     Ge,
@@ -69,56 +230,478 @@ pub enum BinOpKind {
     And,
     Or,
     Not,
+    BitAnd,
+    BitOr,
+    Shl,
+    Shr,
+    BitNot,
+}
+
+impl BinOpKind {
+    /// The operator as it is written in the source code.
+    pub fn symbol(&self) -> &'static str {
+        match self {
+            BinOpKind::Assign => "=",
+            BinOpKind::Add => "+",
+            BinOpKind::Sub => "-",
+            BinOpKind::Mult => "*",
+            BinOpKind::Div => "/",
+            BinOpKind::Mod => "%",
+            BinOpKind::Pow => "^",
+            BinOpKind::Gt => ">",
+            BinOpKind::Lt => "<",
+            BinOpKind::Ge => ">=",
+            BinOpKind::Le => "<=",
+            BinOpKind::Eq => "==",
+            BinOpKind::Ne => "!=",
+            BinOpKind::And => "&&",
+            BinOpKind::Or => "||",
+            BinOpKind::Not => "!",
+            BinOpKind::BitAnd => "&",
+            BinOpKind::BitOr => "|",
+            BinOpKind::Shl => "<<",
+            BinOpKind::Shr => ">>",
+            BinOpKind::BitNot => "~",
+        }
+    }
 }
 
 #[derive(PartialEq, Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum UnaryOpKind {
     Neg,
     Not,
+    BitNot,
+}
+
+impl UnaryOpKind {
+    /// The operator as it is written in the source code.
+    pub fn symbol(&self) -> &'static str {
+        match self {
+            UnaryOpKind::Neg => "-",
+            UnaryOpKind::Not => "!",
+            UnaryOpKind::BitNot => "~",
+        }
+    }
+}
+
+impl TryFrom<&Token> for BinOpKind {
+    type Error = CompilerError;
+
+    fn try_from(token: &Token) -> Result<Self, Self::Error> {
+        match &token.kind {
+            TokenKind::BinOp(BinOpKind::Not | BinOpKind::BitNot | BinOpKind::Assign) => Err(CompilerError::SyntaxError {
+                message: format!("Expected a binary operator, found {:?}.", token.kind),
+                span: token.span.clone(),
+                fixit: None,
+            }),
+            TokenKind::BinOp(op) => Ok(op.clone()),
+            t => Err(CompilerError::SyntaxError {
+                message: format!("Expected a binary operator, found token {:?}.", t),
+                span: token.span.clone(),
+                fixit: None,
+            }),
+        }
+    }
 }
 
+impl TryFrom<&Token> for UnaryOpKind {
+    type Error = CompilerError;
+
+    fn try_from(token: &Token) -> Result<Self, Self::Error> {
+        match &token.kind {
+            TokenKind::BinOp(BinOpKind::Sub) => Ok(UnaryOpKind::Neg),
+            TokenKind::BinOp(BinOpKind::Not) => Ok(UnaryOpKind::Not),
+            TokenKind::BinOp(BinOpKind::BitNot) => Ok(UnaryOpKind::BitNot),
+            t => Err(CompilerError::SyntaxError {
+                message: format!("Expected a unary operator, found token {:?}.", t),
+                span: token.span.clone(),
+                fixit: None,
+            }),
+        }
+    }
+}
+
+/// An AST node together with its location in the source and the id the parser assigned to it.
 #[derive(PartialEq, Clone, Debug)]
-pub enum Expr {
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Spanned<T> {
+    pub node: T,
+    pub node_id: NodeId,
+    pub span: Span,
+}
+
+impl<T> Spanned<T> {
+    /// Wraps a node which has not been assigned an id, e.g. one synthesized after parsing.
+    pub fn new(node: T, span: Span) -> Self {
+        Spanned {
+            node,
+            node_id: NodeId::default(),
+            span,
+        }
+    }
+
+    pub fn span(&self) -> &Span {
+        &self.span
+    }
+
+    pub fn node_id(&self) -> NodeId {
+        self.node_id
+    }
+}
+
+pub type Expr = Spanned<ExprKind>;
+
+#[derive(PartialEq, Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ExprKind {
     Literal {
         value: String,
         primitive: Primitive,
-        span: Span,
     },
     Identifier {
         name: String,
-        span: Span,
     },
     BinOp {
         op: BinOpKind,
         left: Box<Expr>,
         right: Box<Expr>,
-        span: Span,
     },
     UnaryOp {
         op: UnaryOpKind,
         expr: Box<Expr>,
-        span: Span,
     },
+    /// Assignment used as an expression, e.g. the `b = 5` in `a = b = 5;`. Only produced when enabled in the parser.
+    Assign {
+        name: String,
+        expr: Box<Expr>,
+    },
+    /// The `typeof(expr)` builtin, evaluates to the name of the type of its argument.
+    TypeOf {
+        expr: Box<Expr>,
+    },
+    /// Call of a builtin or declared function like `pow(2, 10)`.
+    Call {
+        name: String,
+        args: Vec<Expr>,
+    },
+}
+
+impl Expr {
+    /// Maximum nesting depth of the expression, a lone literal or identifier has depth 1.
+    /// Computed without recursion so that it is safe to call on arbitrarily deep trees.
+    pub fn depth(&self) -> usize {
+        let mut max_depth = 0;
+        let mut stack = vec![(self, 1)];
+        while let Some((expr, depth)) = stack.pop() {
+            max_depth = max_depth.max(depth);
+            match &expr.node {
+                ExprKind::Literal { .. } | ExprKind::Identifier { .. } => (),
+                ExprKind::BinOp { left, right, .. } => {
+                    stack.push((left, depth + 1));
+                    stack.push((right, depth + 1));
+                }
+                ExprKind::UnaryOp { expr, .. } | ExprKind::Assign { expr, .. } | ExprKind::TypeOf { expr } => {
+                    stack.push((expr, depth + 1))
+                }
+                ExprKind::Call { args, .. } => stack.extend(args.iter().map(|arg| (arg, depth + 1))),
+            }
+        }
+        max_depth
+    }
+
+    /// Number of nodes in the expression tree, counted without recursion like `depth`.
+    pub fn node_count(&self) -> usize {
+        let mut count = 0;
+        let mut stack = vec![self];
+        while let Some(expr) = stack.pop() {
+            count += 1;
+            match &expr.node {
+                ExprKind::Literal { .. } | ExprKind::Identifier { .. } => (),
+                ExprKind::BinOp { left, right, .. } => {
+                    stack.push(left);
+                    stack.push(right);
+                }
+                ExprKind::UnaryOp { expr, .. } | ExprKind::Assign { expr, .. } | ExprKind::TypeOf { expr } => {
+                    stack.push(expr)
+                }
+                ExprKind::Call { args, .. } => stack.extend(args.iter()),
+            }
+        }
+        count
+    }
 }
 
 #[derive(PartialEq, Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Stmt {
     Declare {
         dtype: Primitive,
         mutable: bool,
         name: String,
         expr: Expr,
+        node_id: NodeId,
         span: Span,
     },
     MutAssign {
         name: String,
         expr: Expr,
+        node_id: NodeId,
         span: Span,
     },
     Print {
         expr: Expr,
+        node_id: NodeId,
+        span: Span,
+    },
+    EPrint {
+        expr: Expr,
+        node_id: NodeId,
+        span: Span,
+    },
+    Expr {
+        expr: Expr,
+        node_id: NodeId,
+        span: Span,
+    },
+    Swap {
+        left: String,
+        right: String,
+        node_id: NodeId,
+        span: Span,
+    },
+    /// A brace-delimited list of statements with its own scope.
+    Block {
+        body: Ast,
+        node_id: NodeId,
+        span: Span,
+    },
+    /// `if (cond) { ... } else { ... }`, an `else if` is an else branch holding a single if statement.
+    If {
+        cond: Expr,
+        then_body: Ast,
+        else_body: Option<Ast>,
+        node_id: NodeId,
+        span: Span,
+    },
+    /// `for (init; cond; step) { ... }`, the variables declared by `init` are only visible inside the loop.
+    For {
+        init: Box<Stmt>,
+        cond: Expr,
+        step: Box<Stmt>,
+        body: Ast,
+        node_id: NodeId,
+        span: Span,
+    },
+    /// `fn name(int a, float b) -> int { ... }`, only allowed at the top level. A function without
+    /// `-> type` has no return type and returns no value.
+    FnDecl {
+        name: String,
+        params: Vec<(String, Primitive)>,
+        ret: Option<Primitive>,
+        body: Ast,
+        node_id: NodeId,
         span: Span,
     },
+    /// `return expr;`, or a bare `return;` in a function without return type.
+    Return {
+        expr: Option<Expr>,
+        node_id: NodeId,
+        span: Span,
+    },
+}
+
+impl Stmt {
+    pub fn span(&self) -> &Span {
+        match self {
+            Stmt::Declare { span, .. }
+            | Stmt::MutAssign { span, .. }
+            | Stmt::Print { span, .. }
+            | Stmt::EPrint { span, .. }
+            | Stmt::Expr { span, .. }
+            | Stmt::Swap { span, .. }
+            | Stmt::Block { span, .. }
+            | Stmt::If { span, .. }
+            | Stmt::For { span, .. }
+            | Stmt::FnDecl { span, .. }
+            | Stmt::Return { span, .. } => span,
+        }
+    }
+
+    pub fn node_id(&self) -> NodeId {
+        match self {
+            Stmt::Declare { node_id, .. }
+            | Stmt::MutAssign { node_id, .. }
+            | Stmt::Print { node_id, .. }
+            | Stmt::EPrint { node_id, .. }
+            | Stmt::Expr { node_id, .. }
+            | Stmt::Swap { node_id, .. }
+            | Stmt::Block { node_id, .. }
+            | Stmt::If { node_id, .. }
+            | Stmt::For { node_id, .. }
+            | Stmt::FnDecl { node_id, .. }
+            | Stmt::Return { node_id, .. } => *node_id,
+        }
+    }
+
+    /// The statement lists nested in this statement, e.g. the body of a block or the branches of an if.
+    /// The init and step clauses of a for loop are not part of its bodies.
+    pub fn bodies(&self) -> Vec<&Ast> {
+        match self {
+            Stmt::Block { body, .. } | Stmt::For { body, .. } | Stmt::FnDecl { body, .. } => vec![body],
+            Stmt::If {
+                then_body, else_body, ..
+            } => std::iter::once(then_body).chain(else_body).collect(),
+            _ => vec![],
+        }
+    }
 }
 
 pub type Ast = Vec<Stmt>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_primitive_predicates() {
+        let named = Primitive::Named("Color".to_string());
+        let cases = [
+            (Primitive::Int, true, true, false, false),
+            (Primitive::Float, true, false, true, false),
+            (Primitive::Bool, false, false, false, true),
+            (Primitive::Str, false, false, false, false),
+            (Primitive::Char, false, false, false, false),
+            (named, false, false, false, false),
+        ];
+        for (primitive, numeric, integer, float, boolean) in cases {
+            assert_eq!(primitive.is_numeric(), numeric, "{:?}", primitive);
+            assert_eq!(primitive.is_integer(), integer, "{:?}", primitive);
+            assert_eq!(primitive.is_float(), float, "{:?}", primitive);
+            assert_eq!(primitive.is_boolean(), boolean, "{:?}", primitive);
+        }
+    }
+
+    #[test]
+    fn test_conversion() {
+        let span = Span::default();
+        assert_eq!(Conversion::between(&Primitive::Int, &Primitive::Float, &span).unwrap(), Conversion::IntToFloat);
+        assert_eq!(Conversion::between(&Primitive::Float, &Primitive::Int, &span).unwrap(), Conversion::FloatToInt);
+        assert_eq!(Conversion::between(&Primitive::Bool, &Primitive::Bool, &span).unwrap(), Conversion::Identity);
+        assert!(matches!(
+            Conversion::between(&Primitive::Char, &Primitive::Int, &span),
+            Err(CompilerError::TypeDeclarationError { expected: Primitive::Int, found: Primitive::Char, .. })
+        ));
+    }
+
+    fn literal(value: &str) -> Expr {
+        Expr::new(
+            ExprKind::Literal {
+                value: value.to_string(),
+                primitive: Primitive::Int,
+            },
+            Span::default(),
+        )
+    }
+
+    fn token(kind: TokenKind) -> Token {
+        Token {
+            kind,
+            span: Span { line: 1, col: 3, end_line: 1, end_col: 3, file_id: None },
+        }
+    }
+
+    #[test]
+    fn test_binop_from_token() {
+        assert_eq!(
+            BinOpKind::try_from(&token(TokenKind::BinOp(BinOpKind::Mult))).unwrap(),
+            BinOpKind::Mult
+        );
+        assert_eq!(
+            BinOpKind::try_from(&token(TokenKind::BinOp(BinOpKind::Le))).unwrap(),
+            BinOpKind::Le
+        );
+        assert!(matches!(
+            BinOpKind::try_from(&token(TokenKind::BinOp(BinOpKind::Not))),
+            Err(CompilerError::SyntaxError { span, .. }) if span.col == 3
+        ));
+        assert!(BinOpKind::try_from(&token(TokenKind::BinOp(BinOpKind::Assign))).is_err());
+        assert!(BinOpKind::try_from(&token(TokenKind::LParen)).is_err());
+        assert!(BinOpKind::try_from(&token(TokenKind::Identifier("a".to_string()))).is_err());
+    }
+
+    #[test]
+    fn test_unaryop_from_token() {
+        assert_eq!(
+            UnaryOpKind::try_from(&token(TokenKind::BinOp(BinOpKind::Sub))).unwrap(),
+            UnaryOpKind::Neg
+        );
+        assert_eq!(
+            UnaryOpKind::try_from(&token(TokenKind::BinOp(BinOpKind::Not))).unwrap(),
+            UnaryOpKind::Not
+        );
+        assert!(UnaryOpKind::try_from(&token(TokenKind::BinOp(BinOpKind::Mult))).is_err());
+        assert!(UnaryOpKind::try_from(&token(TokenKind::EOS)).is_err());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_ast_json_round_trip() {
+        let source = "mut int a = -(1 + 2) * 3;\nfloat b = sqrt(a / 2.5);\nif (a > 1 && !false) {\n  a += 1;\n} else {\n  eprint(typeof(b));\n}\nfor (mut int i = 0; i < 3; i += 1) { print(\"i\"); }\nswap(a, a);";
+        let mut lexer = crate::lexer::Lexer::new(source);
+        lexer.tokenize().unwrap();
+        let mut parser = crate::parser::Parser::new(lexer.get_tokens().to_vec());
+        parser.parse().unwrap();
+        let ast = parser.into_tree();
+
+        let json = serde_json::to_string_pretty(&ast).unwrap();
+        let deserialized: Ast = serde_json::from_str(&json).unwrap();
+        assert_eq!(deserialized, ast);
+    }
+
+    #[test]
+    fn test_expr_depth() {
+        assert_eq!(literal("1").depth(), 1);
+
+        // -(1 + (2 * 3))
+        let product = Expr::new(
+            ExprKind::BinOp {
+                op: BinOpKind::Mult,
+                left: Box::new(literal("2")),
+                right: Box::new(literal("3")),
+            },
+            Span::default(),
+        );
+        let sum = Expr::new(
+            ExprKind::BinOp {
+                op: BinOpKind::Add,
+                left: Box::new(literal("1")),
+                right: Box::new(product),
+            },
+            Span::default(),
+        );
+        let expr = Expr::new(
+            ExprKind::UnaryOp {
+                op: UnaryOpKind::Neg,
+                expr: Box::new(sum),
+            },
+            Span::default(),
+        );
+        assert_eq!(expr.depth(), 4);
+        assert_eq!(expr.node_count(), 6);
+        assert_eq!(literal("1").node_count(), 1);
+    }
+
+    #[test]
+    fn test_spanned_accessors() {
+        let expr = Expr::new(
+            ExprKind::Identifier {
+                name: "a".to_string(),
+            },
+            Span { line: 2, col: 5, end_line: 2, end_col: 5, file_id: None },
+        );
+        assert_eq!(expr.node, ExprKind::Identifier { name: "a".to_string() });
+        assert_eq!(expr.span(), &Span { line: 2, col: 5, end_line: 2, end_col: 5, file_id: None });
+        assert_eq!(expr.node_id(), NodeId::default());
+    }
+}
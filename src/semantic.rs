@@ -1,23 +1,144 @@
 use crate::{
-    errors::CompilerError,
-    schemas::{Ast, BinOpKind, Expr, Identifier, Primitive, Span, Stmt, UnaryOpKind},
+    errors::{CompilerError, CompilerWarning},
+    interpreter::Value,
+    optimize::{fold_expr, literal_value},
+    schemas::{Ast, BinOpKind, Expr, ExprKind, Identifier, IntWidth, Primitive, Span, Stmt, UnaryOpKind},
 };
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+
+/// Expression depth accepted by default. The interpreter and the backends evaluate expressions
+/// recursively, so a long operator chain like `1 + 1 + ... + 1` would otherwise overflow the stack.
+pub const DEFAULT_MAX_EXPRESSION_DEPTH: usize = 256;
+
+/// Signature of a declared function, `ret` is `None` if it returns no value.
+#[derive(Clone)]
+struct Function {
+    params: Vec<Primitive>,
+    ret: Option<Primitive>,
+    span: Span,
+}
+
+/// Stack of scopes, the innermost block is on top. The bottom scope holds the global variables.
+#[derive(Clone)]
+pub(crate) struct SymbolTable {
+    scopes: Vec<HashMap<String, Identifier>>,
+    /// Functions live in their own namespace, a call is recognized by its parentheses.
+    functions: HashMap<String, Function>,
+    /// Name of the function whose body is being checked, `None` at the top level.
+    current_function: Option<String>,
+}
+
+impl SymbolTable {
+    fn new() -> Self {
+        SymbolTable {
+            scopes: vec![HashMap::new()],
+            functions: HashMap::new(),
+            current_function: None,
+        }
+    }
+
+    /// Looks up a variable, starting in the innermost scope so that inner declarations shadow outer ones.
+    fn get(&self, name: &str) -> Option<&Identifier> {
+        self.scopes.iter().rev().find_map(|scope| scope.get(name))
+    }
+
+    /// Looks up a variable in the innermost scope only, i.e. a declaration that a new one would clash with.
+    fn get_in_current_scope(&self, name: &str) -> Option<&Identifier> {
+        self.scopes.last().expect("the global scope is never popped").get(name)
+    }
+
+    /// The error for a use of the unknown variable `name`, suggesting the closest visible variable.
+    /// Names are suggested up to an edit distance of 2, but never by replacing every character.
+    fn name_error(&self, name: &str, span: &Span) -> CompilerError {
+        let visible = self.scopes.iter().flat_map(|scope| scope.keys().map(String::as_str));
+        CompilerError::NameError {
+            name: name.to_string(),
+            self_reference: false,
+            suggestion: closest_name(name, visible, 2.min(name.chars().count().saturating_sub(1))),
+            span: span.clone(),
+        }
+    }
+
+    fn insert(&mut self, name: String, identifier: Identifier) {
+        self.scopes.last_mut().expect("the global scope is never popped").insert(name, identifier);
+    }
+
+    fn push_scope(&mut self) {
+        self.scopes.push(HashMap::new());
+    }
+
+    fn pop_scope(&mut self) {
+        if self.scopes.len() > 1 {
+            self.scopes.pop();
+        }
+    }
+
+    fn globals(&self) -> &HashMap<String, Identifier> {
+        &self.scopes[0]
+    }
+}
 
 pub struct SemanticAnalyser {
     ast: Ast,
-    symbol_table: HashMap<String, Identifier>,
+    symbol_table: SymbolTable,
+    warnings: Vec<CompilerWarning>,
+    allowed_warnings: Vec<String>,
+    warn_unused_variables: bool,
+    last_expression_type: Option<Primitive>,
+    max_expression_depth: Option<usize>,
+    require_output: bool,
+    int_width: IntWidth,
 }
 
 impl SemanticAnalyser {
     pub fn new(ast: Ast) -> Self {
         SemanticAnalyser {
-            ast: ast,
-            symbol_table: HashMap::new(),
+            ast,
+            symbol_table: SymbolTable::new(),
+            warnings: vec![],
+            allowed_warnings: vec![],
+            warn_unused_variables: false,
+            last_expression_type: None,
+            max_expression_depth: Some(DEFAULT_MAX_EXPRESSION_DEPTH),
+            require_output: false,
+            int_width: IntWidth::default(),
+        }
+    }
+
+    /// Enables the (opt-in) detection of variables which are declared but never read.
+    pub fn warn_unused_variables(&mut self, enabled: bool) {
+        self.warn_unused_variables = enabled;
+    }
+
+    /// Rejects expressions nested deeper than `limit` before type checking them recursively, by default
+    /// `DEFAULT_MAX_EXPRESSION_DEPTH`. Later stages recurse on expressions as well, so `None` is only safe
+    /// for trees which are not interpreted or compiled afterwards.
+    pub fn max_expression_depth(&mut self, limit: Option<usize>) {
+        self.max_expression_depth = limit;
+    }
+
+    /// Warns if the program doesn't end with a statement producing output, e.g. for automated grading.
+    pub fn require_output(&mut self, enabled: bool) {
+        self.require_output = enabled;
+    }
+
+    /// Sets the width of the `int` primitive which all int literals are checked against, 64 bit by default.
+    pub fn int_width(&mut self, width: IntWidth) {
+        self.int_width = width;
+    }
+
+    /// Suppresses all warnings with the given code, e.g. "unused-variable".
+    pub fn allow(&mut self, code: &str) {
+        if !self.allowed_warnings.iter().any(|allowed| allowed == code) {
+            self.allowed_warnings.push(code.to_string());
         }
     }
 
-    fn infer_binop_type(
+    pub fn get_allowed_warnings(&self) -> &Vec<String> {
+        &self.allowed_warnings
+    }
+
+    pub(crate) fn infer_binop_type(
         op: &BinOpKind,
         left_type: &Primitive,
         right_type: &Primitive,
@@ -29,15 +150,28 @@ impl SemanticAnalyser {
                 Ok(Primitive::Int)
             }
 
+            // Exponentiation returns int for int operands, like addition.
+            (BinOpKind::Pow, Primitive::Int, Primitive::Int) => Ok(Primitive::Int),
+
+            // Modulo returns int for int operands.
+            (BinOpKind::Mod, Primitive::Int, Primitive::Int) => Ok(Primitive::Int),
+
+            // Bitwise operations and shifts are only defined on int operands.
+            (BinOpKind::BitAnd | BinOpKind::BitOr | BinOpKind::Shl | BinOpKind::Shr, Primitive::Int, Primitive::Int) => {
+                Ok(Primitive::Int)
+            }
+
             // Division returns float for int operands.
             (BinOpKind::Div, Primitive::Int, Primitive::Int) => Ok(Primitive::Float),
 
-            // Any airthmetic operation with one or more float operand returns float.
-            (
-                BinOpKind::Add | BinOpKind::Sub | BinOpKind::Mult | BinOpKind::Div,
-                Primitive::Int | Primitive::Float,
-                Primitive::Int | Primitive::Float,
-            ) => Ok(Primitive::Float),
+            // Any airthmetic operation with one or more float operand returns float. Like Rust's `%`, a float
+            // modulo is the truncated remainder, whose sign follows the dividend, e.g. `-7.5 % 2` is `-1.5`.
+            // Unlike Rust, which gives NaN, a modulo by zero is a runtime error, just like a division by zero.
+            (BinOpKind::Add | BinOpKind::Sub | BinOpKind::Mult | BinOpKind::Div | BinOpKind::Pow | BinOpKind::Mod, left, right)
+                if left.is_numeric() && right.is_numeric() =>
+            {
+                Ok(Primitive::Float)
+            }
 
             // Boolean operation on bool operands return bool.
             (
@@ -46,6 +180,10 @@ impl SemanticAnalyser {
                 Primitive::Bool,
             ) => Ok(Primitive::Bool),
 
+            // Strings and chars can only be compared for equality.
+            (BinOpKind::Eq | BinOpKind::Ne, Primitive::Str, Primitive::Str)
+            | (BinOpKind::Eq | BinOpKind::Ne, Primitive::Char, Primitive::Char) => Ok(Primitive::Bool),
+
             // Comparison operations on int and float return bool.
             (
                 BinOpKind::Gt
@@ -54,25 +192,20 @@ impl SemanticAnalyser {
                 | BinOpKind::Le
                 | BinOpKind::Eq
                 | BinOpKind::Ne,
-                Primitive::Int | Primitive::Float,
-                Primitive::Int | Primitive::Float,
-            ) => Ok(Primitive::Bool),
+                left,
+                right,
+            ) if left.is_numeric() && right.is_numeric() => Ok(Primitive::Bool),
 
             // Int and float can be assigned to each other, bool only to bool.
             (BinOpKind::Assign, left_type, right_type) => {
-                if left_type == right_type {
+                if left_type == right_type || (left_type.is_numeric() && right_type.is_numeric()) {
                     return Ok(left_type.clone());
                 }
-                match (left_type, right_type) {
-                    (Primitive::Int, Primitive::Int | Primitive::Float) => Ok(Primitive::Int),
-                    (Primitive::Float, Primitive::Int | Primitive::Float) => Ok(Primitive::Float),
-                    (Primitive::Bool, Primitive::Bool) => Ok(Primitive::Bool),
-                    _ => Err(CompilerError::TypeDeclarationError {
-                        expected: left_type.clone(),
-                        found: right_type.clone(),
-                        span: span.clone(),
-                    }),
-                }
+                Err(CompilerError::TypeDeclarationError {
+                    expected: left_type.clone(),
+                    found: right_type.clone(),
+                    span: span.clone(),
+                })
             }
             _ => Err(CompilerError::TypeBinOpError {
                 op: op.clone(),
@@ -90,11 +223,14 @@ impl SemanticAnalyser {
     ) -> Result<Primitive, CompilerError> {
         match (op, operand_type) {
             // Unary negation (-) only valid on int or float
-            (UnaryOpKind::Neg, Primitive::Int | Primitive::Float) => Ok(operand_type.clone()),
+            (UnaryOpKind::Neg, operand_type) if operand_type.is_numeric() => Ok(operand_type.clone()),
 
             // Logical not (!) only valid on bool
             (UnaryOpKind::Not, Primitive::Bool) => Ok(Primitive::Bool),
 
+            // Bitwise not (~) only valid on int
+            (UnaryOpKind::BitNot, Primitive::Int) => Ok(Primitive::Int),
+
             _ => Err(CompilerError::TypeUnaryOpError {
                 op: op.clone(),
                 operand: operand_type.clone(),
@@ -103,49 +239,272 @@ impl SemanticAnalyser {
         }
     }
 
+    /// Errors on an int division or modulo by zero whose operands are constant once folded, e.g.
+    /// `5 / (2 - 2)`. Divisions by variables are left to the runtime.
+    fn check_division_by_zero(left: &Expr, right: &Expr, span: &Span) -> Result<(), CompilerError> {
+        let (left, right) = (fold_expr(left.clone()), fold_expr(right.clone()));
+        match (literal_value(&left), literal_value(&right)) {
+            (Some(Value::Int(_)), Some(Value::Int(0))) => Err(CompilerError::DivisionByZero { span: span.clone() }),
+            _ => Ok(()),
+        }
+    }
+
     fn check_expr(
         expr: &Expr,
-        symbol_table: &HashMap<String, Identifier>,
+        symbol_table: &SymbolTable,
     ) -> Result<Primitive, CompilerError> {
-        match expr {
-            Expr::Literal { primitive, .. } => {
-                return Ok(primitive.clone());
-            }
-            Expr::Identifier { name, span } => match symbol_table.get(name) {
-                Some(identifier) => return Ok(identifier.primitive.clone()),
-                None => Err(CompilerError::NameError {
-                    name: name.to_string(),
-                    span: span.clone(),
-                }),
+        let span = &expr.span;
+        match &expr.node {
+            ExprKind::Literal { primitive, .. } => Ok(primitive.clone()),
+            ExprKind::Identifier { name } => match symbol_table.get(name) {
+                Some(identifier) => Ok(identifier.primitive.clone()),
+                None => Err(symbol_table.name_error(name, span)),
             },
-            Expr::BinOp {
-                op,
-                left,
-                right,
-                span,
-            } => {
+            ExprKind::BinOp { op, left, right } => {
                 let left_type = Self::check_expr(left, symbol_table)?;
                 let right_type = Self::check_expr(right, symbol_table)?;
 
-                match Self::infer_binop_type(&op, &left_type, &right_type, &span) {
-                    Ok(infered_type) => Ok(infered_type),
-                    Err(err) => Err(err),
+                let infered_type = Self::infer_binop_type(op, &left_type, &right_type, span)?;
+                if matches!(op, BinOpKind::Div | BinOpKind::Mod) && left_type.is_integer() && right_type.is_integer() {
+                    Self::check_division_by_zero(left, right, span)?;
                 }
+                Ok(infered_type)
             }
-            Expr::UnaryOp { op, expr, span } => {
+            ExprKind::UnaryOp { op, expr } => {
                 let expr = Self::check_expr(expr, symbol_table)?;
-                match Self::infer_unaryop_type(&op, &expr, &span) {
+                match Self::infer_unaryop_type(op, &expr, span) {
                     Ok(infered_type) => Ok(infered_type),
                     Err(err) => Err(err),
                 }
             }
+            ExprKind::TypeOf { expr } => {
+                Self::check_expr(expr, symbol_table)?;
+                Ok(Primitive::Str)
+            }
+            ExprKind::Call { name, args } => Self::check_call(name, args, span, symbol_table),
+            // An assignment expression evaluates to the assigned value, converted to the variable's type.
+            ExprKind::Assign { name, expr } => {
+                let Some(symbol) = symbol_table.get(name) else {
+                    return Err(symbol_table.name_error(name, span));
+                };
+                if !symbol.mutable {
+                    return Err(CompilerError::MutabilityError {
+                        name: name.to_string(),
+                        span: span.clone(),
+                    });
+                }
+
+                let expr_type = Self::check_expr(expr, symbol_table)?;
+                Self::infer_binop_type(&BinOpKind::Assign, &symbol.primitive, &expr_type, span)
+            }
+        }
+    }
+
+    /// Checks a call of a declared function, whose arguments are converted to the parameter types like
+    /// in an assignment, or of one of the builtin math functions `pow(base, exp)` and `sqrt(x)`. The
+    /// arguments of the builtins may be int or float and are promoted to float, so they always return float.
+    fn check_call(
+        name: &str,
+        args: &[Expr],
+        span: &Span,
+        symbol_table: &SymbolTable,
+    ) -> Result<Primitive, CompilerError> {
+        if let Some(function) = symbol_table.functions.get(name) {
+            Self::check_arguments(name, function, args, span, symbol_table)?;
+            return function.ret.clone().ok_or_else(|| CompilerError::UnitFunctionValue {
+                name: name.to_string(),
+                span: span.clone(),
+            });
+        }
+
+        // The conversions between a char and its code point.
+        let conversion = match name {
+            "int" => Some((Primitive::Char, Primitive::Int)),
+            "char" => Some((Primitive::Int, Primitive::Char)),
+            _ => None,
+        };
+        if let Some((param, ret)) = conversion {
+            if args.len() != 1 {
+                return Err(CompilerError::ArgumentCountError {
+                    name: name.to_string(),
+                    expected: 1,
+                    found: args.len(),
+                    span: span.clone(),
+                });
+            }
+            let arg_type = Self::check_expr(&args[0], symbol_table)?;
+            if arg_type != param {
+                return Err(CompilerError::TypeDeclarationError {
+                    expected: param,
+                    found: arg_type,
+                    span: args[0].span.clone(),
+                });
+            }
+            return Ok(ret);
+        }
+
+        let arity = match name {
+            "pow" => 2,
+            "sqrt" => 1,
+            _ => {
+                let functions = symbol_table.functions.keys().map(String::as_str);
+                return Err(CompilerError::NameError {
+                    name: name.to_string(),
+                    self_reference: false,
+                    suggestion: closest_name(name, ["pow", "sqrt"].into_iter().chain(functions), 2),
+                    span: span.clone(),
+                });
+            }
+        };
+        if args.len() != arity {
+            return Err(CompilerError::ArgumentCountError {
+                name: name.to_string(),
+                expected: arity,
+                found: args.len(),
+                span: span.clone(),
+            });
+        }
+
+        for arg in args {
+            let arg_type = Self::check_expr(arg, symbol_table)?;
+            if !arg_type.is_numeric() {
+                return Err(CompilerError::TypeDeclarationError {
+                    expected: Primitive::Float,
+                    found: arg_type,
+                    span: arg.span.clone(),
+                });
+            }
+        }
+        Ok(Primitive::Float)
+    }
+
+    /// Checks the number and types of the arguments of a call of a declared function.
+    fn check_arguments(
+        name: &str,
+        function: &Function,
+        args: &[Expr],
+        span: &Span,
+        symbol_table: &SymbolTable,
+    ) -> Result<(), CompilerError> {
+        if args.len() != function.params.len() {
+            return Err(CompilerError::ArgumentCountError {
+                name: name.to_string(),
+                expected: function.params.len(),
+                found: args.len(),
+                span: span.clone(),
+            });
+        }
+        for (arg, param) in args.iter().zip(&function.params) {
+            let arg_type = Self::check_expr(arg, symbol_table)?;
+            Self::infer_binop_type(&BinOpKind::Assign, param, &arg_type, &arg.span)?;
+        }
+        Ok(())
+    }
+
+    /// Checks that a (possibly negated) literal initializer fits into the declared type.
+    fn check_literal_range(dtype: &Primitive, expr: &Expr, int_width: IntWidth) -> Result<(), CompilerError> {
+        let (negated, literal) = match &expr.node {
+            ExprKind::UnaryOp {
+                op: UnaryOpKind::Neg,
+                expr,
+            } => (true, expr.as_ref()),
+            _ => (false, expr),
+        };
+        let ExprKind::Literal { value, primitive } = &literal.node else {
+            return Ok(());
+        };
+        let span = &literal.span;
+
+        let in_range = match (dtype, primitive) {
+            // Int literals are parsed with a wider type so that `-9223372036854775808` is accepted.
+            (Primitive::Int, Primitive::Int) => match value.parse::<i128>() {
+                Ok(value) => {
+                    let value = if negated { -value } else { value };
+                    value >= int_width.min() && value <= int_width.max()
+                }
+                Err(_) => false,
+            },
+            (Primitive::Int, Primitive::Float) => match value.parse::<f64>() {
+                Ok(value) => {
+                    let value = if negated { -value } else { value };
+                    // `i64::MAX as f64` rounds up to 2^63, so compare against the first value out of range.
+                    value >= int_width.min() as f64 && value < (int_width.max() + 1) as f64
+                }
+                Err(_) => false,
+            },
+            (Primitive::Float, Primitive::Int | Primitive::Float) => {
+                value.parse::<f64>().is_ok_and(|value| value.is_finite())
+            }
+            _ => true,
+        };
+
+        if !in_range {
+            return Err(CompilerError::LiteralOutOfRange {
+                value: value.clone(),
+                primitive: dtype.clone(),
+                span: span.clone(),
+            });
+        }
+        Ok(())
+    }
+
+    /// Checks that every int literal of the expression fits into the int width, not only the ones
+    /// initializing a declaration. A negated literal is checked with its sign.
+    fn check_int_literals(expr: &Expr, int_width: IntWidth) -> Result<(), CompilerError> {
+        match &expr.node {
+            ExprKind::Literal {
+                primitive: Primitive::Int,
+                ..
+            } => Self::check_literal_range(&Primitive::Int, expr, int_width),
+            ExprKind::UnaryOp {
+                op: UnaryOpKind::Neg,
+                expr: operand,
+            } if matches!(
+                operand.node,
+                ExprKind::Literal {
+                    primitive: Primitive::Int,
+                    ..
+                }
+            ) =>
+            {
+                Self::check_literal_range(&Primitive::Int, expr, int_width)
+            }
+            ExprKind::Literal { .. } | ExprKind::Identifier { .. } => Ok(()),
+            ExprKind::BinOp { left, right, .. } => {
+                Self::check_int_literals(left, int_width)?;
+                Self::check_int_literals(right, int_width)
+            }
+            ExprKind::UnaryOp { expr, .. } | ExprKind::Assign { expr, .. } | ExprKind::TypeOf { expr } => {
+                Self::check_int_literals(expr, int_width)
+            }
+            ExprKind::Call { args, .. } => args.iter().try_for_each(|arg| Self::check_int_literals(arg, int_width)),
+        }
+    }
+
+    /// Returns the expression a statement evaluates itself, without the ones of nested statements.
+    fn stmt_expr(stmt: &Stmt) -> Option<&Expr> {
+        match stmt {
+            Stmt::Declare { expr, .. }
+            | Stmt::MutAssign { expr, .. }
+            | Stmt::Print { expr, .. }
+            | Stmt::EPrint { expr, .. }
+            | Stmt::Expr { expr, .. }
+            | Stmt::If { cond: expr, .. }
+            | Stmt::For { cond: expr, .. }
+            | Stmt::Return { expr: Some(expr), .. } => Some(expr),
+            Stmt::Swap { .. } | Stmt::Block { .. } | Stmt::FnDecl { .. } | Stmt::Return { expr: None, .. } => None,
         }
     }
 
     fn check_stmt(
         stmt: &Stmt,
-        symbol_table: &mut HashMap<String, Identifier>,
-    ) -> Result<(), CompilerError> {
+        symbol_table: &mut SymbolTable,
+        warnings: &mut Vec<CompilerWarning>,
+        int_width: IntWidth,
+    ) -> Result<Option<Primitive>, CompilerError> {
+        if let Some(expr) = Self::stmt_expr(stmt) {
+            Self::check_int_literals(expr, int_width)?;
+        }
         match stmt {
             Stmt::Declare {
                 dtype,
@@ -153,7 +512,34 @@ impl SemanticAnalyser {
                 expr,
                 span,
                 mutable,
+                ..
             } => {
+                Self::check_type(dtype, span)?;
+
+                // Shadowing a variable of an enclosing scope is fine, declaring it twice in one scope is not.
+                if let Some(original) = symbol_table.get_in_current_scope(name) {
+                    return Err(CompilerError::RedeclarationError {
+                        name: name.clone(),
+                        original_span: original.span.clone(),
+                        span: span.clone(),
+                    });
+                }
+
+                // The initializer is checked before the variable is declared, so that it cannot refer to itself.
+                let expr_type = Self::check_expr(expr, symbol_table).map_err(|err| match err {
+                    CompilerError::NameError {
+                        name: unknown_name,
+                        span: name_span,
+                        ..
+                    } if unknown_name == *name => CompilerError::NameError {
+                        name: unknown_name,
+                        self_reference: true,
+                        suggestion: None,
+                        span: name_span,
+                    },
+                    err => err,
+                })?;
+                Self::check_int_division_to_int(name, dtype, expr, symbol_table, warnings)?;
                 symbol_table.insert(
                     name.to_string(),
                     Identifier {
@@ -162,19 +548,16 @@ impl SemanticAnalyser {
                         mutable: *mutable,
                     },
                 );
-                let expr_type = Self::check_expr(expr, symbol_table)?;
+                Self::check_literal_range(dtype, expr, int_width)?;
                 match Self::infer_binop_type(&BinOpKind::Assign, dtype, &expr_type, span) {
-                    Ok(_) => Ok(()),
-                    Err(err) => return Err(err),
+                    Ok(_) => Ok(Some(expr_type)),
+                    Err(err) => Err(err),
                 }
             }
-            Stmt::MutAssign { name, expr, span } => {
+            Stmt::MutAssign { name, expr, span, .. } => {
                 let symbol = match symbol_table.get(name) {
                     Some(identifier) => identifier,
-                    None => return Err(CompilerError::NameError {
-                        name: name.to_string(),
-                        span: span.clone(),
-                    }),
+                    None => return Err(symbol_table.name_error(name, span)),
                 };
 
                 if !symbol.mutable {
@@ -185,109 +568,745 @@ impl SemanticAnalyser {
                 }
 
                 let expr_type = Self::check_expr(expr, symbol_table)?;
+                Self::check_int_division_to_int(name, &symbol.primitive, expr, symbol_table, warnings)?;
                 match Self::infer_binop_type(&BinOpKind::Assign, &symbol.primitive, &expr_type, span) {
-                    Ok(_) => Ok(()),
-                    Err(err) => return Err(err),
+                    Ok(_) => Ok(None),
+                    Err(err) => Err(err),
                 }
             },
-            Stmt::Print { expr, span: _ } => {
-                Self::check_expr(expr, symbol_table)?;
-                Ok(())
+            Stmt::Print { expr, .. } | Stmt::EPrint { expr, .. } => {
+                Ok(Some(Self::check_expr(expr, symbol_table)?))
+            }
+            Stmt::Expr { expr, .. } => {
+                // A function without return type can only be called as a statement of its own.
+                if let ExprKind::Call { name, args } = &expr.node
+                    && let Some(function @ Function { ret: None, .. }) = symbol_table.functions.get(name)
+                {
+                    Self::check_arguments(name, function, args, &expr.span, symbol_table)?;
+                    return Ok(None);
+                }
+
+                let expr_type = Self::check_expr(expr, symbol_table)?;
+
+                // A comparison as a statement has no effect, `a == 2;` is likely a mistyped assignment.
+                if let ExprKind::BinOp { op, left, .. } = &expr.node
+                    && matches!(
+                        op,
+                        BinOpKind::Eq | BinOpKind::Ne | BinOpKind::Gt | BinOpKind::Lt | BinOpKind::Ge | BinOpKind::Le
+                    )
+                {
+                    let assign_hint = match (op, &left.node) {
+                        (BinOpKind::Eq, ExprKind::Identifier { name }) => Some(name.clone()),
+                        _ => None,
+                    };
+                    warnings.push(CompilerWarning::DiscardedComparison {
+                        op: op.clone(),
+                        assign_hint,
+                        span: expr.span.clone(),
+                    });
+                }
+                Ok(Some(expr_type))
+            }
+            Stmt::Swap { left, right, span, .. } => {
+                let mut primitives = vec![];
+                for name in [left, right] {
+                    let symbol = match symbol_table.get(name) {
+                        Some(identifier) => identifier,
+                        None => return Err(symbol_table.name_error(name, span)),
+                    };
+                    if !symbol.mutable {
+                        return Err(CompilerError::MutabilityError {
+                            name: name.to_string(),
+                            span: span.clone(),
+                        });
+                    }
+                    primitives.push(symbol.primitive.clone());
+                }
+
+                // Swapped variables must have the exact same type, no int/float coercion happens.
+                if primitives[0] != primitives[1] {
+                    return Err(CompilerError::TypeDeclarationError {
+                        expected: primitives[0].clone(),
+                        found: primitives[1].clone(),
+                        span: span.clone(),
+                    });
+                }
+                Ok(None)
+            }
+            Stmt::If {
+                cond,
+                then_body,
+                else_body,
+                ..
+            } => {
+                Self::check_condition(cond, symbol_table)?;
+                Self::check_block(then_body, symbol_table, warnings, int_width)?;
+                if let Some(else_body) = else_body {
+                    Self::check_block(else_body, symbol_table, warnings, int_width)?;
+                }
+                Ok(None)
+            }
+            Stmt::Block { body, .. } => {
+                Self::check_block(body, symbol_table, warnings, int_width)?;
+                Ok(None)
+            }
+            // The loop header gets a scope around the one of the body, so the loop variable ends with the loop.
+            Stmt::For {
+                init, cond, step, body, ..
+            } => {
+                symbol_table.push_scope();
+                let result = Self::check_for_loop(init, cond, step, body, symbol_table, warnings, int_width);
+                symbol_table.pop_scope();
+                result.map(|_| None)
+            }
+            Stmt::FnDecl {
+                name,
+                params,
+                ret,
+                body,
+                span,
+                ..
+            } => {
+                if let Some(ret) = ret {
+                    Self::check_type(ret, span)?;
+                }
+                for (_, primitive) in params {
+                    Self::check_type(primitive, span)?;
+                }
+                if let Some(original) = symbol_table.functions.get(name) {
+                    return Err(CompilerError::RedeclarationError {
+                        name: name.clone(),
+                        original_span: original.span.clone(),
+                        span: span.clone(),
+                    });
+                }
+
+                // The function is declared before its body is checked, so that it can call itself.
+                symbol_table.functions.insert(
+                    name.clone(),
+                    Function {
+                        params: params.iter().map(|(_, primitive)| primitive.clone()).collect(),
+                        ret: ret.clone(),
+                        span: span.clone(),
+                    },
+                );
+                symbol_table.push_scope();
+                let enclosing_function = symbol_table.current_function.replace(name.clone());
+                let result = Self::check_function_body(params, body, span, symbol_table, warnings, int_width);
+                symbol_table.current_function = enclosing_function;
+                symbol_table.pop_scope();
+                result?;
+
+                if ret.is_some() && !Self::always_returns(body) {
+                    return Err(CompilerError::MissingReturn {
+                        name: name.clone(),
+                        span: span.clone(),
+                    });
+                }
+                Ok(None)
+            }
+            Stmt::Return { expr, span, .. } => {
+                let Some(name) = symbol_table.current_function.clone() else {
+                    return Err(CompilerError::ReturnOutsideFunction { span: span.clone() });
+                };
+                let ret = symbol_table.functions.get(&name).and_then(|function| function.ret.clone());
+                match (expr, ret) {
+                    (Some(expr), Some(ret)) => {
+                        let expr_type = Self::check_expr(expr, symbol_table)?;
+                        Self::infer_binop_type(&BinOpKind::Assign, &ret, &expr_type, &expr.span)?;
+                    }
+                    // A bare `return;` leaves a function that has to return a value without one.
+                    (None, Some(_)) => return Err(CompilerError::MissingReturn { name, span: span.clone() }),
+                    (Some(expr), None) => {
+                        Self::check_expr(expr, symbol_table)?;
+                        return Err(CompilerError::UnitFunctionValue {
+                            name,
+                            span: expr.span.clone(),
+                        });
+                    }
+                    (None, None) => (),
+                }
+                Ok(None)
             }
         }
     }
 
-    pub fn check(&mut self) -> Result<(), CompilerError> {
-        for stmt in &self.ast {
-            match Self::check_stmt(&stmt, &mut self.symbol_table) {
-                Ok(_) => (),
-                Err(err) => return Err(err),
+    /// Checks the body of a function in the current scope, after declaring the parameters in it. The
+    /// parameters share the scope with the body, so a declaration in the body cannot shadow them.
+    fn check_function_body(
+        params: &[(String, Primitive)],
+        body: &Ast,
+        span: &Span,
+        symbol_table: &mut SymbolTable,
+        warnings: &mut Vec<CompilerWarning>,
+        int_width: IntWidth,
+    ) -> Result<(), CompilerError> {
+        for (name, primitive) in params {
+            if symbol_table.get_in_current_scope(name).is_some() {
+                return Err(CompilerError::RedeclarationError {
+                    name: name.clone(),
+                    original_span: span.clone(),
+                    span: span.clone(),
+                });
             }
+            symbol_table.insert(
+                name.clone(),
+                Identifier {
+                    primitive: primitive.clone(),
+                    span: span.clone(),
+                    mutable: false,
+                },
+            );
         }
-        Ok(())
+        body.iter()
+            .try_for_each(|stmt| Self::check_stmt(stmt, symbol_table, warnings, int_width).map(|_| ()))
     }
 
-    pub fn get_symbol_table(&self) -> &HashMap<String, Identifier> {
-        return &self.symbol_table;
+    /// Whether executing the statements always ends in a `return`. Loops are not followed, as their
+    /// body may not run at all.
+    fn always_returns(body: &[Stmt]) -> bool {
+        body.iter().any(|stmt| match stmt {
+            Stmt::Return { .. } => true,
+            Stmt::Block { body, .. } => Self::always_returns(body),
+            Stmt::If {
+                then_body,
+                else_body: Some(else_body),
+                ..
+            } => Self::always_returns(then_body) && Self::always_returns(else_body),
+            _ => false,
+        })
     }
-}
-
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use crate::{lexer::Lexer, parser::Parser};
 
-    fn check(input: &str) -> Result<(), CompilerError> {
-        let mut lexer = Lexer::new(&(input.to_owned() + "\0"));
-        lexer.tokenize()?;
+    /// Checks that the condition of an if statement or loop is a bool.
+    fn check_condition(cond: &Expr, symbol_table: &SymbolTable) -> Result<(), CompilerError> {
+        let cond_type = Self::check_expr(cond, symbol_table)?;
+        if cond_type != Primitive::Bool {
+            return Err(CompilerError::TypeDeclarationError {
+                expected: Primitive::Bool,
+                found: cond_type,
+                span: cond.span.clone(),
+            });
+        }
+        Ok(())
+    }
 
-        let mut parser = Parser::new(lexer.get_tokens().to_vec());
-        parser.parse()?;
-        let mut parser = SemanticAnalyser::new(parser.get_tree().to_vec());
-        parser.check()?;
+    /// Validates a type written in the source, every type name that isn't built in is reported here.
+    fn check_type(dtype: &Primitive, span: &Span) -> Result<(), CompilerError> {
+        // There are no user-defined types yet, so every named type is unknown.
+        match dtype {
+            Primitive::Named(type_name) => Err(CompilerError::UnknownType {
+                name: type_name.clone(),
+                suggestion: closest_name(
+                    type_name,
+                    Primitive::BUILTINS.iter().map(Primitive::name),
+                    type_name.chars().count() / 3 + 1,
+                ),
+                span: span.clone(),
+            }),
+            _ => Ok(()),
+        }
+    }
 
+    /// Warns if the value assigned to the int variable `name` is a division of two ints, since its
+    /// float result is silently truncated, e.g. `int d = 7 / 2;` stores 3.
+    fn check_int_division_to_int(
+        name: &str,
+        dtype: &Primitive,
+        expr: &Expr,
+        symbol_table: &SymbolTable,
+        warnings: &mut Vec<CompilerWarning>,
+    ) -> Result<(), CompilerError> {
+        let ExprKind::BinOp {
+            op: BinOpKind::Div,
+            left,
+            right,
+        } = &expr.node
+        else {
+            return Ok(());
+        };
+        if *dtype == Primitive::Int
+            && Self::check_expr(left, symbol_table)? == Primitive::Int
+            && Self::check_expr(right, symbol_table)? == Primitive::Int
+        {
+            warnings.push(CompilerWarning::IntDivisionToInt {
+                name: name.to_string(),
+                span: expr.span.clone(),
+            });
+        }
         Ok(())
     }
 
-    #[test]
-    fn test_correct_program_analysis() {
-        check(
-            "
-            int a = (1 * (2 + 3));
-            float b = -a / 5;
-            print(b + 3);
-            float c = 0.00001;
-            float d = a + c;
-            float e = a;
-            bool b1 = 2 == 2;
-            bool b2 = !(true && (2 > 0.5)) || b1 != (e <= 200);
-            print(true && b2);
-            \0
-        ",
-        )
-        .unwrap();
+    fn check_for_loop(
+        init: &Stmt,
+        cond: &Expr,
+        step: &Stmt,
+        body: &Ast,
+        symbol_table: &mut SymbolTable,
+        warnings: &mut Vec<CompilerWarning>,
+        int_width: IntWidth,
+    ) -> Result<(), CompilerError> {
+        Self::check_stmt(init, symbol_table, warnings, int_width)?;
+        Self::check_condition(cond, symbol_table)?;
+        Self::check_stmt(step, symbol_table, warnings, int_width)?;
+        Self::check_block(body, symbol_table, warnings, int_width)
     }
 
-    #[test]
-    fn test_assigning_float_to_int_var() {
-        check("int a = 0.5;\0").unwrap();
+    /// Checks the statements of a block in their own scope, so that their declarations don't leak out of it.
+    fn check_block(
+        body: &Ast,
+        symbol_table: &mut SymbolTable,
+        warnings: &mut Vec<CompilerWarning>,
+        int_width: IntWidth,
+    ) -> Result<(), CompilerError> {
+        symbol_table.push_scope();
+        let result = body
+            .iter()
+            .try_for_each(|stmt| Self::check_stmt(stmt, symbol_table, warnings, int_width).map(|_| ()));
+        symbol_table.pop_scope();
+        result
     }
 
-    #[test]
-    fn test_assigning_int_to_float_var() {
-        check("float a = 200;\0").unwrap();
+    fn check_expr_depth(stmt: &Stmt, limit: usize) -> Result<(), CompilerError> {
+        if let Stmt::For { init, step, .. } = stmt {
+            Self::check_expr_depth(init, limit)?;
+            Self::check_expr_depth(step, limit)?;
+        }
+
+        if let Some(expr) = Self::stmt_expr(stmt) {
+            let depth = expr.depth();
+            if depth > limit {
+                return Err(CompilerError::ExpressionDepthError {
+                    depth,
+                    limit,
+                    span: expr.span().clone(),
+                });
+            }
+        }
+        stmt.bodies()
+            .into_iter()
+            .flatten()
+            .try_for_each(|stmt| Self::check_expr_depth(stmt, limit))
     }
 
-    #[test]
-    fn test_arithm_binop_between_int_and_float() {
-        check("int a = 0.5 * -200;\0").unwrap();
-        check("float a = 0.5 * -200;\0").unwrap();
+    fn collect_used_identifiers(expr: &Expr, used: &mut HashSet<String>) {
+        match &expr.node {
+            ExprKind::Literal { .. } => (),
+            ExprKind::Identifier { name } => {
+                used.insert(name.clone());
+            }
+            ExprKind::BinOp { left, right, .. } => {
+                Self::collect_used_identifiers(left, used);
+                Self::collect_used_identifiers(right, used);
+            }
+            ExprKind::UnaryOp { expr, .. } | ExprKind::Assign { expr, .. } | ExprKind::TypeOf { expr } => {
+                Self::collect_used_identifiers(expr, used)
+            }
+            ExprKind::Call { args, .. } => args.iter().for_each(|arg| Self::collect_used_identifiers(arg, used)),
+        }
     }
 
-    #[test]
-    fn test_assigning_bool_to_int_and_float_var() {
-        let result = check("int a = 200 == 200;\0");
-        assert!(matches!(
-            result,
-            Err(CompilerError::TypeDeclarationError { .. })
-        ));
+    fn check_unused_variables(&mut self) {
+        let mut used = HashSet::new();
+        for_each_stmt(&self.ast, &mut |stmt| match stmt {
+            Stmt::Declare { expr, .. }
+            | Stmt::MutAssign { expr, .. }
+            | Stmt::Print { expr, .. }
+            | Stmt::EPrint { expr, .. }
+            | Stmt::Expr { expr, .. }
+            | Stmt::If { cond: expr, .. }
+            | Stmt::For { cond: expr, .. }
+            | Stmt::Return { expr: Some(expr), .. } => Self::collect_used_identifiers(expr, &mut used),
+            Stmt::Swap { left, right, .. } => {
+                used.insert(left.clone());
+                used.insert(right.clone());
+            }
+            Stmt::Block { .. } | Stmt::FnDecl { .. } | Stmt::Return { expr: None, .. } => (),
+        });
 
-        let result = check("float b = !false;\0");
-        assert!(matches!(
-            result,
-            Err(CompilerError::TypeDeclarationError { .. })
-        ));
+        let warnings = &mut self.warnings;
+        for_each_stmt(&self.ast, &mut |stmt| {
+            if let Stmt::Declare { name, span, .. } = stmt
+                && !used.contains(name)
+            {
+                warnings.push(CompilerWarning::UnusedVariable {
+                    name: name.clone(),
+                    span: span.clone(),
+                });
+            }
+        });
     }
 
-    #[test]
-    fn test_assigning_int_and_float_to_bool_var() {
-        let result = check("bool b = 200 - 200;\0");
-        assert!(matches!(
-            result,
-            Err(CompilerError::TypeDeclarationError { .. })
+    /// Checks a single statement against the symbols declared by the previously checked ones.
+    pub fn check_statement(&mut self, stmt: &Stmt) -> Result<(), CompilerError> {
+        if let Some(limit) = self.max_expression_depth {
+            Self::check_expr_depth(stmt, limit)?;
+        }
+        match Self::check_stmt(stmt, &mut self.symbol_table, &mut self.warnings, self.int_width) {
+            Ok(expr_type) => self.last_expression_type = expr_type,
+            Err(err) => return Err(err),
+        }
+        Ok(())
+    }
+
+    pub fn check(&mut self) -> Result<(), CompilerError> {
+        self.check_all().map_err(|mut errors| errors.remove(0))
+    }
+
+    /// Checks all statements like `check`, but continues after a failing statement and returns
+    /// the errors of all statements at once.
+    pub fn check_all(&mut self) -> Result<(), Vec<CompilerError>> {
+        let ast = std::mem::take(&mut self.ast);
+        let mut errors = vec![];
+        for stmt in &ast {
+            if let Err(err) = self.check_statement(stmt) {
+                // The variable of a failed declaration is declared anyway, so that its uses don't report follow-up
+                // errors. A redeclaration keeps the original declaration.
+                let redeclared = matches!(err, CompilerError::RedeclarationError { .. });
+                errors.push(err);
+                if !redeclared
+                    && let Stmt::Declare {
+                        dtype,
+                        mutable,
+                        name,
+                        span,
+                        ..
+                    } = stmt
+                {
+                    self.symbol_table.insert(
+                        name.to_string(),
+                        Identifier {
+                            primitive: dtype.clone(),
+                            span: span.clone(),
+                            mutable: *mutable,
+                        },
+                    );
+                }
+            }
+        }
+        self.ast = ast;
+        if !errors.is_empty() {
+            return Err(errors);
+        }
+
+        if self.warn_unused_variables {
+            self.check_unused_variables();
+        }
+        if self.require_output {
+            match self.ast.last() {
+                Some(Stmt::Print { .. } | Stmt::EPrint { .. }) => (),
+                last_stmt => self.warnings.push(CompilerWarning::MissingOutput {
+                    span: last_stmt.map_or(Span { line: 1, col: 1, end_line: 1, end_col: 1, file_id: None }, |stmt| stmt.span().clone()),
+                }),
+            }
+        }
+        let allowed_warnings = &self.allowed_warnings;
+        self.warnings
+            .retain(|warning| !allowed_warnings.iter().any(|allowed| allowed == warning.code()));
+
+        Ok(())
+    }
+
+    /// Hands the AST back, so that it can be moved on to the next stage once it is checked.
+    pub fn into_ast(self) -> Ast {
+        self.ast
+    }
+
+    /// The global variables, the ones declared inside of blocks are gone once the block is checked.
+    pub fn get_symbol_table(&self) -> &HashMap<String, Identifier> {
+        self.symbol_table.globals()
+    }
+
+    /// Exports the symbol table as tab-separated values with a header row, sorted by variable name.
+    pub fn symbol_table_to_tsv(&self) -> String {
+        let symbol_table = self.get_symbol_table();
+        let mut names: Vec<&String> = symbol_table.keys().collect();
+        names.sort();
+
+        let mut tsv = "name\ttype\tmutable\tline\tcol\n".to_string();
+        for name in names {
+            let identifier = &symbol_table[name];
+            tsv.push_str(&format!(
+                "{}\t{:?}\t{}\t{}\t{}\n",
+                name, identifier.primitive, identifier.mutable, identifier.span.line, identifier.span.col
+            ));
+        }
+        tsv
+    }
+
+    /// Copy of the declared symbols, to roll back the declarations of a statement which failed.
+    pub(crate) fn snapshot(&self) -> SymbolTable {
+        self.symbol_table.clone()
+    }
+
+    pub(crate) fn restore(&mut self, snapshot: SymbolTable) {
+        self.symbol_table = snapshot;
+    }
+
+    /// Type of the expression of the last checked statement, if it was a declaration, print or expression.
+    pub fn last_expression_type(&self) -> Option<Primitive> {
+        self.last_expression_type.clone()
+    }
+
+    pub fn get_warnings(&self) -> &Vec<CompilerWarning> {
+        &self.warnings
+    }
+}
+
+/// Returns every use of an identifier which is not preceded by a declaration of it, in source order.
+/// Unlike `SemanticAnalyser::check` this does not stop at the first undeclared identifier.
+pub fn free_identifiers(ast: &Ast) -> Vec<(String, Span)> {
+    let mut free = vec![];
+    collect_free_identifiers(ast, &mut vec![HashSet::new()], &mut free);
+    free
+}
+
+/// `declared` holds the names declared in every enclosing scope, the innermost one is on top.
+fn collect_free_identifiers(ast: &[Stmt], declared: &mut Vec<HashSet<String>>, free: &mut Vec<(String, Span)>) {
+    for stmt in ast {
+        // The header of a for loop has its own scope, enclosing the one of the body.
+        if let Stmt::For { init, .. } = stmt {
+            declared.push(HashSet::new());
+            collect_free_identifiers(std::slice::from_ref(init), declared, free);
+        }
+
+        let mut uses = vec![];
+        match stmt {
+            Stmt::Declare { expr, .. }
+            | Stmt::Print { expr, .. }
+            | Stmt::EPrint { expr, .. }
+            | Stmt::Expr { expr, .. }
+            | Stmt::If { cond: expr, .. }
+            | Stmt::For { cond: expr, .. }
+            | Stmt::Return { expr: Some(expr), .. } => collect_identifier_uses(expr, &mut uses),
+            Stmt::MutAssign { name, expr, span, .. } => {
+                uses.push((name.clone(), span.clone()));
+                collect_identifier_uses(expr, &mut uses);
+            }
+            Stmt::Swap { left, right, span, .. } => {
+                uses.push((left.clone(), span.clone()));
+                uses.push((right.clone(), span.clone()));
+            }
+            Stmt::Block { .. } | Stmt::FnDecl { .. } | Stmt::Return { expr: None, .. } => (),
+        }
+        free.extend(
+            uses.into_iter()
+                .filter(|(name, _)| !declared.iter().any(|scope| scope.contains(name))),
+        );
+
+        if let Stmt::Declare { name, .. } = stmt
+            && let Some(scope) = declared.last_mut()
+        {
+            scope.insert(name.clone());
+        }
+        if let Stmt::For { step, .. } = stmt {
+            collect_free_identifiers(std::slice::from_ref(step), declared, free);
+        }
+        // The parameters of a function are declared in a scope enclosing its body.
+        if let Stmt::FnDecl { params, .. } = stmt {
+            declared.push(params.iter().map(|(name, _)| name.clone()).collect());
+        }
+        for body in stmt.bodies() {
+            declared.push(HashSet::new());
+            collect_free_identifiers(body, declared, free);
+            declared.pop();
+        }
+        if let Stmt::For { .. } | Stmt::FnDecl { .. } = stmt {
+            declared.pop();
+        }
+    }
+}
+
+/// Ranks the statements by the complexity of their expression, e.g. to find the lines most worth
+/// simplifying. The complexity is the number of expression nodes, ties are broken by the expression
+/// depth and then by source order. Statements without an expression, like `swap` or `return;`, are left out.
+pub fn complexity_report(ast: &Ast) -> Vec<(Span, usize)> {
+    let mut report = vec![];
+    for_each_stmt(ast, &mut |stmt| match stmt {
+        Stmt::Declare { expr, .. }
+        | Stmt::MutAssign { expr, .. }
+        | Stmt::Print { expr, .. }
+        | Stmt::EPrint { expr, .. }
+        | Stmt::Expr { expr, .. }
+        | Stmt::If { cond: expr, .. }
+        | Stmt::For { cond: expr, .. }
+        | Stmt::Return { expr: Some(expr), .. } => report.push((stmt.span().clone(), expr.node_count(), expr.depth())),
+        Stmt::Swap { .. } | Stmt::Block { .. } | Stmt::FnDecl { .. } | Stmt::Return { expr: None, .. } => (),
+    });
+
+    report.sort_by(|(_, left_count, left_depth), (_, right_count, right_depth)| {
+        (right_count, right_depth).cmp(&(left_count, left_depth))
+    });
+    report.into_iter().map(|(span, node_count, _)| (span, node_count)).collect()
+}
+
+/// Calls `f` for every statement in source order, including the ones nested in blocks, if statements and loops.
+fn for_each_stmt(ast: &[Stmt], f: &mut impl FnMut(&Stmt)) {
+    for stmt in ast {
+        f(stmt);
+        if let Stmt::For { init, step, .. } = stmt {
+            f(init);
+            f(step);
+        }
+        for body in stmt.bodies() {
+            for_each_stmt(body, f);
+        }
+    }
+}
+
+fn collect_identifier_uses(expr: &Expr, uses: &mut Vec<(String, Span)>) {
+    match &expr.node {
+        ExprKind::Literal { .. } => (),
+        ExprKind::Identifier { name } => uses.push((name.clone(), expr.span.clone())),
+        ExprKind::BinOp { left, right, .. } => {
+            collect_identifier_uses(left, uses);
+            collect_identifier_uses(right, uses);
+        }
+        ExprKind::UnaryOp { expr, .. } | ExprKind::TypeOf { expr } => collect_identifier_uses(expr, uses),
+        ExprKind::Call { args, .. } => args.iter().for_each(|arg| collect_identifier_uses(arg, uses)),
+        ExprKind::Assign { name, expr: value } => {
+            uses.push((name.clone(), expr.span.clone()));
+            collect_identifier_uses(value, uses);
+        }
+    }
+}
+
+/// Returns the candidate with the smallest edit distance to `name`, if it is at most `max_distance`
+/// away. Used for "did you mean" suggestions, ties are broken alphabetically to keep them stable.
+fn closest_name<'a>(name: &str, candidates: impl Iterator<Item = &'a str>, max_distance: usize) -> Option<String> {
+    candidates
+        .filter(|candidate| *candidate != name)
+        .map(|candidate| (edit_distance(name, candidate), candidate))
+        .filter(|(distance, _)| *distance <= max_distance)
+        .min()
+        .map(|(_, candidate)| candidate.to_string())
+}
+
+/// Levenshtein distance between two strings, counted in characters.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    for (i, a_char) in a.chars().enumerate() {
+        let mut current = vec![i + 1];
+        for (j, b_char) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(a_char != *b_char);
+            current.push(substitution.min(previous[j + 1] + 1).min(current[j] + 1));
+        }
+        previous = current;
+    }
+    previous[b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{lexer::Lexer, parser::Parser};
+
+    fn check(input: &str) -> Result<(), CompilerError> {
+        let mut lexer = Lexer::new(input);
+        lexer.tokenize()?;
+
+        let mut parser = Parser::new(lexer.get_tokens().to_vec());
+        parser.parse()?;
+        let mut parser = SemanticAnalyser::new(parser.into_tree());
+        parser.check()?;
+
+        Ok(())
+    }
+
+    fn new_analyser(input: &str) -> Result<SemanticAnalyser, CompilerError> {
+        let mut lexer = Lexer::new(input);
+        lexer.tokenize()?;
+
+        let mut parser = Parser::new(lexer.get_tokens().to_vec());
+        parser.parse()?;
+        Ok(SemanticAnalyser::new(parser.into_tree()))
+    }
+
+    fn check_warnings(input: &str) -> Result<Vec<CompilerWarning>, CompilerError> {
+        let mut analyser = new_analyser(input)?;
+        analyser.check()?;
+
+        Ok(analyser.warnings)
+    }
+
+    #[test]
+    fn test_correct_program_analysis() {
+        check(
+            "
+            int a = (1 * (2 + 3));
+            float b = -a / 5;
+            print(b + 3);
+            float c = 0.00001;
+            float d = a + c;
+            float e = a;
+            bool b1 = 2 == 2;
+            bool b2 = !(true && (2 > 0.5)) || b1 != (e <= 200);
+            print(true && b2);
+        ",
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_assigning_float_to_int_var() {
+        check("int a = 0.5;").unwrap();
+    }
+
+    #[test]
+    fn test_assigning_int_to_float_var() {
+        check("float a = 200;").unwrap();
+    }
+
+    #[test]
+    fn test_arithm_binop_between_int_and_float() {
+        check("int a = 0.5 * -200;").unwrap();
+        check("float a = 0.5 * -200;").unwrap();
+    }
+
+    #[test]
+    fn test_modulo_binop() {
+        check("int r = 10 % 3;").unwrap();
+        check("int a = 7;\nfloat b = a % 2 + 0.5;").unwrap();
+
+        // With a float operand the result is a float.
+        check("float x = 10.0 % 3;\nfloat y = 10 % 2.5;\nfloat z = 10.5 % 2.5;").unwrap();
+        let result = check("bool r = 10 % 2.5;");
+        assert!(matches!(
+            result,
+            Err(CompilerError::TypeDeclarationError { expected: Primitive::Bool, found: Primitive::Float, .. })
+        ));
+
+        let result = check("float x = 10.0 % true;");
+        assert!(matches!(
+            result,
+            Err(CompilerError::TypeBinOpError { op: BinOpKind::Mod, left: Primitive::Float, right: Primitive::Bool, .. })
+        ));
+    }
+
+    #[test]
+    fn test_assigning_bool_to_int_and_float_var() {
+        let result = check("int a = 200 == 200;");
+        assert!(matches!(
+            result,
+            Err(CompilerError::TypeDeclarationError { .. })
+        ));
+
+        let result = check("float b = !false;");
+        assert!(matches!(
+            result,
+            Err(CompilerError::TypeDeclarationError { .. })
+        ));
+    }
+
+    #[test]
+    fn test_assigning_int_and_float_to_bool_var() {
+        let result = check("bool b = 200 - 200;");
+        assert!(matches!(
+            result,
+            Err(CompilerError::TypeDeclarationError { .. })
         ));
 
-        let result = check("bool b = 0.02;\0");
+        let result = check("bool b = 0.02;");
         assert!(matches!(
             result,
             Err(CompilerError::TypeDeclarationError { .. })
@@ -302,7 +1321,6 @@ mod tests {
             bool b = !(10 == 11);
             bool c = 10 <= 11;
             bool d = 10 != 11;
-            \0
         ",
         )
         .unwrap();
@@ -314,7 +1332,6 @@ mod tests {
             "
             bool a = true == true;
             bool b = true != false;
-            \0
         ",
         )
         .unwrap();
@@ -327,7 +1344,6 @@ mod tests {
             mut int a = 1;
             int b = 2;
             a = (5 * b) / 3;
-            \0
         ",
         )
         .unwrap();
@@ -339,7 +1355,6 @@ mod tests {
             "
             int a = 1;
             a = 2;
-            \0
         ",
         );
         assert!(matches!(result, Err(CompilerError::MutabilityError { .. })));
@@ -347,30 +1362,30 @@ mod tests {
 
     #[test]
     fn test_boolean_binop_between_bool_and_int() {
-        let result = check("int a = 1 && true;\0");
+        let result = check("int a = 1 && true;");
         assert!(matches!(result, Err(CompilerError::TypeBinOpError { .. })));
 
-        let result = check("bool b = 1 != true;\0");
+        let result = check("bool b = 1 != true;");
         assert!(matches!(result, Err(CompilerError::TypeBinOpError { .. })));
 
-        let result = check("int a = false || 4;\0");
+        let result = check("int a = false || 4;");
         assert!(matches!(result, Err(CompilerError::TypeBinOpError { .. })));
     }
 
     #[test]
     fn test_cmp_binop_between_bool_and_int() {
-        let result = check("int a = 1 > true;\0");
+        let result = check("int a = 1 > true;");
         assert!(matches!(result, Err(CompilerError::TypeBinOpError { .. })));
 
-        let result = check("bool b = 1 != (true <= false);\0");
+        let result = check("bool b = 1 != (true <= false);");
         assert!(matches!(result, Err(CompilerError::TypeBinOpError { .. })));
     }
 
     #[test]
     fn test_arithm_unaryop() {
-        check("int a = -2 * +-+-+(-+-4.0);\0").unwrap();
+        check("int a = -2 * +-+-+(-+-4.0);").unwrap();
 
-        let result = check("bool a = -false;\0");
+        let result = check("bool a = -false;");
         assert!(matches!(
             result,
             Err(CompilerError::TypeUnaryOpError { .. })
@@ -379,12 +1394,874 @@ mod tests {
 
     #[test]
     fn test_boolean_unaryop() {
-        check("bool b = !true && !!(!!false);\0").unwrap();
+        check("bool b = !true && !!(!!false);").unwrap();
 
-        let result = check("int a = 200;int b = !a;\0");
+        let result = check("int a = 200;int b = !a;");
         assert!(matches!(
             result,
             Err(CompilerError::TypeUnaryOpError { .. })
         ));
     }
+
+    #[test]
+    fn test_discarded_comparison_warning() {
+        let warnings = check_warnings("int a = 1;\na == 2;").unwrap();
+        assert_eq!(
+            warnings,
+            vec![CompilerWarning::DiscardedComparison {
+                op: BinOpKind::Eq,
+                assign_hint: Some("a".to_string()),
+                span: Span { line: 2, col: 1, end_line: 2, end_col: 7, file_id: None },
+            }]
+        );
+
+        let warnings = check_warnings("int a = 1;\n2 < a;").unwrap();
+        assert!(matches!(
+            warnings.as_slice(),
+            [CompilerWarning::DiscardedComparison { assign_hint: None, .. }]
+        ));
+    }
+
+    #[test]
+    fn test_expression_statement_without_comparison() {
+        let warnings = check_warnings("int a = 1;\na + 2;").unwrap();
+        assert!(warnings.is_empty());
+
+        let result = check("b + 2;");
+        assert!(matches!(result, Err(CompilerError::NameError { .. })));
+    }
+
+    #[test]
+    fn test_variable_used_in_own_initializer() {
+        let err = check("int a = a + 1;").unwrap_err();
+        assert!(matches!(
+            &err,
+            CompilerError::NameError { name, self_reference: true, span, .. } if name == "a" && span.col == 9
+        ));
+        assert_eq!(err.message(), "Variable 'a' is used in its own initializer.");
+
+        let result = check("mut int a = a;");
+        assert!(matches!(result, Err(CompilerError::NameError { self_reference: true, .. })));
+
+        // Other unknown names in the initializer are plain name errors.
+        let result = check("int a = b;");
+        assert!(matches!(result, Err(CompilerError::NameError { self_reference: false, .. })));
+
+        // Shadowing a variable may still refer to the outer declaration.
+        check("int a = 1;\n{\n  int a = a + 1;\n}").unwrap();
+    }
+
+    #[test]
+    fn test_name_error_suggestion() {
+        let err = check("int count = 1;\nprint(cont);").unwrap_err();
+        assert!(matches!(
+            &err,
+            CompilerError::NameError { name, suggestion: Some(suggestion), .. } if name == "cont" && suggestion == "count"
+        ));
+        assert_eq!(err.message(), "Cannot find identifier 'cont'. Did you mean 'count'?");
+
+        // Variables of enclosing scopes are suggested too, and so are the builtin functions.
+        let result = check("mut int total = 1;\n{\n  totl = 2;\n}");
+        assert!(matches!(result, Err(CompilerError::NameError { suggestion: Some(suggestion), .. }) if suggestion == "total"));
+        let result = check("float a = sqt(2);");
+        assert!(matches!(result, Err(CompilerError::NameError { suggestion: Some(suggestion), .. }) if suggestion == "sqrt"));
+
+        let err = check("int count = 1;\nprint(total);").unwrap_err();
+        assert!(matches!(err, CompilerError::NameError { suggestion: None, .. }));
+        assert_eq!(err.message(), "Cannot find identifier 'total'.");
+        let result = check("int a = 1;\nprint(b);");
+        assert!(matches!(result, Err(CompilerError::NameError { suggestion: None, .. })));
+    }
+
+    #[test]
+    fn test_forward_reference() {
+        let result = check("int a = a;");
+        assert!(matches!(
+            result,
+            Err(CompilerError::NameError { name, self_reference: true, span, .. }) if name == "a" && span.line == 1 && span.col == 9
+        ));
+
+        let result = check("int b = c;\nint c = 1;");
+        assert!(matches!(
+            result,
+            Err(CompilerError::NameError { name, self_reference: false, suggestion: None, span }) if name == "c" && span.line == 1 && span.col == 9
+        ));
+
+        // A variable declared later in a block isn't visible to the statements before it either.
+        let result = check("{\n  print(d);\n  int d = 1;\n}");
+        assert!(matches!(
+            result,
+            Err(CompilerError::NameError { name, span, .. }) if name == "d" && span.line == 2 && span.col == 9
+        ));
+    }
+
+    #[test]
+    fn test_redeclaration() {
+        let err = check("int a = 1;\nprint(a);\nfloat a = 2.0;").unwrap_err();
+        let CompilerError::RedeclarationError {
+            name,
+            original_span,
+            span,
+        } = &err
+        else {
+            panic!("expected a redeclaration error, found {:?}", err);
+        };
+        assert_eq!(name, "a");
+        assert_eq!(original_span, &Span { line: 1, col: 1, end_line: 1, end_col: 4, file_id: None });
+        assert_eq!(span, &Span { line: 3, col: 1, end_line: 3, end_col: 6, file_id: None });
+        assert_eq!(
+            err.to_string(),
+            "NameError (line 3, position 1): 'a' is already declared in this scope, on line 1."
+        );
+
+        let result = check("{\n  int b = 1;\n  mut int b = 2;\n}");
+        assert!(matches!(result, Err(CompilerError::RedeclarationError { span: Span { line: 3, col: 3, .. }, .. })));
+
+        // Declaring the same name in sibling blocks or an inner block is allowed.
+        check("int c = 1;\n{\n  int c = 2;\n}\n{\n  int c = 3;\n}").unwrap();
+    }
+
+    #[test]
+    fn test_symbol_table_to_tsv() {
+        let mut analyser = new_analyser("mut float b = 1.5;\nint a = 2;\nprint(a);").unwrap();
+        analyser.check().unwrap();
+        assert_eq!(
+            analyser.symbol_table_to_tsv(),
+            "name\ttype\tmutable\tline\tcol\na\tInt\tfalse\t2\t1\nb\tFloat\ttrue\t1\t1\n"
+        );
+    }
+
+    #[test]
+    fn test_string_declaration() {
+        check("string s = \"hello\";\nprint(s);\nbool b = s == \"hi\";").unwrap();
+
+        let result = check("int a = \"1\";");
+        assert!(matches!(
+            result,
+            Err(CompilerError::TypeDeclarationError { expected: Primitive::Int, found: Primitive::Str, .. })
+        ));
+
+        let result = check("string s = \"a\";\nprint(s + 1);");
+        assert!(matches!(result, Err(CompilerError::TypeBinOpError { .. })));
+    }
+
+    #[test]
+    fn test_constant_division_by_zero() {
+        let result = check("int a = 5 / 0;");
+        assert!(matches!(
+            result,
+            Err(CompilerError::DivisionByZero { span }) if span == Span { line: 1, col: 9, end_line: 1, end_col: 14, file_id: None }
+        ));
+
+        let err = check("int b = 5 / (2 - 2);").unwrap_err();
+        assert!(matches!(err, CompilerError::DivisionByZero { .. }));
+        assert_eq!(err.to_string(), "ZeroDivisionError (line 1, position 9): Division by zero.");
+
+        let result = check("print(7 % (1 - 1));");
+        assert!(matches!(result, Err(CompilerError::DivisionByZero { .. })));
+
+        check("int a = 0;\nint b = 5;\nprint(b / a);\nprint(5 / a);\nprint(5 / (2 - 1));\nprint(5.0 / 0.0);").unwrap();
+    }
+
+    #[test]
+    fn test_comma_separated_declaration() {
+        check("int a = 1, b = a + 1;\nprint(b);").unwrap();
+
+        let result = check("int a = 1, b = true;");
+        assert!(matches!(
+            result,
+            Err(CompilerError::TypeDeclarationError { expected: Primitive::Int, found: Primitive::Bool, .. })
+        ));
+
+        let result = check("int a = b, b = 1;");
+        assert!(matches!(result, Err(CompilerError::NameError { name, .. }) if name == "b"));
+
+        let result = check("int a = 1, a = 2;");
+        assert!(matches!(result, Err(CompilerError::RedeclarationError { name, .. }) if name == "a"));
+    }
+
+    #[test]
+    fn test_char_declaration() {
+        check("char c = 'a';\nprint(c);\nbool b = c == '\\n' || c != 'b';").unwrap();
+
+        let result = check("char c = \"a\";");
+        assert!(matches!(
+            result,
+            Err(CompilerError::TypeDeclarationError { expected: Primitive::Char, found: Primitive::Str, .. })
+        ));
+
+        for source in ["char c = 'a';\nprint(c + 'b');", "char c = 'a';\nprint(c < 'b');", "print('a' == \"a\");"] {
+            let result = check(source);
+            assert!(matches!(result, Err(CompilerError::TypeBinOpError { .. })), "{}", source);
+        }
+    }
+
+    #[test]
+    fn test_char_conversion() {
+        check("char c = 'A';\nint code = int(c);\nchar d = char(code + 1);\nbool b = char(int(c)) == c;").unwrap();
+
+        let result = check("int code = int(1.5);");
+        assert!(matches!(
+            result,
+            Err(CompilerError::TypeDeclarationError { expected: Primitive::Char, found: Primitive::Float, span }) if span.col == 16
+        ));
+        let result = check("char c = char('A');");
+        assert!(matches!(
+            result,
+            Err(CompilerError::TypeDeclarationError { expected: Primitive::Int, found: Primitive::Char, .. })
+        ));
+        let result = check("char c = 'A';\nchar d = int(c);");
+        assert!(matches!(
+            result,
+            Err(CompilerError::TypeDeclarationError { expected: Primitive::Char, found: Primitive::Int, .. })
+        ));
+        let result = check("int a = int();");
+        assert!(matches!(
+            result,
+            Err(CompilerError::ArgumentCountError { name, expected: 1, found: 0, .. }) if name == "int"
+        ));
+    }
+
+    #[test]
+    fn test_complexity_report() {
+        let mut lexer = Lexer::new("int a = 1;\nprint(-(a * (2 + (3 - a))));\n{\n  int b = a + 1;\n  int c = -a - 1;\n}\nswap(a, b);");
+        lexer.tokenize().unwrap();
+        let mut parser = Parser::new(lexer.get_tokens().to_vec());
+        parser.parse().unwrap();
+
+        let report: Vec<(usize, usize)> = complexity_report(parser.get_tree())
+            .into_iter()
+            .map(|(span, node_count)| (span.line, node_count))
+            .collect();
+        // Lines 4 and 5 have the same number of nodes, but line 5 is nested deeper.
+        assert_eq!(report, vec![(2, 8), (5, 4), (4, 3), (1, 1)]);
+    }
+
+    #[test]
+    fn test_free_identifiers() {
+        let mut lexer = Lexer::new("int a = b + 1;\nprint(a * c);\nint b = 2;\nprint(b);");
+        lexer.tokenize().unwrap();
+        let mut parser = Parser::new(lexer.get_tokens().to_vec());
+        parser.parse().unwrap();
+
+        assert_eq!(
+            free_identifiers(parser.get_tree()),
+            vec![
+                ("b".to_string(), Span { line: 1, col: 9, end_line: 1, end_col: 10, file_id: None }),
+                ("c".to_string(), Span { line: 2, col: 11, end_line: 2, end_col: 12, file_id: None }),
+            ]
+        );
+
+        // Variables declared in a block are free again after it.
+        let mut lexer = Lexer::new("{ int d = 1; print(d); }\nprint(d);");
+        lexer.tokenize().unwrap();
+        let mut parser = Parser::new(lexer.get_tokens().to_vec());
+        parser.parse().unwrap();
+        assert_eq!(free_identifiers(parser.get_tree()), vec![("d".to_string(), Span { line: 2, col: 7, end_line: 2, end_col: 8, file_id: None })]);
+
+        let mut lexer = Lexer::new("for (mut int i = 0; i < n; i = i + 1) {}\nprint(i);");
+        lexer.tokenize().unwrap();
+        let mut parser = Parser::new(lexer.get_tokens().to_vec());
+        parser.parse().unwrap();
+        assert_eq!(
+            free_identifiers(parser.get_tree()),
+            vec![
+                ("n".to_string(), Span { line: 1, col: 25, end_line: 1, end_col: 26, file_id: None }),
+                ("i".to_string(), Span { line: 2, col: 7, end_line: 2, end_col: 8, file_id: None }),
+            ]
+        );
+    }
+
+    fn check_assignment_expressions(input: &str) -> Result<(), CompilerError> {
+        let mut lexer = Lexer::new(input);
+        lexer.tokenize()?;
+
+        let mut parser = Parser::new(lexer.get_tokens().to_vec());
+        parser.assignment_expressions(true);
+        parser.parse()?;
+        SemanticAnalyser::new(parser.into_tree()).check()
+    }
+
+    #[test]
+    fn test_assignment_expression() {
+        check_assignment_expressions("mut int a = 0;\nmut int b = 0;\na = b = 5;\nbool c = (b = 2) > 1;").unwrap();
+
+        // The assignment has the type of the variable, not of the assigned value.
+        check_assignment_expressions("mut int a = 0;\nprint((a = 2.5) + 1);").unwrap();
+        let result = check_assignment_expressions("mut int a = 0;\nbool b = (a = 1);");
+        assert!(matches!(
+            result,
+            Err(CompilerError::TypeDeclarationError { expected: Primitive::Bool, found: Primitive::Int, .. })
+        ));
+
+        let result = check_assignment_expressions("int a = 0;\nmut int b = 0;\nb = a = 1;");
+        assert!(matches!(result, Err(CompilerError::MutabilityError { name, .. }) if name == "a"));
+
+        let result = check_assignment_expressions("mut int b = 0;\nb = c = 1;");
+        assert!(matches!(result, Err(CompilerError::NameError { name, .. }) if name == "c"));
+
+        check_assignment_expressions("mut int j = 0;\nfor (mut int i = 0; i < 9; i = j = i + 1) {}").unwrap();
+        let result = check_assignment_expressions("int j = 0;\nfor (mut int i = 0; i < 9; i = j = i + 1) {}");
+        assert!(matches!(result, Err(CompilerError::MutabilityError { name, .. }) if name == "j"));
+    }
+
+    #[test]
+    fn test_chained_assignment() {
+        // Chained assignment statements are part of the default language, with the same checks.
+        check("mut int a = 0;\nmut int b = 0;\na = b = 5;").unwrap();
+        let result = check("int a = 0;\nmut int b = 0;\nb = a = 1;");
+        assert!(matches!(result, Err(CompilerError::MutabilityError { name, .. }) if name == "a"));
+        let result = check("mut bool a = false;\nmut int b = 0;\na = b = 1;");
+        assert!(matches!(
+            result,
+            Err(CompilerError::TypeDeclarationError { expected: Primitive::Bool, found: Primitive::Int, .. })
+        ));
+
+        check("mut int j = 0;\nfor (mut int i = 0; i < 9; i = j = i + 1) {}").unwrap();
+        let result = check("int j = 0;\nfor (mut int i = 0; i < 9; i = j = i + 1) {}");
+        assert!(matches!(result, Err(CompilerError::MutabilityError { name, .. }) if name == "j"));
+    }
+
+    #[test]
+    fn test_math_builtins() {
+        check("int a = 2;\nfloat b = pow(a, 0.5);\nfloat c = sqrt(16) + sqrt(2.25);\nfloat d = pow(sqrt(b), c);").unwrap();
+
+        // Both functions always return float.
+        let result = check("bool a = sqrt(16);");
+        assert!(matches!(
+            result,
+            Err(CompilerError::TypeDeclarationError { expected: Primitive::Bool, found: Primitive::Float, .. })
+        ));
+
+        let result = check("float a = sqrt(true);");
+        assert!(matches!(
+            result,
+            Err(CompilerError::TypeDeclarationError { expected: Primitive::Float, found: Primitive::Bool, span }) if span.col == 16
+        ));
+        let result = check("float a = pow(2, 1 > 0);");
+        assert!(matches!(
+            result,
+            Err(CompilerError::TypeDeclarationError { found: Primitive::Bool, span, .. }) if span.col == 18
+        ));
+
+        let result = check("float a = pow(2);");
+        assert!(matches!(
+            result,
+            Err(CompilerError::ArgumentCountError { name, expected: 2, found: 1, .. }) if name == "pow"
+        ));
+        let result = check("float a = cbrt(8);");
+        assert!(matches!(result, Err(CompilerError::NameError { name, .. }) if name == "cbrt"));
+    }
+
+    #[test]
+    fn test_typeof_yields_string() {
+        check("string t = typeof(1 + 2.0);\nstring u = typeof(true);\nstring v = typeof(t);").unwrap();
+
+        let result = check("int a = typeof(1);");
+        assert!(matches!(
+            result,
+            Err(CompilerError::TypeDeclarationError { expected: Primitive::Int, found: Primitive::Str, .. })
+        ));
+
+        // The argument is still checked.
+        let result = check("string t = typeof(true + 1);");
+        assert!(matches!(result, Err(CompilerError::TypeBinOpError { .. })));
+        let result = check("string t = typeof(b);");
+        assert!(matches!(result, Err(CompilerError::NameError { .. })));
+    }
+
+    #[test]
+    fn test_multiple_statements_on_one_line() {
+        check("int a = 1; int b = a + 2; print(a * b);").unwrap();
+
+        let result = check("int a = 1; bool b = a + 2;");
+        assert!(matches!(
+            result,
+            Err(CompilerError::TypeDeclarationError { span, .. }) if span.line == 1 && span.col == 12
+        ));
+
+        let result = check("int a = 1; int b = a + true;");
+        assert!(matches!(
+            result,
+            Err(CompilerError::TypeBinOpError { span, .. }) if span.line == 1 && span.col == 20
+        ));
+    }
+
+    #[test]
+    fn test_unused_variable_warning() {
+        let mut analyser = new_analyser("int a = 1;\nint b = a;\nmut int c = 2;\nc = 3;").unwrap();
+        analyser.warn_unused_variables(true);
+        analyser.check().unwrap();
+        assert_eq!(
+            analyser.get_warnings(),
+            &vec![
+                CompilerWarning::UnusedVariable {
+                    name: "b".to_string(),
+                    span: Span { line: 2, col: 1, end_line: 2, end_col: 4, file_id: None },
+                },
+                CompilerWarning::UnusedVariable {
+                    name: "c".to_string(),
+                    span: Span { line: 3, col: 1, end_line: 3, end_col: 4, file_id: None },
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_allow_warning_code() {
+        let mut analyser = new_analyser("int a = 1;\na == 1;").unwrap();
+        analyser.warn_unused_variables(true);
+        analyser.allow("unused-variable");
+        analyser.allow("unused-variable");
+        analyser.check().unwrap();
+
+        assert_eq!(analyser.get_allowed_warnings(), &vec!["unused-variable".to_string()]);
+        assert!(matches!(
+            analyser.get_warnings().as_slice(),
+            [CompilerWarning::DiscardedComparison { .. }]
+        ));
+
+        let mut analyser = new_analyser("int unused = 1;").unwrap();
+        analyser.warn_unused_variables(true);
+        analyser.allow("unused-variable");
+        analyser.check().unwrap();
+        assert!(analyser.get_warnings().is_empty());
+    }
+
+    #[test]
+    fn test_print_next_to_print_like_identifiers() {
+        check("int printer = 1;\nint print_ = 2;\nprint(printer + print_);").unwrap();
+    }
+
+    #[test]
+    fn test_last_expression_type() {
+        let mut analyser = new_analyser("int a = 1 + 2;").unwrap();
+        analyser.check().unwrap();
+        assert_eq!(analyser.last_expression_type(), Some(Primitive::Int));
+
+        let mut analyser = new_analyser("int a = 1;\nprint(a / 2);").unwrap();
+        analyser.check().unwrap();
+        assert_eq!(analyser.last_expression_type(), Some(Primitive::Float));
+
+        let mut analyser = new_analyser("mut bool b = true;\nb = false;").unwrap();
+        analyser.check().unwrap();
+        assert_eq!(analyser.last_expression_type(), None);
+    }
+
+    #[test]
+    fn test_max_expression_depth() {
+        let mut analyser = new_analyser("int a = -(1 + (2 * 3));").unwrap();
+        analyser.max_expression_depth(Some(4));
+        analyser.check().unwrap();
+
+        let mut analyser = new_analyser("int a = -(1 + (2 * 3));").unwrap();
+        analyser.max_expression_depth(Some(3));
+        assert!(matches!(
+            analyser.check(),
+            Err(CompilerError::ExpressionDepthError { depth: 4, limit: 3, span }) if span.col == 9
+        ));
+    }
+
+    #[test]
+    fn test_swap() {
+        check("mut int a = 1;\nmut int b = 2;\nswap(a, b);").unwrap();
+
+        let result = check("mut int a = 1;\nmut float b = 2;\nswap(a, b);");
+        assert!(matches!(
+            result,
+            Err(CompilerError::TypeDeclarationError { expected: Primitive::Int, found: Primitive::Float, .. })
+        ));
+
+        let result = check("mut int a = 1;\nint b = 2;\nswap(a, b);");
+        assert!(matches!(result, Err(CompilerError::MutabilityError { name, .. }) if name == "b"));
+
+        let result = check("mut int a = 1;\nswap(a, c);");
+        assert!(matches!(result, Err(CompilerError::NameError { name, .. }) if name == "c"));
+    }
+
+    #[test]
+    fn test_if_statement() {
+        check("int a = 1;\nif (a > 0) {\n  if (a == 1) { print(a); }\n} else if (a < -1) {\n  print(-a);\n} else {\n  print(0);\n}").unwrap();
+
+        let result = check("int a = 1;\nif (a) {\n  print(a);\n}");
+        assert!(matches!(
+            result,
+            Err(CompilerError::TypeDeclarationError { expected: Primitive::Bool, found: Primitive::Int, span }) if span == Span { line: 2, col: 5, end_line: 2, end_col: 6, file_id: None }
+        ));
+
+        // Nested statements are checked as well.
+        let result = check("if (true) {\n  if (false) {} else { print(x); }\n}");
+        assert!(matches!(result, Err(CompilerError::NameError { name, .. }) if name == "x"));
+        let result = check("if (true) {} else if (1.5) {}");
+        assert!(matches!(
+            result,
+            Err(CompilerError::TypeDeclarationError { expected: Primitive::Bool, found: Primitive::Float, .. })
+        ));
+    }
+
+    #[test]
+    fn test_block_scope_leakage() {
+        let result = check("{\n  int a = 1;\n  print(a);\n}\nprint(a);");
+        assert!(matches!(
+            result,
+            Err(CompilerError::NameError { name, span, .. }) if name == "a" && span == Span { line: 5, col: 7, end_line: 5, end_col: 8, file_id: None }
+        ));
+
+        let result = check("if (true) {\n  int a = 1;\n} else {\n  print(a);\n}");
+        assert!(matches!(result, Err(CompilerError::NameError { name, .. }) if name == "a"));
+    }
+
+    #[test]
+    fn test_block_scope_shadowing() {
+        // The inner declaration shadows the outer one until the block ends.
+        check("int a = 1;\n{\n  bool a = true;\n  bool b = a;\n}\nint c = a;").unwrap();
+
+        // Outer variables are visible and can be assigned inside of a block.
+        check("mut int a = 1;\n{\n  { a = a + 1; }\n}\nprint(a);").unwrap();
+
+        let mut lexer = Lexer::new("int a = 1;\n{\n  int b = a;\n}");
+        lexer.tokenize().unwrap();
+        let mut parser = Parser::new(lexer.get_tokens().to_vec());
+        parser.parse().unwrap();
+        let mut analyser = SemanticAnalyser::new(parser.into_tree());
+        analyser.check().unwrap();
+        assert!(analyser.get_symbol_table().contains_key("a"));
+        assert!(!analyser.get_symbol_table().contains_key("b"));
+    }
+
+    #[test]
+    fn test_for_loop() {
+        check("mut int sum = 0;\nfor (mut int i = 0; i < 10; i = i + 1) {\n  sum = sum + i;\n}\nprint(sum);").unwrap();
+
+        let result = check("for (mut int i = 0; i; i = i + 1) {}");
+        assert!(matches!(
+            result,
+            Err(CompilerError::TypeDeclarationError { expected: Primitive::Bool, found: Primitive::Int, span }) if span.col == 21
+        ));
+
+        // The loop variable is not visible after the loop.
+        let result = check("for (mut int i = 0; i < 10; i = i + 1) {}\nprint(i);");
+        assert!(matches!(result, Err(CompilerError::NameError { name, span, .. }) if name == "i" && span.line == 2));
+
+        let result = check("for (int i = 0; i < 10; i = i + 1) {}");
+        assert!(matches!(result, Err(CompilerError::MutabilityError { name, .. }) if name == "i"));
+    }
+
+    #[test]
+    fn test_function_declaration() {
+        check("int offset = 1;\nfn add(int x, int y) -> int {\n  return x + y + offset;\n}\nint z = add(1, 2);\nfloat w = add(z, 2);").unwrap();
+
+        // The function is known inside of its own body, the parameters are converted like in an assignment.
+        check("fn fact(float n) -> float {\n  if (n <= 1) { return 1; } else { return n * fact(n - 1); }\n}\nprint(fact(5));").unwrap();
+
+        let result = check("fn add(int x, int y) -> int {\n  return x + y;\n}\nint z = add(1);");
+        assert!(matches!(
+            result,
+            Err(CompilerError::ArgumentCountError { name, expected: 2, found: 1, span }) if name == "add" && span.line == 4
+        ));
+
+        let result = check("fn add(int x, int y) -> int {\n  return x + y;\n}\nint z = add(1, true);");
+        assert!(matches!(
+            result,
+            Err(CompilerError::TypeDeclarationError { expected: Primitive::Int, found: Primitive::Bool, span }) if span.line == 4 && span.col == 16
+        ));
+
+        let result = check("fn add(int x, int y) -> int {\n  return x + y;\n}\nbool z = add(1, 2);");
+        assert!(matches!(
+            result,
+            Err(CompilerError::TypeDeclarationError { expected: Primitive::Bool, found: Primitive::Int, .. })
+        ));
+
+        let result = check("fn add(int x, int y) -> int {\n  return x + y;\n}\nint z = ad(1, 2);");
+        assert!(matches!(
+            result,
+            Err(CompilerError::NameError { name, suggestion: Some(suggestion), .. }) if name == "ad" && suggestion == "add"
+        ));
+
+        // Calls must follow the declaration of the function.
+        let result = check("int z = add(1, 2);\nfn add(int x, int y) -> int {\n  return x + y;\n}");
+        assert!(matches!(result, Err(CompilerError::NameError { name, .. }) if name == "add"));
+    }
+
+    #[test]
+    fn test_invalid_function_declaration() {
+        let result = check("fn half(int x) -> int {\n  return true;\n}");
+        assert!(matches!(
+            result,
+            Err(CompilerError::TypeDeclarationError { expected: Primitive::Int, found: Primitive::Bool, span }) if span.line == 2 && span.col == 10
+        ));
+
+        let result = check("fn sign(int x) -> int {\n  if (x < 0) { return -1; }\n}");
+        assert!(matches!(
+            result,
+            Err(CompilerError::MissingReturn { name, span }) if name == "sign" && span.line == 1
+        ));
+
+        let result = check("int a = 1;\nreturn a;");
+        assert!(matches!(result, Err(CompilerError::ReturnOutsideFunction { span }) if span.line == 2));
+
+        let result = check("fn f(int x, float x) -> int {\n  return 1;\n}");
+        assert!(matches!(result, Err(CompilerError::RedeclarationError { name, .. }) if name == "x"));
+
+        let result = check("fn f() -> int {\n  return 1;\n}\nfn f() -> int {\n  return 2;\n}");
+        assert!(matches!(
+            result,
+            Err(CompilerError::RedeclarationError { name, original_span, span }) if name == "f" && original_span.line == 1 && span.line == 4
+        ));
+
+        // The local variables of the caller are not visible in the body.
+        let result = check("{\n  int local = 1;\n}\nfn f() -> int {\n  return local;\n}");
+        assert!(matches!(result, Err(CompilerError::NameError { name, .. }) if name == "local"));
+
+        let result = check("fn f(Colour c) -> int {\n  return 1;\n}");
+        assert!(matches!(result, Err(CompilerError::UnknownType { name, .. }) if name == "Colour"));
+    }
+
+    #[test]
+    fn test_return_statement() {
+        // Returned ints and floats are converted to the return type.
+        check("fn half(int x) -> float {\n  return x / 2;\n}\nfn floor(float x) -> int {\n  return x;\n}").unwrap();
+        check("fn log(int x) {\n  if (x < 0) { return; }\n  print(x);\n}\nlog(1);").unwrap();
+
+        let result = check("fn name(int x) -> string {\n  return x + 1;\n}");
+        assert!(matches!(
+            result,
+            Err(CompilerError::TypeDeclarationError { expected: Primitive::Str, found: Primitive::Int, span }) if span.line == 2 && span.col == 10
+        ));
+
+        let result = check("fn f(int x) -> int {\n  if (x < 0) { return; }\n  return x;\n}");
+        assert!(matches!(
+            result,
+            Err(CompilerError::MissingReturn { name, span }) if name == "f" && span.line == 2 && span.col == 16
+        ));
+
+        let result = check("fn log(int x) {\n  return x;\n}");
+        assert!(matches!(
+            result,
+            Err(CompilerError::UnitFunctionValue { name, span }) if name == "log" && span.line == 2 && span.col == 10
+        ));
+
+        let result = check("fn log(int x) {\n  print(x);\n}\nint a = log(1);");
+        assert!(matches!(
+            result,
+            Err(CompilerError::UnitFunctionValue { name, span }) if name == "log" && span.line == 4
+        ));
+
+        let result = check("fn log(int x) {\n  print(x);\n}\nlog(true);");
+        assert!(matches!(result, Err(CompilerError::TypeDeclarationError { found: Primitive::Bool, .. })));
+
+        let result = check("return;");
+        assert!(matches!(result, Err(CompilerError::ReturnOutsideFunction { .. })));
+    }
+
+    #[test]
+    fn test_compound_assign() {
+        check("mut int a = 1;\na += 2;\na *= a;\nmut float b = 1.5;\nb /= 2;").unwrap();
+
+        let result = check("mut int a = 1;\na += true;");
+        assert!(matches!(
+            result,
+            Err(CompilerError::TypeBinOpError { op: BinOpKind::Add, left: Primitive::Int, right: Primitive::Bool, .. })
+        ));
+
+        let result = check("int a = 1;\na += 1;");
+        assert!(matches!(result, Err(CompilerError::MutabilityError { name, .. }) if name == "a"));
+    }
+
+    #[test]
+    fn test_unknown_type() {
+        let result = check("int a = 1;\nColor c = a;");
+        assert!(matches!(
+            result,
+            Err(CompilerError::UnknownType { name, suggestion: None, span }) if name == "Color" && span.line == 2 && span.col == 1
+        ));
+
+        let err = check("strng x = 1;").unwrap_err();
+        assert!(matches!(
+            &err,
+            CompilerError::UnknownType { name, suggestion: Some(suggestion), .. } if name == "strng" && suggestion == "string"
+        ));
+        assert_eq!(err.message(), "Cannot find type 'strng', did you mean 'string'?");
+
+        let result = check("mut flot y = 1.5;");
+        assert!(matches!(result, Err(CompilerError::UnknownType { suggestion: Some(suggestion), .. }) if suggestion == "float"));
+    }
+
+    #[test]
+    fn test_literal_out_of_range() {
+        check("int a = 9223372036854775807;\nint b = -9223372036854775808;\nint c = 100000.5;").unwrap();
+
+        let result = check("int a = 9223372036854775808;");
+        assert!(matches!(
+            result,
+            Err(CompilerError::LiteralOutOfRange { primitive: Primitive::Int, span, .. }) if span.col == 9
+        ));
+
+        // 2^63 is the first float out of range, even though `i64::MAX as f64` rounds up to it.
+        check("int a = 9223372036854774784.0;\nint b = -9223372036854775808.0;").unwrap();
+        let result = check("int a = 9223372036854775808.0;");
+        assert!(matches!(
+            result,
+            Err(CompilerError::LiteralOutOfRange { primitive: Primitive::Int, span, .. }) if span.col == 9
+        ));
+
+        let result = check("int a = -30000000000000000000000000000000000000000.0;");
+        assert!(matches!(
+            result,
+            Err(CompilerError::LiteralOutOfRange { value, span, .. }) if value.starts_with("3000") && span.col == 10
+        ));
+    }
+
+    #[test]
+    fn test_check_all_collects_errors() {
+        let mut analyser = new_analyser("int a = true;\nfloat b = 1;\nbool c = b + 1;\nprint(a + c);").unwrap();
+        let errors = analyser.check_all().unwrap_err();
+        assert_eq!(errors.len(), 3);
+        assert!(matches!(
+            &errors[0],
+            CompilerError::TypeDeclarationError { expected: Primitive::Int, found: Primitive::Bool, span } if span.line == 1
+        ));
+        assert!(matches!(
+            &errors[1],
+            CompilerError::TypeDeclarationError { expected: Primitive::Bool, found: Primitive::Float, span } if span.line == 3
+        ));
+        assert!(matches!(&errors[2], CompilerError::TypeBinOpError { span, .. } if span.line == 4));
+
+        let mut analyser = new_analyser("int a = 1.5 + true;\nprint(a * 2);").unwrap();
+        let errors = analyser.check_all().unwrap_err();
+        assert_eq!(errors.len(), 1);
+    }
+
+    #[test]
+    fn test_power_operator() {
+        let mut analyser = new_analyser("int a = 2 ^ 3 ^ 2;").unwrap();
+        analyser.check().unwrap();
+        assert_eq!(analyser.last_expression_type(), Some(Primitive::Int));
+
+        let mut analyser = new_analyser("float b = 2 ^ 0.5;").unwrap();
+        analyser.check().unwrap();
+        assert_eq!(analyser.last_expression_type(), Some(Primitive::Float));
+
+        let result = check("float b = 2.5 ^ 2;\nint c = b ^ 2;\nbool d = true ^ 2;");
+        assert!(matches!(
+            result,
+            Err(CompilerError::TypeBinOpError { op: BinOpKind::Pow, left: Primitive::Bool, right: Primitive::Int, .. })
+        ));
+    }
+
+    #[test]
+    fn test_bitwise_operators() {
+        let mut analyser = new_analyser("int a = 0xF0 >> 4;\nint b = ~a & 3 | a << 1;").unwrap();
+        analyser.check().unwrap();
+        assert_eq!(analyser.last_expression_type(), Some(Primitive::Int));
+
+        let result = check("float f = 1.5;\nint a = f & 1;");
+        assert!(matches!(
+            result,
+            Err(CompilerError::TypeBinOpError { op: BinOpKind::BitAnd, left: Primitive::Float, right: Primitive::Int, .. })
+        ));
+
+        let result = check("bool b = true | false;");
+        assert!(matches!(result, Err(CompilerError::TypeBinOpError { op: BinOpKind::BitOr, .. })));
+
+        let result = check("int a = 1 << 2.0;");
+        assert!(matches!(result, Err(CompilerError::TypeBinOpError { op: BinOpKind::Shl, .. })));
+
+        let result = check("bool b = ~true;");
+        assert!(matches!(
+            result,
+            Err(CompilerError::TypeUnaryOpError { op: UnaryOpKind::BitNot, operand: Primitive::Bool, .. })
+        ));
+    }
+
+    #[test]
+    fn test_int_division_to_int_warning() {
+        let warnings = check_warnings("int d = 10 / 10;").unwrap();
+        assert!(matches!(
+            warnings.as_slice(),
+            [CompilerWarning::IntDivisionToInt { name, .. }] if name == "d"
+        ));
+
+        let warnings = check_warnings("float d = 10 / 10;").unwrap();
+        assert!(warnings.is_empty());
+
+        let warnings = check_warnings("mut int d = 1;\nfloat e = 2.0;\nd = d / e;\nd = d / 2;").unwrap();
+        assert!(matches!(
+            warnings.as_slice(),
+            [CompilerWarning::IntDivisionToInt { span: Span { line: 4, .. }, .. }]
+        ));
+    }
+
+    #[test]
+    fn test_int_width() {
+        let input = "int a = 3000000000;\nint b = -2147483648;";
+        let mut analyser = new_analyser(input).unwrap();
+        analyser.int_width(IntWidth::I64);
+        analyser.check().unwrap();
+
+        let mut analyser = new_analyser(input).unwrap();
+        analyser.int_width(IntWidth::I32);
+        assert!(matches!(
+            analyser.check(),
+            Err(CompilerError::LiteralOutOfRange { value, primitive: Primitive::Int, span }) if value == "3000000000" && span.line == 1
+        ));
+
+        let mut analyser = new_analyser("int b = -2147483648;\nint c = 2147483647.5;\nint d = 2147483648.0;").unwrap();
+        analyser.int_width(IntWidth::I32);
+        assert!(matches!(
+            analyser.check(),
+            Err(CompilerError::LiteralOutOfRange { span, .. }) if span.line == 3
+        ));
+
+        // Every int literal is bounded, not only the initializer of a declaration.
+        for input in [
+            "mut int a = 0;\na = 3000000000;",
+            "print(3000000000);",
+            "int a = 1;\nprint(a + 3000000000);",
+            "fn f(int x) -> int { return x; }\nprint(f(-2147483649));",
+            "for (mut int i = 0; i < 3000000000; i += 1) { print(i); }",
+        ] {
+            let mut analyser = new_analyser(input).unwrap();
+            analyser.int_width(IntWidth::I32);
+            assert!(
+                matches!(analyser.check(), Err(CompilerError::LiteralOutOfRange { primitive: Primitive::Int, .. })),
+                "{}",
+                input
+            );
+            new_analyser(input).unwrap().check().unwrap();
+        }
+        let mut analyser = new_analyser("print(-2147483648);\nfloat f = 3000000000.0;").unwrap();
+        analyser.int_width(IntWidth::I32);
+        analyser.check().unwrap();
+    }
+
+    #[test]
+    fn test_eprint() {
+        check("float a = 1.5;\neprint(a * 2);").unwrap();
+
+        let result = check("eprint(-true);");
+        assert!(matches!(result, Err(CompilerError::TypeUnaryOpError { .. })));
+    }
+
+    #[test]
+    fn test_require_output() {
+        let mut analyser = new_analyser("int a = 1;\nprint(a);").unwrap();
+        analyser.require_output(true);
+        analyser.check().unwrap();
+        assert!(analyser.get_warnings().is_empty());
+
+        let mut analyser = new_analyser("print(1);\nint a = 1;").unwrap();
+        analyser.require_output(true);
+        analyser.check().unwrap();
+        assert_eq!(
+            analyser.get_warnings(),
+            &vec![CompilerWarning::MissingOutput {
+                span: Span { line: 2, col: 1, end_line: 2, end_col: 4, file_id: None }
+            }]
+        );
+
+        let mut analyser = new_analyser("print(1);\nint a = 1;").unwrap();
+        analyser.check().unwrap();
+        assert!(analyser.get_warnings().is_empty());
+    }
 }
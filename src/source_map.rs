@@ -0,0 +1,113 @@
+use crate::{diagnostic::Diagnostic, errors::CompilerError};
+
+#[derive(Debug, PartialEq)]
+pub struct SourceFile {
+    pub name: String,
+    pub source: String,
+}
+
+/// Table of the files of a multi-file build. Spans refer to their file by the id `add_file` returned,
+/// so an AST combined from several files still knows where each of its nodes comes from.
+#[derive(Debug, Default)]
+pub struct SourceMap {
+    files: Vec<SourceFile>,
+}
+
+impl SourceMap {
+    pub fn new() -> Self {
+        SourceMap::default()
+    }
+
+    /// Registers a file and returns its id, which is passed to `Lexer::file_id` when lexing it.
+    pub fn add_file(&mut self, name: &str, source: &str) -> u32 {
+        self.files.push(SourceFile {
+            name: name.to_string(),
+            source: source.to_string(),
+        });
+        (self.files.len() - 1) as u32
+    }
+
+    pub fn get_file(&self, file_id: u32) -> Option<&SourceFile> {
+        self.files.get(file_id as usize)
+    }
+
+    /// Renders the error with the name and offending line of the file its span points into. Errors
+    /// without a known file id are rendered like the `Display` output of their `Diagnostic`.
+    pub fn render(&self, error: &CompilerError) -> String {
+        let diagnostic = Diagnostic::from(error);
+        match error.span().file_id.and_then(|file_id| self.get_file(file_id)) {
+            Some(file) => diagnostic.render_in_file(Some(&file.name), &file.source),
+            None => diagnostic.to_string(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        lexer::Lexer,
+        parser::Parser,
+        schemas::{Ast, Span},
+        semantic::SemanticAnalyser,
+    };
+
+    fn parse_file(source_map: &SourceMap, file_id: u32) -> Result<Ast, CompilerError> {
+        let mut lexer = Lexer::new(&source_map.get_file(file_id).unwrap().source);
+        lexer.file_id(file_id);
+        lexer.tokenize()?;
+
+        let mut parser = Parser::new(lexer.get_tokens().to_vec());
+        parser.parse()?;
+        Ok(parser.into_tree())
+    }
+
+    #[test]
+    fn test_spans_across_files() {
+        let mut source_map = SourceMap::new();
+        let lib = source_map.add_file("lib.arith", "int a = 1;\nint b = a * 2;");
+        let main = source_map.add_file("main.arith", "print(b);\nprint(c);");
+
+        let mut ast = parse_file(&source_map, lib).unwrap();
+        ast.extend(parse_file(&source_map, main).unwrap());
+        assert_eq!(ast[1].span().file_id, Some(0));
+        assert_eq!(ast[2].span().file_id, Some(1));
+
+        let err = SemanticAnalyser::new(ast).check().unwrap_err();
+        assert_eq!(
+            source_map.render(&err),
+            "\
+error[E0005] (line 2, position 7): Cannot find identifier 'c'.
+ --> main.arith:2:7
+  |
+2 | print(c);
+  |       ^"
+        );
+    }
+
+    #[test]
+    fn test_render_syntax_error_in_file() {
+        let mut source_map = SourceMap::new();
+        source_map.add_file("lib.arith", "int a = 1;");
+        let broken = source_map.add_file("broken.arith", "int a = 1;\nint b = * 2;");
+
+        let err = parse_file(&source_map, broken).unwrap_err();
+        assert_eq!(
+            source_map.render(&err),
+            "\
+error[E0001] (line 2, position 9): Expected an operand, found operator '*'.
+ --> broken.arith:2:9
+  |
+2 | int b = * 2;
+  |         ^"
+        );
+
+        // Spans without a file id can't be resolved and fall back to the plain message.
+        let err = CompilerError::SyntaxError {
+            message: "Unexpected token.".to_string(),
+            span: Span { line: 1, col: 1, end_line: 1, end_col: 1, file_id: None },
+            fixit: None,
+        };
+        assert_eq!(source_map.render(&err), Diagnostic::from(&err).to_string());
+    }
+}
@@ -0,0 +1,216 @@
+//! Stable text format of a token stream, used to snapshot the lexer output of a corpus and detect
+//! lexer changes in golden tests. Every token is written on its own line as its span, its kind and
+//! the payload of the kind, separated by tabs, e.g. `1:9-1:10 Literal int 1` for the `1` in `int a = 1;`.
+//! A span is written as `line:col-end_line:end_col`, followed by `@file_id` if it has a file id. Tabs, newlines and backslashes in
+//! identifiers and literal values are escaped as `\t`, `\n`, `\r` and `\\`.
+
+use crate::schemas::*;
+
+const BIN_OPS: [BinOpKind; 21] = [
+    BinOpKind::Assign,
+    BinOpKind::Add,
+    BinOpKind::Sub,
+    BinOpKind::Mult,
+    BinOpKind::Div,
+    BinOpKind::Mod,
+    BinOpKind::Pow,
+    BinOpKind::Gt,
+    BinOpKind::Lt,
+    BinOpKind::Ge,
+    BinOpKind::Le,
+    BinOpKind::Eq,
+    BinOpKind::Ne,
+    BinOpKind::And,
+    BinOpKind::Or,
+    BinOpKind::Not,
+    BinOpKind::BitAnd,
+    BinOpKind::BitOr,
+    BinOpKind::Shl,
+    BinOpKind::Shr,
+    BinOpKind::BitNot,
+];
+
+pub fn tokens_to_string(tokens: &[Token]) -> String {
+    let mut output = String::new();
+    for token in tokens {
+        let span = &token.span;
+        output.push_str(&format!("{}:{}-{}:{}", span.line, span.col, span.end_line, span.end_col));
+        if let Some(file_id) = span.file_id {
+            output.push_str(&format!("@{}", file_id));
+        }
+        let fields = match &token.kind {
+            TokenKind::Declare(primitive) => vec!["Declare".to_string(), escape(primitive.name())],
+            TokenKind::Identifier(name) => vec!["Identifier".to_string(), escape(name)],
+            TokenKind::Literal(literal) => vec![
+                "Literal".to_string(),
+                escape(literal.primitive.name()),
+                escape(&literal.value),
+            ],
+            TokenKind::BinOp(op) => vec!["BinOp".to_string(), op.symbol().to_string()],
+            TokenKind::CompoundAssign(op) => vec!["CompoundAssign".to_string(), op.symbol().to_string()],
+            kind => vec![format!("{:?}", kind)],
+        };
+        for field in fields {
+            output.push('\t');
+            output.push_str(&field);
+        }
+        output.push('\n');
+    }
+    output
+}
+
+/// Reads a token stream written by `tokens_to_string`. Errors name the offending line of the input.
+pub fn tokens_from_string(input: &str) -> Result<Vec<Token>, String> {
+    input
+        .lines()
+        .enumerate()
+        .map(|(index, line)| parse_token(line).ok_or_else(|| format!("Invalid token on line {}: '{}'.", index + 1, line)))
+        .collect()
+}
+
+fn parse_token(line: &str) -> Option<Token> {
+    let fields: Vec<&str> = line.split('\t').collect();
+    let span = parse_span(fields.first()?)?;
+    let kind = match fields[1..] {
+        ["Declare", primitive] => TokenKind::Declare(parse_primitive(&unescape(primitive)?)),
+        ["Identifier", name] => TokenKind::Identifier(unescape(name)?),
+        ["Literal", primitive, value] => TokenKind::Literal(Literal {
+            value: unescape(value)?,
+            primitive: parse_primitive(&unescape(primitive)?),
+        }),
+        ["BinOp", symbol] => TokenKind::BinOp(parse_bin_op(symbol)?),
+        ["CompoundAssign", symbol] => TokenKind::CompoundAssign(parse_bin_op(symbol)?),
+        ["Mut"] => TokenKind::Mut,
+        ["LParen"] => TokenKind::LParen,
+        ["RParen"] => TokenKind::RParen,
+        ["LBrace"] => TokenKind::LBrace,
+        ["RBrace"] => TokenKind::RBrace,
+        ["Comma"] => TokenKind::Comma,
+        ["Print"] => TokenKind::Print,
+        ["EPrint"] => TokenKind::EPrint,
+        ["Swap"] => TokenKind::Swap,
+        ["TypeOf"] => TokenKind::TypeOf,
+        ["If"] => TokenKind::If,
+        ["Else"] => TokenKind::Else,
+        ["For"] => TokenKind::For,
+        ["Fn"] => TokenKind::Fn,
+        ["Return"] => TokenKind::Return,
+        ["Arrow"] => TokenKind::Arrow,
+        ["EOS"] => TokenKind::EOS,
+        ["EOF"] => TokenKind::EOF,
+        _ => return None,
+    };
+    Some(Token { kind, span })
+}
+
+fn parse_span(field: &str) -> Option<Span> {
+    let (position, file_id) = match field.split_once('@') {
+        Some((position, file_id)) => (position, Some(file_id.parse().ok()?)),
+        None => (field, None),
+    };
+    let (start, end) = position.split_once('-')?;
+    let (line, col) = parse_position(start)?;
+    let (end_line, end_col) = parse_position(end)?;
+    Some(Span {
+        line,
+        col,
+        end_line,
+        end_col,
+        file_id,
+    })
+}
+
+fn parse_position(field: &str) -> Option<(usize, usize)> {
+    let (line, col) = field.split_once(':')?;
+    Some((line.parse().ok()?, col.parse().ok()?))
+}
+
+fn parse_primitive(name: &str) -> Primitive {
+    Primitive::BUILTINS
+        .iter()
+        .find(|primitive| primitive.name() == name)
+        .cloned()
+        .unwrap_or_else(|| Primitive::Named(name.to_string()))
+}
+
+fn parse_bin_op(symbol: &str) -> Option<BinOpKind> {
+    BIN_OPS.into_iter().find(|op| op.symbol() == symbol)
+}
+
+fn escape(value: &str) -> String {
+    let mut escaped = String::new();
+    for c in value.chars() {
+        match c {
+            '\\' => escaped.push_str("\\\\"),
+            '\t' => escaped.push_str("\\t"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+fn unescape(value: &str) -> Option<String> {
+    let mut unescaped = String::new();
+    let mut chars = value.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            unescaped.push(c);
+            continue;
+        }
+        unescaped.push(match chars.next()? {
+            '\\' => '\\',
+            't' => '\t',
+            'n' => '\n',
+            'r' => '\r',
+            _ => return None,
+        });
+    }
+    Some(unescaped)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lexer::Lexer;
+
+    #[test]
+    fn test_tokens_to_string() {
+        let mut lexer = Lexer::new("int a = 1;\nprint(a += 2);");
+        lexer.tokenize().unwrap();
+        assert_eq!(
+            tokens_to_string(lexer.get_tokens()),
+            "1:1-1:4\tDeclare\tint\n1:5-1:6\tIdentifier\ta\n1:7-1:8\tBinOp\t=\n1:9-1:10\tLiteral\tint\t1\n\
+             1:10-1:11\tEOS\n2:1-2:6\tPrint\n2:6-2:7\tLParen\n2:7-2:8\tIdentifier\ta\n2:9-2:11\tCompoundAssign\t+\n\
+             2:12-2:13\tLiteral\tint\t2\n2:13-2:14\tRParen\n2:14-2:15\tEOS\n2:15-2:15\tEOF\n"
+        );
+    }
+
+    #[test]
+    fn test_token_stream_round_trip() {
+        let source = "mut float x = 1.5e3;\nstring s = \"a\\tb\\\\c\\n\";\nColor c = Red;\n\
+                      if (x >= 2 && !(x != 3)) {\n  swap(x, y);\n} else {\n  eprint(typeof(s));\n}\n\
+                      for (int i = 0; i < 10; i += 1) { x = x ^ 2 % 3; }";
+        let mut lexer = Lexer::new(source);
+        lexer.file_id(3);
+        lexer.tokenize().unwrap();
+        let tokens = lexer.get_tokens();
+
+        let serialized = tokens_to_string(tokens);
+        let deserialized = tokens_from_string(&serialized).unwrap();
+        assert_eq!(&deserialized, tokens);
+        assert_eq!(tokens_to_string(&deserialized), serialized);
+    }
+
+    #[test]
+    fn test_invalid_token_stream() {
+        assert_eq!(
+            tokens_from_string("1:1-1:2\tEOS\n1:x-1:2\tEOF\n"),
+            Err("Invalid token on line 2: '1:x-1:2\tEOF'.".to_string())
+        );
+        assert!(tokens_from_string("1:1\tEOS").is_err());
+        assert!(tokens_from_string("1:1-1:3\tBinOp\t=>").is_err());
+        assert!(tokens_from_string("1:1-1:3\tIdentifier\ta\\q").is_err());
+    }
+}
@@ -0,0 +1,167 @@
+use std::{
+    io::Write,
+    path::PathBuf,
+    process::{Command, Output, Stdio},
+};
+
+fn compiler() -> Command {
+    Command::new(env!("CARGO_BIN_EXE_arith-compiler"))
+}
+
+/// Writes the program to a file in the temp dir, named after the test to keep parallel tests apart.
+fn temp_file(name: &str, source: &str) -> PathBuf {
+    let path = std::env::temp_dir().join(format!("arith-compiler-{}-{}.txt", std::process::id(), name));
+    std::fs::write(&path, source).unwrap();
+    path
+}
+
+fn stdout(output: &Output) -> String {
+    String::from_utf8(output.stdout.clone()).unwrap()
+}
+
+fn stderr(output: &Output) -> String {
+    String::from_utf8(output.stderr.clone()).unwrap()
+}
+
+#[test]
+fn test_compile_file() {
+    let path = temp_file("compile", "int a = (1 * (2 + 3)) + 3;\nfloat b = -a / 5;\nprint(b + 3);\nprint(a);");
+    let output = compiler().arg(&path).output().unwrap();
+    std::fs::remove_file(&path).unwrap();
+
+    assert!(output.status.success());
+    assert_eq!(stdout(&output), "1.4\n8\n");
+}
+
+#[test]
+fn test_compile_error_exits_with_1() {
+    let path = temp_file("error", "int a = 1;\nbool b = a;");
+    let output = compiler().arg(&path).output().unwrap();
+    std::fs::remove_file(&path).unwrap();
+
+    assert_eq!(output.status.code(), Some(1));
+    assert!(stderr(&output).starts_with("error[E0002] (line 2, position 1): Expected 'Bool', found 'Int'.\n"));
+    assert!(stderr(&output).contains("2 | bool b = a;"));
+}
+
+#[test]
+fn test_warning_with_source_line() {
+    let path = temp_file("warning", "int a = 1;\na == 2;\nprint(a);");
+    let output = compiler().arg(&path).output().unwrap();
+    std::fs::remove_file(&path).unwrap();
+
+    assert!(output.status.success());
+    assert_eq!(stdout(&output), "1\n");
+    assert!(stderr(&output).starts_with("warning[discarded-comparison] (line 2, position 1): "));
+    assert!(stderr(&output).contains("2 | a == 2;"));
+}
+
+#[test]
+fn test_assignment_expressions() {
+    let path = temp_file(
+        "assign",
+        "mut int a = 0;\nmut int b = 0;\na = b = 3;\nprint(a + b);\nmut int j = 0;\nfor (mut int i = 0; i < 9; i = j = i + 4) {}\nprint(j);",
+    );
+    let output = compiler().arg(&path).output().unwrap();
+    std::fs::remove_file(&path).unwrap();
+
+    assert!(output.status.success());
+    assert_eq!(stdout(&output), "6\n12\n");
+}
+
+#[test]
+fn test_int_width() {
+    let path = temp_file("int-width", "int a = 2147483647;\nprint(a);\nprint(a + 1);");
+    let output = compiler().arg(&path).output().unwrap();
+    assert!(output.status.success());
+    assert_eq!(stdout(&output), "2147483647\n2147483648\n");
+
+    let output = compiler().arg("--int-width=32").arg(&path).output().unwrap();
+    assert_eq!(output.status.code(), Some(1));
+    assert_eq!(stdout(&output), "2147483647\n");
+    assert!(stderr(&output).contains("Integer overflow."));
+
+    let output = compiler().arg("--int-width=16").arg(&path).output().unwrap();
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(output.status.code(), Some(1));
+    assert_eq!(stderr(&output), "Invalid int width '16', expected 32 or 64.\n");
+}
+
+#[test]
+fn test_missing_file() {
+    let output = compiler().arg("does/not/exist.txt").output().unwrap();
+    assert_eq!(output.status.code(), Some(1));
+    assert!(stderr(&output).starts_with("IOError: Cannot read file 'does/not/exist.txt': "));
+}
+
+#[test]
+fn test_compile_stdin() {
+    let mut child = compiler().stdin(Stdio::piped()).stdout(Stdio::piped()).spawn().unwrap();
+    child.stdin.take().unwrap().write_all(b"print(2 * 21);").unwrap();
+    let output = child.wait_with_output().unwrap();
+
+    assert!(output.status.success());
+    assert_eq!(stdout(&output), "42\n");
+}
+
+#[test]
+fn test_long_operator_chain() {
+    let source = format!("int a = {};\nprint(a);", vec!["1"; 1500].join(" + "));
+    let mut child = compiler().stdin(Stdio::piped()).stderr(Stdio::piped()).spawn().unwrap();
+    child.stdin.take().unwrap().write_all(source.as_bytes()).unwrap();
+    let output = child.wait_with_output().unwrap();
+
+    assert_eq!(output.status.code(), Some(1));
+    assert!(stderr(&output).contains("nested 1500 levels deep"));
+}
+
+#[test]
+fn test_deep_recursion() {
+    let path = temp_file(
+        "recursion",
+        "fn sum(int n) -> int {\n  if (n == 0) { return 0; }\n  return n + sum(n - 1);\n}\nprint(sum(1000));",
+    );
+    let output = compiler().arg(&path).output().unwrap();
+    std::fs::remove_file(&path).unwrap();
+
+    assert!(output.status.success());
+    assert_eq!(stdout(&output), "500500\n");
+}
+
+#[test]
+fn test_repl() {
+    let mut child = compiler()
+        .arg("--repl")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+    child.stdin.take().unwrap().write_all(b"int a = 2;\nprint(a + b);\nprint(a * 21);\n").unwrap();
+    let output = child.wait_with_output().unwrap();
+
+    assert!(output.status.success());
+    assert_eq!(stdout(&output), "42\n");
+    assert!(stderr(&output).contains("error[E0005]"));
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn test_emit_ast_json() {
+    let path = temp_file("json", "print(1 + 2);");
+    let output = compiler().arg("--emit-ast-json").arg(&path).output().unwrap();
+    std::fs::remove_file(&path).unwrap();
+
+    assert!(output.status.success());
+    let json = stdout(&output);
+    assert!(json.starts_with("[\n  {\n    \"Print\": {"));
+    assert!(json.contains("\"op\": \"Add\""));
+}
+
+#[cfg(not(feature = "serde"))]
+#[test]
+fn test_emit_ast_json_requires_feature() {
+    let output = compiler().arg("--emit-ast-json").output().unwrap();
+    assert_eq!(output.status.code(), Some(1));
+    assert_eq!(stderr(&output), "The '--emit-ast-json' flag requires the 'serde' feature.\n");
+}
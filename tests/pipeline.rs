@@ -0,0 +1,47 @@
+use arith_compiler::{CompilerError, ExprKind, IntWidth, Interpreter, Primitive, Stmt, compile_str, compile_str_with_int_width};
+
+fn run(source: &str) -> Result<String, CompilerError> {
+    let ast = compile_str(source)?;
+    let (mut stdout, mut stderr) = (vec![], vec![]);
+    Interpreter::new(&mut stdout, &mut stderr).run(&ast)?;
+    Ok(String::from_utf8(stdout).unwrap())
+}
+
+#[test]
+fn test_compile_str() {
+    let ast = compile_str("int a = 1 + 2;\nfloat b = a / 2;\nprint(b);").unwrap();
+    assert_eq!(ast.len(), 3);
+    assert!(matches!(
+        &ast[0],
+        Stmt::Declare { dtype: Primitive::Int, name, expr, .. } if name == "a" && matches!(expr.node, ExprKind::BinOp { .. })
+    ));
+}
+
+#[test]
+fn test_compile_str_errors() {
+    assert!(matches!(compile_str("int a = 1 $ 2;"), Err(CompilerError::SyntaxError { .. })));
+    assert!(matches!(compile_str("int a = ;"), Err(CompilerError::SyntaxError { .. })));
+    assert!(matches!(compile_str("bool a = 1;"), Err(CompilerError::TypeDeclarationError { .. })));
+}
+
+#[test]
+fn test_deep_recursion_on_main_stack() {
+    let result = run("fn f(int n) -> int {\n  if (n == 0) { return 0; }\n  return f(n - 1) + 1;\n}\nprint(f(4900));");
+    assert!(matches!(result, Err(CompilerError::RuntimeError { message, .. }) if message == "Maximum call depth exceeded."));
+}
+
+#[test]
+fn test_compile_str_with_int_width() {
+    let source = "int a = 3000000000;";
+    compile_str(source).unwrap();
+    assert!(matches!(
+        compile_str_with_int_width(source, IntWidth::I32),
+        Err(CompilerError::LiteralOutOfRange { primitive: Primitive::Int, .. })
+    ));
+}
+
+#[test]
+fn test_run_program() {
+    let output = run("mut int sum = 0;\nfor (mut int i = 1; i <= 10; i += 1) {\n  sum += i;\n}\nprint(sum);\nprint(sum / 4);").unwrap();
+    assert_eq!(output, "55\n13.75\n");
+}